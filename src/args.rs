@@ -0,0 +1,298 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::config::ListenerBackend;
+use crate::stats::store::StorageBackend;
+use crate::ui::keyboard_layout::{KeyboardLayout, KeyboardSize};
+
+/// Command-line options
+#[derive(Debug, Clone)]
+pub struct Args {
+    /// Count OS auto-repeat events as key presses
+    pub count_repeats: bool,
+    
+    /// Run without a window, collecting stats until Ctrl-C
+    pub headless: bool,
+    
+    /// Record aggregate counts only, without key identities
+    pub privacy: bool,
+    
+    /// Local hour (0-23) at which the daily summary is produced
+    pub summary_hour: u32,
+    
+    /// Seconds between periodic saves, overriding the config file
+    pub save_interval: Option<u64>,
+    
+    /// Write a Markdown report of each finished day
+    pub daily_report: bool,
+    
+    /// Config file to use instead of the default `config.toml`
+    pub config: Option<PathBuf>,
+    
+    /// Local port serving Prometheus metrics at `/metrics`; off when unset
+    pub metrics_port: Option<u16>,
+    
+    /// Backend used to persist the statistics
+    pub storage: StorageBackend,
+    
+    /// Source of input events, overriding the config file
+    pub backend: Option<ListenerBackend>,
+    
+    /// Keyboard drawn by the heatmap, overriding the config file
+    pub keyboard_layout: Option<KeyboardLayout>,
+    pub keyboard_size: Option<KeyboardSize>,
+    
+    /// `import <FILE>`: merge another stats file and exit
+    pub import: Option<PathBuf>,
+    
+    /// `normalize`: rename the recorded keys to their aliases and exit
+    pub normalize: bool,
+    
+    /// `report`: write the Year in Review and exit
+    pub report: bool,
+    
+    /// Year of the report; the current year if not given
+    pub year: Option<i32>,
+    
+    /// File the report is written to; standard output if not given
+    pub output: Option<PathBuf>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            count_repeats: false,
+            headless: false,
+            privacy: false,
+            summary_hour: 23,
+            save_interval: None,
+            daily_report: false,
+            config: None,
+            metrics_port: None,
+            storage: StorageBackend::default(),
+            backend: None,
+            keyboard_layout: None,
+            keyboard_size: None,
+            import: None,
+            normalize: false,
+            report: false,
+            year: None,
+            output: None,
+        }
+    }
+}
+
+impl Args {
+    /// Parse options from the process arguments
+    pub fn parse() -> Self {
+        Self::parse_from(std::env::args().skip(1))
+    }
+    
+    /// Parse options from an argument list (without the program name)
+    pub fn parse_from<I: IntoIterator<Item = String>>(args: I) -> Self {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--count-repeats" => parsed.count_repeats = true,
+                "--headless" => parsed.headless = true,
+                "--privacy" => parsed.privacy = true,
+                "--daily-report" => parsed.daily_report = true,
+                "--summary-hour" => {
+                    match parse_value::<u32>(&arg, args.next()) {
+                        Some(hour) if hour < 24 => parsed.summary_hour = hour,
+                        Some(hour) => log::warn!("Ignoring {} {}: the hour must be 0-23", arg, hour),
+                        None => {}
+                    }
+                }
+                "--save-interval" => {
+                    match parse_value::<u64>(&arg, args.next()) {
+                        Some(0) => log::warn!("Ignoring {} 0: the interval must be at least 1 second", arg),
+                        Some(secs) => parsed.save_interval = Some(secs),
+                        None => {}
+                    }
+                }
+                "--config" => match args.next() {
+                    Some(path) => parsed.config = Some(PathBuf::from(path)),
+                    None => log::warn!("Ignoring {}: missing file", arg),
+                },
+                "--metrics-port" => {
+                    if let Some(port) = parse_value(&arg, args.next()) {
+                        parsed.metrics_port = Some(port);
+                    }
+                }
+                "--storage" => {
+                    if let Some(storage) = parse_value(&arg, args.next()) {
+                        parsed.storage = storage;
+                    }
+                }
+                "--backend" => {
+                    if let Some(backend) = parse_value(&arg, args.next()) {
+                        parsed.backend = Some(backend);
+                    }
+                }
+                "--keyboard-layout" => {
+                    if let Some(layout) = parse_value(&arg, args.next()) {
+                        parsed.keyboard_layout = Some(layout);
+                    }
+                }
+                "--keyboard-size" => {
+                    if let Some(size) = parse_value(&arg, args.next()) {
+                        parsed.keyboard_size = Some(size);
+                    }
+                }
+                // Read in `main` before logging starts
+                "--log-file" => {}
+                "import" => match args.next() {
+                    Some(path) => parsed.import = Some(PathBuf::from(path)),
+                    None => log::warn!("Ignoring import: missing file"),
+                },
+                "normalize" => parsed.normalize = true,
+                "report" => parsed.report = true,
+                "--year" => {
+                    if let Some(year) = parse_value(&arg, args.next()) {
+                        parsed.year = Some(year);
+                    }
+                }
+                "-o" | "--output" => match args.next() {
+                    Some(path) => parsed.output = Some(PathBuf::from(path)),
+                    None => log::warn!("Ignoring {}: missing file", arg),
+                },
+                "-h" | "--help" => {
+                    print_usage();
+                    std::process::exit(0);
+                }
+                other => log::warn!("Ignoring unknown argument: {}", other),
+            }
+        }
+        
+        parsed
+    }
+}
+
+/// Parse the value following a flag, warning when it is missing or invalid
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Option<T> {
+    let parsed = value.as_deref().and_then(|v| v.parse().ok());
+    if parsed.is_none() {
+        log::warn!("Ignoring {}: missing or invalid value", flag);
+    }
+    parsed
+}
+
+fn print_usage() {
+    println!("Usage: rust-finger [OPTIONS]");
+    println!("       rust-finger [OPTIONS] import <FILE>");
+    println!("       rust-finger [OPTIONS] normalize");
+    println!("       rust-finger [OPTIONS] report [--year <Y>] [-o <FILE>]");
+    println!();
+    println!("Commands:");
+    println!("  import <FILE>         Merge another stats.json into the local stats and exit");
+    println!("  normalize             Rename recorded keys by the [key_aliases] in config.toml and exit");
+    println!("  report                Write a Year in Review (Markdown, or HTML for .html files) and exit");
+    println!("    --year <Y>          Year to review [default: the current year]");
+    println!("    -o, --output <FILE> Where to write the report [default: standard output]");
+    println!();
+    println!("Options:");
+    println!("  --config <FILE>       Config file to use [default: config.toml in the config directory]");
+    println!("  --count-repeats       Count held-key auto-repeat as key presses [default: as in config.toml]");
+    println!("  --headless            Run without a window (listener and saving only)");
+    println!("  --privacy             Enable privacy mode (no key identities are stored)");
+    println!("  --summary-hour <H>    Hour of day (0-23) for the daily summary [default: 23]");
+    println!("  --save-interval <S>   Seconds between periodic saves [default: 60, or as in config.toml]");
+    println!("  --daily-report        Write a Markdown report of each finished day to reports/ in the data directory");
+    println!("  --metrics-port <P>    Serve Prometheus metrics on http://127.0.0.1:<P>/metrics");
+    println!("  --storage <BACKEND>   Storage backend: json or sqlite [default: json]");
+    println!("  --backend <BACKEND>   Input capture: rdev or evdev (Linux, /dev/input) [default: rdev]");
+    println!("  --keyboard-layout <L> Heatmap keyboard layout: ansi, iso or custom");
+    println!("  --keyboard-size <S>   Heatmap keyboard size: 60, tkl or full");
+    println!("  --log-file            Log to finger.log in the data directory instead of stderr");
+    println!("  -h, --help            Print this help");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse_from(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let args = parse(&[]);
+        assert!(!args.count_repeats);
+        assert!(!args.privacy);
+        assert!(!args.headless);
+        assert_eq!(args.summary_hour, 23);
+        assert_eq!(args.storage, StorageBackend::Json);
+        assert_eq!(args.save_interval, None);
+        assert!(args.import.is_none());
+        assert!(args.backend.is_none());
+        assert!(!args.daily_report);
+        assert!(args.metrics_port.is_none());
+        assert!(args.config.is_none());
+    }
+
+    #[test]
+    fn parses_flags_and_values() {
+        let args = parse(&["--privacy", "--summary-hour", "7", "--count-repeats", "--storage", "sqlite", "--headless"]);
+        assert!(args.privacy);
+        assert!(args.headless);
+        assert_eq!(args.storage, StorageBackend::Sqlite);
+        assert!(args.count_repeats);
+        assert_eq!(parse(&["--backend", "evdev"]).backend, Some(ListenerBackend::Evdev));
+        assert!(parse(&["--daily-report"]).daily_report);
+        assert_eq!(parse(&["--metrics-port", "9184"]).metrics_port, Some(9184));
+        assert!(parse(&["--metrics-port", "70000"]).metrics_port.is_none());
+        assert_eq!(args.summary_hour, 7);
+    }
+
+    #[test]
+    fn parses_save_interval() {
+        assert_eq!(parse(&["--save-interval", "300"]).save_interval, Some(300));
+    }
+
+    #[test]
+    fn parses_config_path() {
+        assert_eq!(parse(&["--config", "/etc/finger.toml"]).config, Some(PathBuf::from("/etc/finger.toml")));
+        assert!(parse(&["--config"]).config.is_none());
+    }
+
+    #[test]
+    fn parses_import_command() {
+        let args = parse(&["--storage", "sqlite", "import", "laptop.json"]);
+        assert_eq!(args.import, Some(PathBuf::from("laptop.json")));
+        assert_eq!(args.storage, StorageBackend::Sqlite);
+        assert!(!args.normalize);
+        assert!(parse(&["normalize"]).normalize);
+    }
+
+    #[test]
+    fn parses_report_command() {
+        let args = parse(&["report", "--year", "2024", "-o", "review.md"]);
+        assert!(args.report);
+        assert_eq!(args.year, Some(2024));
+        assert_eq!(args.output, Some(PathBuf::from("review.md")));
+        assert_eq!(parse(&["report", "--output", "review.html"]).output, Some(PathBuf::from("review.html")));
+        let defaults = parse(&["report", "--year", "last"]);
+        assert_eq!(defaults.year, None);
+        assert_eq!(defaults.output, None);
+    }
+
+    #[test]
+    fn parses_keyboard() {
+        let args = parse(&["--keyboard-layout", "iso", "--keyboard-size", "full"]);
+        assert_eq!(args.keyboard_layout, Some(KeyboardLayout::Iso));
+        assert_eq!(args.keyboard_size, Some(KeyboardSize::Full));
+        assert!(parse(&["--keyboard-size", "75"]).keyboard_size.is_none());
+    }
+
+    #[test]
+    fn invalid_values_keep_defaults() {
+        assert_eq!(parse(&["--summary-hour", "24"]).summary_hour, 23);
+        assert_eq!(parse(&["--summary-hour", "late"]).summary_hour, 23);
+        assert_eq!(parse(&["--summary-hour"]).summary_hour, 23);
+        assert_eq!(parse(&["--save-interval", "0"]).save_interval, None);
+    }
+}
@@ -0,0 +1,548 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::args::Args;
+
+use crate::stats::key_aliases::KeyAliases;
+use crate::stats::multi_click::DEFAULT_MULTI_CLICK_WINDOW;
+use crate::stats::autosave::DEFAULT_SAVE_INTERVAL;
+use crate::stats::store::DEFAULT_STARTUP_BACKUPS;
+use crate::stats::{
+    ScrollUnit, DEFAULT_DEDUP_WINDOW, DEFAULT_MAX_MOVEMENT, DEFAULT_RECENT_HALF_LIFE_DAYS, DEFAULT_STREAK_MIN_KEYS, DEFAULT_WORD_LENGTH, DEFAULT_WPM_SMOOTHING,
+};
+use crate::ui::dashboard::DEFAULT_REFRESH_INTERVAL;
+use crate::ui::heat_scale::HeatColorScheme;
+use crate::ui::key_labels::KeyLabels;
+use crate::ui::keyboard_layout::{KeyScale, KeyboardLayout, KeyboardSize};
+use crate::ui::theme::{AccentColors, ThemeMode};
+
+/// User settings read from `config.toml`, and written back by the settings view
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Directory for the stats, reports and logs; `FINGER_DATA_DIR` takes
+    /// precedence. When unset, `rust-finger` in the local data directory.
+    pub data_dir: Option<PathBuf>,
+    
+    /// Copies of the stats file kept in `backups/` in the data directory,
+    /// one taken at each start; 0 disables them
+    pub startup_backups: usize,
+    
+    /// Count OS auto-repeat events of a held key as key presses
+    pub count_repeats: bool,
+    
+    /// Applications in which no input is recorded (case-insensitive substring match)
+    pub excluded_apps: Vec<String>,
+    
+    /// What to record for keys without a name, such as dead keys on international layouts
+    pub unmapped_keys: UnmappedKeys,
+    
+    /// How global input is captured
+    pub backend: ListenerBackend,
+    
+    /// Seconds between periodic saves
+    pub save_interval_secs: u64,
+    
+    /// Custom key names by recorded name, e.g. `"Key(191)" = "Macro1"` for a
+    /// macro key, or `"KP+" = "+"` to count both plus keys as one
+    pub key_aliases: KeyAliases,
+    
+    /// Names keys are shown under without changing how they are recorded,
+    /// e.g. `"`" = "Backtick"`
+    pub key_labels: KeyLabels,
+    
+    /// Unit for the scroll distance shown on the dashboard
+    pub scroll_unit: ScrollUnit,
+    
+    /// Weight (0-1) of the newest value in the displayed WPM; lower is steadier
+    pub wpm_smoothing: f64,
+    
+    /// Average word length in characters used to compute the WPM
+    pub word_length: f64,
+    
+    /// Do not record input while a fullscreen window (game, video) has focus
+    pub pause_in_fullscreen: bool,
+    
+    /// Identical key presses or clicks closer together than this many
+    /// milliseconds count once; 0 disables deduplication
+    #[serde(alias = "dedup_ms")]
+    pub dedup_window_ms: u64,
+    
+    /// Longest gap in milliseconds between presses of the same button that
+    /// still form a double or triple click
+    pub multi_click_ms: u64,
+    
+    /// Cursor steps longer than this many pixels, such as jumps between
+    /// screens or warps, are not counted as travel; 0 counts every step
+    pub max_movement_px: f64,
+    
+    /// Append every key press and click with its time to `events.jsonl` in
+    /// the data directory, and to the events table of the SQLite store, for
+    /// custom analysis
+    pub record_events: bool,
+    
+    /// Days of daily stats, and of events in the SQLite store, to keep;
+    /// older ones are dropped when saving, without changing the all-time
+    /// counts or the streaks of the `streak_min_keys` in use. At least 31
+    /// days are kept; `None` keeps every day.
+    pub retention_days: Option<u32>,
+    
+    /// Keyboard drawn by the heatmap; when unset, the last choice made in
+    /// the dashboard is kept
+    #[serde(alias = "layout")]
+    pub keyboard_layout: Option<KeyboardLayout>,
+    pub keyboard_size: Option<KeyboardSize>,
+    
+    /// Size of the heatmap keys, a factor of the default size or fitted to the window
+    pub key_scale: KeyScale,
+    
+    /// Colors of the heatmaps and the hourly chart at startup; can be
+    /// switched above the keyboard heatmap
+    pub heatmap_palette: HeatColorScheme,
+    
+    /// Days after which a key press counts half as much in the "Recent" heatmap
+    pub recent_half_life_days: f64,
+    
+    /// Key presses a day needs to count towards the streak
+    pub streak_min_keys: u64,
+    
+    /// Keep the window above all others while in the compact view
+    pub compact_on_top: bool,
+    
+    /// Milliseconds between dashboard updates while input arrives; the
+    /// dashboard slows down to once a second when input stops, and to every
+    /// two seconds while the window is in the background
+    pub refresh_ms: u64,
+    
+    /// Light or dark dashboard; when unset, the last choice made with the
+    /// toggle in the menu bar is kept
+    pub theme: Option<ThemeMode>,
+    
+    /// Colors replacing the accents of both themes, e.g. `blue = "#2ac3de"`
+    pub accent_colors: AccentColors,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            startup_backups: DEFAULT_STARTUP_BACKUPS,
+            count_repeats: false,
+            excluded_apps: Vec::new(),
+            unmapped_keys: UnmappedKeys::default(),
+            backend: ListenerBackend::default(),
+            save_interval_secs: DEFAULT_SAVE_INTERVAL.as_secs(),
+            key_aliases: KeyAliases::default(),
+            key_labels: KeyLabels::default(),
+            scroll_unit: ScrollUnit::default(),
+            wpm_smoothing: DEFAULT_WPM_SMOOTHING,
+            word_length: DEFAULT_WORD_LENGTH,
+            pause_in_fullscreen: false,
+            dedup_window_ms: DEFAULT_DEDUP_WINDOW.as_millis() as u64,
+            multi_click_ms: DEFAULT_MULTI_CLICK_WINDOW.as_millis() as u64,
+            max_movement_px: DEFAULT_MAX_MOVEMENT,
+            record_events: false,
+            retention_days: None,
+            keyboard_layout: None,
+            keyboard_size: None,
+            key_scale: KeyScale::default(),
+            heatmap_palette: HeatColorScheme::default(),
+            recent_half_life_days: DEFAULT_RECENT_HALF_LIFE_DAYS,
+            streak_min_keys: DEFAULT_STREAK_MIN_KEYS,
+            compact_on_top: true,
+            refresh_ms: DEFAULT_REFRESH_INTERVAL.as_millis() as u64,
+            theme: None,
+            accent_colors: AccentColors::default(),
+        }
+    }
+}
+
+/// Policy for key codes the listener has no name for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnmappedKeys {
+    /// Record each code separately as `Key(n)`
+    #[default]
+    Keep,
+    /// Count all of them as a single `Other` key
+    Other,
+    /// Do not record them at all
+    Drop,
+}
+
+impl UnmappedKeys {
+    /// Key name to record for an unmapped key, or `None` to drop it
+    pub fn apply(self, name: String) -> Option<String> {
+        match self {
+            UnmappedKeys::Keep => Some(name),
+            UnmappedKeys::Other => Some("Other".to_string()),
+            UnmappedKeys::Drop => None,
+        }
+    }
+}
+
+/// Source of global input events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListenerBackend {
+    /// rdev: X11 on Linux, native hooks on Windows and macOS
+    #[default]
+    Rdev,
+    /// Read the kernel's `/dev/input` event devices directly, which also
+    /// works under Wayland and without a display. Linux only, needs the
+    /// `input` group and a build with the `evdev-backend` feature.
+    Evdev,
+}
+
+impl FromStr for ListenerBackend {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rdev" => Ok(ListenerBackend::Rdev),
+            "evdev" => Ok(ListenerBackend::Evdev),
+            other => Err(format!("unknown listener backend: {}", other)),
+        }
+    }
+}
+
+/// Config file given with `--config`, set once at startup
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+impl Config {
+    /// Location of the config file: the one given with `--config`, otherwise
+    /// e.g. `~/.config/rust-finger/config.toml`
+    pub fn path() -> PathBuf {
+        PATH_OVERRIDE.get().cloned().unwrap_or_else(|| {
+            dirs::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("rust-finger")
+                .join("config.toml")
+        })
+    }
+    
+    /// Load the config file, with defaults if it is missing. `None` if it
+    /// cannot be read or parsed: the defaults apply then, but the file must
+    /// not be saved over. With `--config`, that file is loaded and later
+    /// saved instead.
+    pub fn load(args: &Args) -> Option<Self> {
+        if let Some(path) = &args.config {
+            let _ = PATH_OVERRIDE.set(path.clone());
+        }
+        Self::load_from(&Self::path())
+    }
+    
+    fn load_from(path: &Path) -> Option<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Some(Self::default()),
+            Err(e) => {
+                log::warn!("Cannot read config {}: {}, using defaults", path.display(), e);
+                return None;
+            }
+        };
+        
+        match toml::from_str(&content) {
+            Ok(config) => {
+                log::info!("Loaded config from {}", path.display());
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("Ignoring invalid config {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+    
+    /// Settings in effect: those given on the command line, then those of
+    /// the config file `file`, then the defaults. The result is never saved;
+    /// the settings view saves its changes on top of `file`.
+    pub fn effective(file: Option<&Config>, args: &Args) -> Config {
+        let mut config = file.cloned().unwrap_or_default();
+        if let Some(backend) = args.backend {
+            config.backend = backend;
+        }
+        if let Some(secs) = args.save_interval {
+            config.save_interval_secs = secs;
+        }
+        if let Some(layout) = args.keyboard_layout {
+            config.keyboard_layout = Some(layout);
+        }
+        if let Some(size) = args.keyboard_size {
+            config.keyboard_size = Some(size);
+        }
+        // A flag can only turn it on
+        config.count_repeats |= args.count_repeats;
+        config
+    }
+    
+    /// These settings with those that differ between `before` and `after`
+    /// taken from `after`, so that a change made on top of the command-line
+    /// flags can be saved without the flags
+    pub fn with_changes(&self, before: &Config, after: &Config) -> Result<Config, Box<dyn std::error::Error>> {
+        let before = toml::Table::try_from(before)?;
+        let after = toml::Table::try_from(after)?;
+        let mut merged = toml::Table::try_from(self)?;
+        for key in before.keys().chain(after.keys()) {
+            if before.get(key) == after.get(key) {
+                continue;
+            }
+            // Unset options are left out of the table
+            match after.get(key) {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+        Ok(merged.try_into()?)
+    }
+    
+    /// Write the settings to the config file. Comments in the file are not kept.
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_to(&Self::path())
+    }
+    
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        log::info!("Saved config to {}", path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::theme::HexColor;
+
+    #[test]
+    fn parses_excluded_apps() {
+        let config: Config = toml::from_str(r#"excluded_apps = ["keepassxc", "1password"]"#).unwrap();
+        assert_eq!(config.excluded_apps, vec!["keepassxc", "1password"]);
+    }
+
+    #[test]
+    fn missing_keys_use_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+        assert!(config.excluded_apps.is_empty());
+        assert_eq!(config.key_aliases.apply("Key(191)"), "Key(191)");
+        assert_eq!(config.unmapped_keys, UnmappedKeys::Keep);
+        assert_eq!(config.backend, ListenerBackend::Rdev);
+        assert_eq!(config.scroll_unit, ScrollUnit::Screens);
+        assert_eq!(config.wpm_smoothing, DEFAULT_WPM_SMOOTHING);
+        assert_eq!(config.word_length, DEFAULT_WORD_LENGTH);
+        assert!(!config.pause_in_fullscreen);
+        assert_eq!(config.dedup_window_ms, 50);
+        assert_eq!(config.multi_click_ms, 400);
+        assert_eq!(config.max_movement_px, 3000.0);
+        assert!(!config.record_events);
+        assert!(config.retention_days.is_none());
+        assert!(config.keyboard_layout.is_none() && config.keyboard_size.is_none());
+        assert!(config.compact_on_top);
+        assert_eq!(config.refresh_ms, 250);
+        assert_eq!(config.save_interval_secs, 60);
+        assert_eq!(config.key_scale, KeyScale::Factor(1.0));
+        assert_eq!(config.heatmap_palette, HeatColorScheme::Classic);
+        assert_eq!(config.recent_half_life_days, 14.0);
+        assert_eq!(config.streak_min_keys, 1);
+        assert!(config.theme.is_none());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let config = Config {
+            excluded_apps: vec!["keepassxc".to_string()],
+            unmapped_keys: UnmappedKeys::Drop,
+            backend: ListenerBackend::Evdev,
+            save_interval_secs: 300,
+            key_aliases: KeyAliases::new([("Key(191)".to_string(), "Macro1".to_string())].into()).unwrap(),
+            retention_days: Some(730),
+            keyboard_size: Some(KeyboardSize::Full),
+            key_scale: KeyScale::Auto,
+            refresh_ms: 250,
+            theme: Some(ThemeMode::Light),
+            ..Config::default()
+        };
+        let written = toml::to_string(&config).unwrap();
+        assert_eq!(toml::from_str::<Config>(&written).unwrap(), config);
+
+        let config = Config { key_scale: KeyScale::Factor(0.75), ..Config::default() };
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&config).unwrap()).unwrap(), config);
+    }
+
+    #[test]
+    fn fields_missing_from_an_older_file_keep_defaults() {
+        let config: Config = toml::from_str("save_interval_secs = 120\n[key_aliases]\n\"KP+\" = \"+\"").unwrap();
+        assert_eq!(config.save_interval_secs, 120);
+        assert_eq!(config.key_aliases.apply("KP+"), "+");
+        assert_eq!(Config { save_interval_secs: 60, key_aliases: KeyAliases::default(), ..config }, Config::default());
+    }
+
+    #[test]
+    fn flags_override_the_file_and_the_file_overrides_defaults() {
+        let args = Args::parse_from(["--save-interval", "30", "--keyboard-size", "full"].map(String::from));
+        let file: Config = toml::from_str("save_interval_secs = 120
+dedup_window_ms = 0
+keyboard_size = \"60\"").unwrap();
+        let config = Config::effective(Some(&file), &args);
+        assert_eq!(config.save_interval_secs, 30);
+        assert_eq!(config.keyboard_size, Some(KeyboardSize::Full));
+        // Not on the command line: the file's value
+        assert_eq!(config.dedup_window_ms, 0);
+        // In neither: the default
+        assert_eq!(config.multi_click_ms, 400);
+        assert_eq!(config.backend, ListenerBackend::Rdev);
+        assert!(!config.count_repeats);
+        // The file itself is left as it was
+        assert_eq!(file.save_interval_secs, 120);
+        // An unreadable file gives the defaults
+        assert_eq!(Config::effective(None, &args).dedup_window_ms, Config::default().dedup_window_ms);
+
+        let file: Config = toml::from_str("count_repeats = true").unwrap();
+        assert!(Config::effective(Some(&file), &Args::default()).count_repeats);
+        let count_repeats = Args::parse_from(["--count-repeats".to_string()]);
+        assert!(Config::effective(Some(&Config::default()), &count_repeats).count_repeats);
+    }
+
+    #[test]
+    fn accepts_short_names() {
+        let config: Config = toml::from_str("dedup_ms = 20
+layout = \"iso\"
+data_dir = \"/srv/finger\"").unwrap();
+        assert_eq!(config.dedup_window_ms, 20);
+        assert_eq!(config.keyboard_layout, Some(KeyboardLayout::Iso));
+        assert_eq!(config.data_dir, Some(PathBuf::from("/srv/finger")));
+    }
+
+    #[test]
+    fn missing_or_malformed_file_gives_defaults() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(Config::load_from(&dir.join("missing.toml")), Some(Config::default()));
+
+        let path = dir.join("config.toml");
+        fs::write(&path, "save_interval_secs = \"often\"").unwrap();
+        assert_eq!(Config::load_from(&path), None);
+        fs::write(&path, "save_interval_secs = 90").unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().save_interval_secs, 90);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saving_a_change_never_writes_flags() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-config-save-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "dedup_window_ms = 50\nkeyboard_size = \"60\"").unwrap();
+        let file = Config::load_from(&path).unwrap();
+        let args = Args::parse_from(["--save-interval", "5", "--backend", "evdev", "--keyboard-size", "full"].map(String::from));
+        let effective = Config::effective(Some(&file), &args);
+
+        // The settings panel changes one setting of the effective config
+        let mut changed = effective.clone();
+        changed.refresh_ms = 500;
+        file.with_changes(&effective, &changed).unwrap().save_to(&path).unwrap();
+
+        let saved = Config::load_from(&path).unwrap();
+        assert_eq!(saved.refresh_ms, 500);
+        assert_eq!(saved.dedup_window_ms, 50);
+        assert_eq!(saved.keyboard_size, Some(KeyboardSize::Compact));
+        assert_eq!(saved.save_interval_secs, Config::default().save_interval_secs);
+        assert_eq!(saved.backend, ListenerBackend::Rdev);
+
+        // A setting changed in the panel is saved even if a flag set it
+        let mut changed = effective.clone();
+        changed.save_interval_secs = 15;
+        let saved = saved.with_changes(&effective, &changed).unwrap();
+        assert_eq!(saved.save_interval_secs, 15);
+        assert_eq!(saved.backend, ListenerBackend::Rdev);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parses_keyboard() {
+        let config: Config = toml::from_str("keyboard_layout = \"iso\"\nkeyboard_size = \"60\"").unwrap();
+        assert_eq!(config.keyboard_layout, Some(KeyboardLayout::Iso));
+        assert_eq!(config.keyboard_size, Some(KeyboardSize::Compact));
+    }
+
+    #[test]
+    fn parses_theme() {
+        let config: Config = toml::from_str("theme = \"light\"").unwrap();
+        assert_eq!(config.theme, Some(ThemeMode::Light));
+        assert!(toml::from_str::<Config>("theme = \"sepia\"").is_err());
+    }
+
+    #[test]
+    fn parses_accent_colors() {
+        let config: Config = toml::from_str("[accent_colors]\nblue = \"#2ac3de\"").unwrap();
+        assert_eq!(config.accent_colors.blue, Some(HexColor(0x2ac3de)));
+        assert!(config.accent_colors.red.is_none());
+        assert!(toml::from_str::<Config>("[accent_colors]\nblue = \"teal\"").is_err());
+        assert!(toml::from_str::<Config>("[accent_colors]\nmagenta = \"#ff00ff\"").is_err());
+
+        let config = Config { accent_colors: AccentColors { red: Some(HexColor(0xff0000)), ..config.accent_colors }, ..Config::default() };
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&config).unwrap()).unwrap(), config);
+    }
+
+    #[test]
+    fn parses_key_scale() {
+        let config: Config = toml::from_str(r#"key_scale = "auto""#).unwrap();
+        assert_eq!(config.key_scale, KeyScale::Auto);
+        let config: Config = toml::from_str("key_scale = 0.8").unwrap();
+        assert_eq!(config.key_scale, KeyScale::Factor(0.8));
+        assert!(toml::from_str::<Config>("key_scale = 3.0").is_err());
+        assert!(toml::from_str::<Config>(r#"key_scale = "huge""#).is_err());
+    }
+
+    #[test]
+    fn parses_heatmap_palette() {
+        let config: Config = toml::from_str(r#"heatmap_palette = "viridis""#).unwrap();
+        assert_eq!(config.heatmap_palette, HeatColorScheme::Viridis);
+        assert_eq!(toml::from_str::<Config>(r#"heatmap_palette = "gray""#).unwrap().heatmap_palette, HeatColorScheme::Grayscale);
+        assert!(toml::from_str::<Config>(r#"heatmap_palette = "rainbow""#).is_err());
+    }
+
+    #[test]
+    fn parses_scroll_unit() {
+        let config: Config = toml::from_str(r#"scroll_unit = "notches""#).unwrap();
+        assert_eq!(config.scroll_unit, ScrollUnit::Notches);
+    }
+
+    #[test]
+    fn unmapped_key_policies() {
+        let config: Config = toml::from_str(r#"unmapped_keys = "other""#).unwrap();
+        assert_eq!(config.unmapped_keys, UnmappedKeys::Other);
+        assert_eq!(UnmappedKeys::Keep.apply("Key(50)".to_string()), Some("Key(50)".to_string()));
+        assert_eq!(UnmappedKeys::Other.apply("Key(50)".to_string()), Some("Other".to_string()));
+        assert_eq!(UnmappedKeys::Drop.apply("Key(50)".to_string()), None);
+    }
+
+    #[test]
+    fn parses_backend() {
+        let config: Config = toml::from_str(r#"backend = "evdev""#).unwrap();
+        assert_eq!(config.backend, ListenerBackend::Evdev);
+        assert_eq!("RDEV".parse::<ListenerBackend>(), Ok(ListenerBackend::Rdev));
+        assert!("uinput".parse::<ListenerBackend>().is_err());
+    }
+
+    #[test]
+    fn parses_key_labels() {
+        let config: Config = toml::from_str("[key_labels]\n\"`\" = \"Backtick\"").unwrap();
+        assert_eq!(config.key_labels.display("`"), "Backtick");
+        assert_eq!(config.key_aliases.apply("`"), "`");
+        assert_eq!(toml::from_str::<Config>(&toml::to_string(&config).unwrap()).unwrap(), config);
+    }
+
+    #[test]
+    fn parses_key_aliases() {
+        let config: Config = toml::from_str("[key_aliases]\n\"Key(191)\" = \"Macro1\"").unwrap();
+        assert_eq!(config.key_aliases.apply("Key(191)"), "Macro1");
+        // Cycles make the whole config invalid
+        assert!(toml::from_str::<Config>("[key_aliases]\nA = \"B\"\nB = \"A\"").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+/// Full count with thousands separators, e.g. `1,234,567`
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Count abbreviated to fit a small space, e.g. `950`, `1.2k`, `45k`, `1.0M`
+pub fn format_compact(count: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")];
+    
+    for (size, suffix) in UNITS {
+        if count >= size {
+            // Truncated rather than rounded, so 999,999 never shows as "1000k";
+            // one decimal while it still fits, as in "1.2k"
+            let tenths = count / (size / 10);
+            return if tenths < 100 {
+                format!("{}.{}{}", tenths / 10, tenths % 10, suffix)
+            } else {
+                format!("{}{}", count / size, suffix)
+            };
+        }
+    }
+    count.to_string()
+}
+
+/// Distance in meters, switching to kilometers from 1 km, e.g. `34 m`, `1.25 km`
+pub fn format_distance(meters: f64) -> String {
+    if meters >= 1000.0 {
+        format!("{:.2} km", meters / 1000.0)
+    } else {
+        format!("{:.0} m", meters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_get_thousands_separators() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1000), "1,000");
+        assert_eq!(format_count(123_456), "123,456");
+        assert_eq!(format_count(1_234_567), "1,234,567");
+        assert_eq!(format_count(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn compact_counts_keep_one_decimal_when_small() {
+        assert_eq!(format_compact(999), "999");
+        assert_eq!(format_compact(1000), "1.0k");
+        assert_eq!(format_compact(1234), "1.2k");
+        assert_eq!(format_compact(9_960), "9.9k");
+        assert_eq!(format_compact(45_678), "45k");
+        assert_eq!(format_compact(999_999), "999k");
+        assert_eq!(format_compact(1_000_000), "1.0M");
+        assert_eq!(format_compact(2_500_000_000), "2.5B");
+    }
+
+    #[test]
+    fn distances_switch_to_kilometers() {
+        assert_eq!(format_distance(0.0), "0 m");
+        assert_eq!(format_distance(34.4), "34 m");
+        assert_eq!(format_distance(999.4), "999 m");
+        assert_eq!(format_distance(1000.0), "1.00 km");
+        assert_eq!(format_distance(12_345.0), "12.35 km");
+    }
+}
@@ -1,8 +1,98 @@
-use rdev::{listen, Button, Event, EventType, Key};
-use std::sync::mpsc::{self, Sender};
+use rdev::{display_size, listen, Button, Event, EventType, Key, ListenError};
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
 use std::thread;
 
-use crate::stats::StatsManager;
+use crate::config::{ListenerBackend, UnmappedKeys};
+use crate::platform;
+use crate::stats::mouse_grid;
+use crate::stats::{StatsManager, DEFAULT_DEVICE, DRAG_THRESHOLD};
+
+#[cfg(all(target_os = "linux", feature = "evdev-backend"))]
+mod evdev;
+
+/// Wheel delta units per notch: rdev reports notches on X11 and Windows,
+/// but pixel deltas on macOS
+#[cfg(target_os = "macos")]
+const SCROLL_UNITS_PER_NOTCH: i64 = 10;
+#[cfg(not(target_os = "macos"))]
+const SCROLL_UNITS_PER_NOTCH: i64 = 1;
+
+/// Maximum number of events buffered between the rdev callback and the consumer
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+/// What usually keeps rdev from capturing input on this platform, and how to fix it
+#[cfg(target_os = "macos")]
+const PERMISSION_GUIDANCE: &str = "Open System Settings → Privacy & Security → Accessibility and allow Finger Monitor \
+    (or the terminal it was started from), then press Retry.";
+#[cfg(target_os = "linux")]
+const PERMISSION_GUIDANCE: &str = "Input is read through the X server, so start the app in an X11 session, or under \
+    Wayland through XWayland with DISPLAY set. Starting with --backend evdev reads /dev/input directly instead.";
+#[cfg(target_os = "windows")]
+const PERMISSION_GUIDANCE: &str = "Input hooks only work inside your desktop session, not from a service or \
+    some remote sessions. Start the app from your desktop, then press Retry.";
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+const PERMISSION_GUIDANCE: &str = "Input capture is not supported on this platform.";
+
+/// What usually keeps the evdev backend from reading input, and how to fix it
+const EVDEV_GUIDANCE: &str = "Reading /dev/input needs your user in the input group \
+    (sudo usermod -aG input $USER); log in again, then press Retry.";
+
+/// What usually keeps input capture with `backend` from starting, and how to fix it
+pub fn permission_guidance(backend: ListenerBackend) -> &'static str {
+    match available_backend(backend) {
+        ListenerBackend::Rdev => PERMISSION_GUIDANCE,
+        ListenerBackend::Evdev => EVDEV_GUIDANCE,
+    }
+}
+
+/// Backend started for `backend`: evdev needs a Linux build with the
+/// `evdev-backend` feature, rdev is used otherwise
+fn available_backend(backend: ListenerBackend) -> ListenerBackend {
+    match backend {
+        ListenerBackend::Evdev if !cfg!(all(target_os = "linux", feature = "evdev-backend")) => ListenerBackend::Rdev,
+        backend => backend,
+    }
+}
+
+/// Listener error set when the pre-flight check finds no Accessibility access
+pub const ACCESSIBILITY_REQUIRED: &str = "Accessibility permission required. Allow Finger Monitor under \
+    System Settings → Privacy & Security → Accessibility, then press Retry";
+
+/// Listener warning under Wayland, where rdev only sees input sent to X11
+/// (XWayland) applications
+pub const WAYLAND_WARNING: &str = "Wayland session detected: only input sent to X11 (XWayland) applications is \
+    captured, so the counts may be incomplete. Log in to an X11 session, or start with \
+    --backend evdev, which reads /dev/input directly (needs the input group).";
+
+/// Whether the desktop session runs Wayland, by `WAYLAND_DISPLAY` and `XDG_SESSION_TYPE`
+pub fn is_wayland_session() -> bool {
+    cfg!(target_os = "linux") && wayland_session_from(std::env::var("WAYLAND_DISPLAY").ok(), std::env::var("XDG_SESSION_TYPE").ok())
+}
+
+fn wayland_session_from(wayland_display: Option<String>, session_type: Option<String>) -> bool {
+    wayland_display.is_some_and(|display| !display.is_empty())
+        || session_type.is_some_and(|session| session.eq_ignore_ascii_case("wayland"))
+}
+
+/// Whether the OS lets this process capture global input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionStatus {
+    Granted,
+    /// Accessibility access has not been granted (macOS)
+    Denied,
+}
+
+/// Check up front whether input capture is allowed, so a missing permission
+/// is reported clearly rather than as an rdev error. Always `Granted` on
+/// platforms without such a permission.
+pub fn check_permissions() -> PermissionStatus {
+    match platform::accessibility_trusted() {
+        Some(false) => PermissionStatus::Denied,
+        _ => PermissionStatus::Granted,
+    }
+}
 
 /// Input event types for communication
 #[derive(Debug, Clone)]
@@ -10,76 +100,305 @@ pub enum InputEvent {
     KeyPress(String),
     KeyRelease(String),
     MouseClick(String),
+    MouseRelease(String),
     MouseMove { x: f64, y: f64 },
     Scroll { delta_x: i64, delta_y: i64 },
 }
 
+impl InputEvent {
+    /// Cheap conversion from a raw rdev event
+    fn from_rdev(event: &Event, unmapped: UnmappedKeys, stats: &StatsManager) -> Option<Self> {
+        match event.event_type {
+            EventType::KeyPress(key) => key_name(&key, unmapped, stats).map(InputEvent::KeyPress),
+            EventType::KeyRelease(key) => key_name(&key, unmapped, stats).map(InputEvent::KeyRelease),
+            EventType::ButtonPress(button) => Some(InputEvent::MouseClick(button_to_string(&button))),
+            EventType::ButtonRelease(button) => Some(InputEvent::MouseRelease(button_to_string(&button))),
+            EventType::MouseMove { x, y } => Some(InputEvent::MouseMove { x, y }),
+            EventType::Wheel { delta_x, delta_y } => Some(InputEvent::Scroll { delta_x, delta_y }),
+        }
+    }
+}
+
+/// An input event and the device it came from, for backends that can tell
+/// devices apart
+struct DeviceEvent {
+    device: Option<Arc<str>>,
+    input: InputEvent,
+}
+
 /// Global input listener that runs in a separate thread
+///
+/// The rdev callback only converts events and pushes them onto a bounded
+/// channel; a consumer thread owns the listener state and applies the
+/// events to `StatsManager`.
 pub struct InputListener {
     stats: StatsManager,
     last_mouse_pos: Option<(f64, f64)>,
+    /// Mouse buttons currently held down
+    held_buttons: HashSet<String>,
+    /// Distance moved since the first held button was pressed
+    drag_moved: f64,
+    /// Size of the primary display, for the cursor position grid; `None`
+    /// if it could not be determined, in which case positions are not tracked
+    display: Option<(u64, u64)>,
+    /// Vertical wheel delta not yet amounting to a whole notch
+    scroll_remainder: i64,
+    /// Device of the last event, passed on to `StatsManager` when it changes
+    device: Option<Arc<str>>,
 }
 
 impl InputListener {
     pub fn new(stats: StatsManager) -> Self {
+        let display = match display_size() {
+            Ok((width, height)) if width > 0 && height > 0 => Some((width, height)),
+            Ok(_) => None,
+            Err(error) => {
+                log::warn!("Display size unavailable, cursor positions are not tracked: {:?}", error);
+                None
+            }
+        };
         Self {
             stats,
             last_mouse_pos: None,
+            held_buttons: HashSet::new(),
+            drag_moved: 0.0,
+            display,
+            scroll_remainder: 0,
+            device: None,
         }
     }
     
     /// Start listening for global input events
+    /// Spawns the capture thread of `backend` and the event consumer thread
+    /// Does nothing while a listener is already running, so it is safe to
+    /// call again to retry after the listener failed
+    pub fn start(stats: StatsManager, unmapped: UnmappedKeys, backend: ListenerBackend) {
+        if !stats.claim_listener() {
+            log::warn!("Input listener is already running");
+            return;
+        }
+        let requested = backend;
+        let backend = available_backend(requested);
+        if backend != requested {
+            log::warn!("The evdev backend needs a Linux build with the evdev-backend feature, using rdev");
+        }
+        let warning = (backend == ListenerBackend::Rdev && is_wayland_session()).then(|| {
+            log::warn!("{}", WAYLAND_WARNING);
+            WAYLAND_WARNING.to_string()
+        });
+        stats.set_listener_warning(warning);
+        if check_permissions() == PermissionStatus::Denied {
+            log::error!("Accessibility permission is missing, input cannot be captured");
+            stats.set_listener_error(ACCESSIBILITY_REQUIRED.to_string());
+            stats.set_listener_active(false);
+            return;
+        }
+        
+        let (sender, receiver) = mpsc::sync_channel(EVENT_CHANNEL_CAPACITY);
+        
+        let consumer = Self::new(stats.clone());
+        thread::spawn(move || consumer.run(receiver));
+        
+        #[cfg(all(target_os = "linux", feature = "evdev-backend"))]
+        if backend == ListenerBackend::Evdev {
+            thread::spawn(move || evdev::capture(stats, sender, unmapped));
+            return;
+        }
+        thread::spawn(move || Self::capture(stats, sender, unmapped));
+    }
+    
+    /// Spawn a fresh listener after the previous one failed, e.g. once the
+    /// missing permission was granted
+    pub fn restart(stats: StatsManager, unmapped: UnmappedKeys, backend: ListenerBackend) {
+        log::info!("Retrying the input listener");
+        Self::start(stats, unmapped, backend);
+    }
+    
+    /// Run rdev and forward converted events to the consumer
     /// This function will block - run it in a separate thread
-    pub fn start(stats: StatsManager) {
-        let stats_clone = stats.clone();
+    fn capture(stats: StatsManager, sender: SyncSender<DeviceEvent>, unmapped: UnmappedKeys) {
+        let callback_stats = stats.clone();
         
-        thread::spawn(move || {
-            let mut last_pos: Option<(f64, f64)> = None;
-            let callback_stats = stats_clone.clone();
-            
-            let callback = move |event: Event| {
-                match event.event_type {
-                    EventType::KeyPress(key) => {
-                        let key_name = key_to_string(&key);
-                        callback_stats.record_key(key_name);
-                    }
-                    EventType::KeyRelease(_) => {
-                        // We only count key presses, not releases
-                    }
-                    EventType::ButtonPress(button) => {
-                        let button_name = button_to_string(&button);
-                        callback_stats.record_click(button_name);
-                    }
-                    EventType::ButtonRelease(_) => {
-                        // We only count button presses
+        let callback = move |event: Event| {
+            let Some(input) = InputEvent::from_rdev(&event, unmapped, &callback_stats) else {
+                return;
+            };
+            forward(&callback_stats, &sender, None, input);
+        };
+        
+        log::info!("Starting global input listener...");
+        
+        // rdev cannot tell devices apart, all input counts for the default device
+        stats.set_device_connected(DEFAULT_DEVICE, true);
+        if let Err(error) = listen(callback) {
+            stats.set_device_connected(DEFAULT_DEVICE, false);
+            log::error!("Error in input listener: {:?}", error);
+            stats.set_listener_error(describe_listen_error(&error));
+            stats.set_listener_active(false);
+        }
+    }
+    
+    /// Consume events until every sender has been dropped
+    fn run(mut self, receiver: Receiver<DeviceEvent>) {
+        for DeviceEvent { device, input } in receiver {
+            if device != self.device {
+                self.stats.set_current_device(device.as_deref());
+                self.device = device;
+            }
+            self.handle_event(input);
+        }
+        log::debug!("Input event channel closed");
+    }
+    
+    /// Apply a single event to the statistics
+    fn handle_event(&mut self, event: InputEvent) {
+        match event {
+            InputEvent::KeyPress(key_name) => {
+                self.stats.record_key(key_name);
+            }
+            InputEvent::KeyRelease(key_name) => {
+                // Releases end a hold so the next press is counted again
+                self.stats.record_key_release(&key_name);
+            }
+            InputEvent::MouseClick(button_name) => {
+                let cell = match (self.last_mouse_pos, self.display) {
+                    (Some((x, y)), Some(display)) => Some(mouse_grid::cell(x, y, display)),
+                    (None, Some(_)) => {
+                        log::debug!("{} click before any cursor movement, position unknown", button_name);
+                        None
                     }
-                    EventType::MouseMove { x, y } => {
-                        if let Some((last_x, last_y)) = last_pos {
-                            let dx = x - last_x;
-                            let dy = y - last_y;
-                            let distance = (dx * dx + dy * dy).sqrt();
-                            callback_stats.record_movement(distance);
+                    (_, None) => None,
+                };
+                if self.held_buttons.is_empty() {
+                    self.drag_moved = 0.0;
+                }
+                self.held_buttons.insert(button_name.clone());
+                self.stats.record_click_at(button_name, cell);
+            }
+            InputEvent::MouseRelease(button_name) => {
+                // A release without a press (e.g. the listener started mid-drag) is ignored
+                if self.held_buttons.remove(&button_name) && self.held_buttons.is_empty() && self.drag_moved > DRAG_THRESHOLD {
+                    self.stats.record_drag();
+                }
+            }
+            InputEvent::MouseMove { x, y } => {
+                if let Some((last_x, last_y)) = self.last_mouse_pos {
+                    let dx = x - last_x;
+                    let dy = y - last_y;
+                    let distance = (dx * dx + dy * dy).sqrt();
+                    let max = self.stats.max_movement();
+                    if is_plausible_movement(distance, max) {
+                        let dragging = !self.held_buttons.is_empty();
+                        if dragging {
+                            self.drag_moved += distance;
                         }
-                        last_pos = Some((x, y));
-                    }
-                    EventType::Wheel { delta_x, delta_y } => {
-                        callback_stats.record_scroll(delta_y);
+                        self.stats.record_movement(distance, dragging);
+                    } else {
+                        log::debug!("Ignoring a cursor jump of {:.0} px (more than {:.0} px)", distance, max);
                     }
                 }
-            };
-            
-            log::info!("Starting global input listener...");
-            stats_clone.set_listener_active(true);
-            
-            if let Err(error) = listen(callback) {
-                stats_clone.set_listener_active(false);
-                stats_clone.set_listener_error(format!("{:?}", error));
-                log::error!("Error in input listener: {:?}", error);
+                self.last_mouse_pos = Some((x, y));
+                if let Some(display) = self.display {
+                    self.stats.record_mouse_position(x, y, display);
+                }
             }
-        });
+            InputEvent::Scroll { delta_x, delta_y } => {
+                // Only vertical scrolling is tracked for now
+                if delta_x != 0 {
+                    log::trace!("Ignoring horizontal scroll: {}", delta_x);
+                }
+                // Normalize to wheel notches so totals are comparable across platforms
+                self.scroll_remainder += delta_y;
+                let notches = self.scroll_remainder / SCROLL_UNITS_PER_NOTCH;
+                self.scroll_remainder -= notches * SCROLL_UNITS_PER_NOTCH;
+                if notches != 0 {
+                    self.stats.record_scroll(notches);
+                }
+            }
+        }
     }
 }
 
+/// Whether a cursor step of `distance` pixels is real travel rather than a
+/// jump between screens with separate coordinates or a warp by an
+/// application; a `max` of 0 accepts every step
+fn is_plausible_movement(distance: f64, max: f64) -> bool {
+    max <= 0.0 || distance <= max
+}
+
+/// Queue an event from `device` for the consumer, counting it as dropped if
+/// the queue is full
+fn forward(stats: &StatsManager, sender: &SyncSender<DeviceEvent>, device: Option<&Arc<str>>, input: InputEvent) {
+    match sender.try_send(DeviceEvent { device: device.cloned(), input }) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => stats.record_dropped_event(),
+        Err(TrySendError::Disconnected(_)) => {
+            // Consumer is gone, nothing left to do
+        }
+    }
+}
+
+/// User-facing explanation of why the listener could not start, with a hint
+/// on how to fix it
+fn describe_listen_error(error: &ListenError) -> String {
+    match error {
+        ListenError::MissingDisplayError => {
+            "No X display found. Input capture needs an X11 session (or XWayland with DISPLAY set)".to_string()
+        }
+        ListenError::KeyboardError => {
+            "Input access denied. Add your user to the 'input' group (sudo usermod -aG input $USER) and log in again".to_string()
+        }
+        ListenError::RecordContextEnablingError
+        | ListenError::RecordContextError
+        | ListenError::XRecordExtensionError => {
+            "The X server does not allow recording input. Enable the RECORD extension; Wayland sessions are not supported".to_string()
+        }
+        ListenError::EventTapError | ListenError::LoopSourceError => {
+            "Input monitoring was denied. Allow this app under System Settings → Privacy & Security → Accessibility".to_string()
+        }
+        ListenError::KeyHookError(code) | ListenError::MouseHookError(code) => {
+            format!("Windows refused the input hook (error {}). Try running the app in your desktop session", code)
+        }
+        other => format!("Input capture failed: {:?}", other),
+    }
+}
+
+/// Key name with the unmapped-key policy applied to codes without a name
+/// (dead keys, layout-specific keys) unless an alias renames them; `stats`
+/// applies the aliases when the key is recorded. `None` drops the event.
+fn key_name(key: &Key, unmapped: UnmappedKeys, stats: &StatsManager) -> Option<String> {
+    let name = key_to_string(key);
+    match key {
+        Key::Unknown(code) if media_key_name(*code).is_none() && !stats.has_key_alias(&name) => unmapped.apply(name),
+        _ => Some(name),
+    }
+}
+
+/// Media keys rdev has no variant for, by the platform key code it reports
+/// (X11 keycodes, Windows virtual-key codes). macOS does not report them.
+fn media_key_name(code: u32) -> Option<&'static str> {
+    let name = if cfg!(target_os = "linux") {
+        match code {
+            121 => "Mute",
+            122 => "VolDown",
+            123 => "VolUp",
+            172 => "Play",
+            _ => return None,
+        }
+    } else if cfg!(target_os = "windows") {
+        match code {
+            0xAD => "Mute",
+            0xAE => "VolDown",
+            0xAF => "VolUp",
+            0xB3 => "Play",
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    Some(name)
+}
+
 /// Convert rdev Key to a human-readable string
 fn key_to_string(key: &Key) -> String {
     match key {
@@ -160,6 +479,29 @@ fn key_to_string(key: &Key) -> String {
         Key::End => "End".to_string(),
         Key::PageUp => "PageUp".to_string(),
         Key::PageDown => "PageDown".to_string(),
+        Key::PrintScreen => "PrintScreen".to_string(),
+        Key::ScrollLock => "ScrollLock".to_string(),
+        Key::Pause => "PauseBreak".to_string(),
+        Key::Function => "Fn".to_string(),
+        
+        // Numeric keypad
+        Key::NumLock => "NumLock".to_string(),
+        Key::Kp0 => "KP0".to_string(),
+        Key::Kp1 => "KP1".to_string(),
+        Key::Kp2 => "KP2".to_string(),
+        Key::Kp3 => "KP3".to_string(),
+        Key::Kp4 => "KP4".to_string(),
+        Key::Kp5 => "KP5".to_string(),
+        Key::Kp6 => "KP6".to_string(),
+        Key::Kp7 => "KP7".to_string(),
+        Key::Kp8 => "KP8".to_string(),
+        Key::Kp9 => "KP9".to_string(),
+        Key::KpPlus => "KP+".to_string(),
+        Key::KpMinus => "KP-".to_string(),
+        Key::KpMultiply => "KP*".to_string(),
+        Key::KpDivide => "KP/".to_string(),
+        Key::KpDelete => "KP.".to_string(),
+        Key::KpReturn => "KPEnter".to_string(),
         
         // Arrow keys
         Key::UpArrow => "↑".to_string(),
@@ -179,12 +521,13 @@ fn key_to_string(key: &Key) -> String {
         Key::Minus => "-".to_string(),
         Key::Equal => "=".to_string(),
         Key::BackQuote => "`".to_string(),
+        Key::IntlBackslash => "<>".to_string(),
         
         // Unknown
-        Key::Unknown(code) => format!("Key({})", code),
-        
-        // Any other key
-        _ => format!("{:?}", key),
+        Key::Unknown(code) => match media_key_name(*code) {
+            Some(name) => name.to_string(),
+            None => format!("Key({})", code),
+        },
     }
 }
 
@@ -194,6 +537,119 @@ fn button_to_string(button: &Button) -> String {
         Button::Left => "Left".to_string(),
         Button::Right => "Right".to_string(),
         Button::Middle => "Middle".to_string(),
-        Button::Unknown(code) => format!("Button({})", code),
+        Button::Unknown(code) => match side_button_name(*code) {
+            Some(name) => name.to_string(),
+            None => format!("Button({})", code),
+        },
+    }
+}
+
+/// Thumb buttons rdev has no variant for, by the platform button code it
+/// reports (X11 buttons 8 and 9, Windows XBUTTON1 and XBUTTON2)
+fn side_button_name(code: u8) -> Option<&'static str> {
+    let name = if cfg!(target_os = "linux") {
+        match code {
+            8 => "Back",
+            9 => "Forward",
+            _ => return None,
+        }
+    } else if cfg!(target_os = "windows") {
+        match code {
+            1 => "Back",
+            2 => "Forward",
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::key_aliases::KeyAliases;
+
+    #[test]
+    fn cursor_jumps_are_not_travel() {
+        assert!(is_plausible_movement(12.5, 3000.0));
+        assert!(is_plausible_movement(3000.0, 3000.0));
+        assert!(!is_plausible_movement(3840.0, 3000.0));
+        assert!(is_plausible_movement(1e6, 0.0));
+    }
+
+    #[test]
+    fn keys_have_friendly_names() {
+        let keys = [
+            Key::KeyA, Key::Num0, Key::ShiftLeft, Key::ControlRight, Key::MetaLeft,
+            Key::Return, Key::Escape, Key::UpArrow, Key::Comma, Key::SemiColon, Key::BackQuote,
+            Key::IntlBackslash, Key::Kp0, Key::Kp9, Key::KpPlus, Key::KpMinus,
+            Key::KpMultiply, Key::KpDivide, Key::KpDelete, Key::KpReturn, Key::Function,
+        ];
+        for key in keys {
+            assert_ne!(key_to_string(&key), format!("{:?}", key));
+        }
+        // Top-row digits and keypad digits stay apart
+        assert_eq!(key_to_string(&Key::Num1), "1");
+        assert_eq!(key_to_string(&Key::Kp1), "KP1");
+        assert_eq!(key_to_string(&Key::Pause), "PauseBreak");
+        // Some keys keep their own names
+        assert_eq!(key_to_string(&Key::F12), "F12");
+        assert_eq!(key_to_string(&Key::NumLock), "NumLock");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn media_keys_are_named() {
+        assert_eq!(key_to_string(&Key::Unknown(123)), "VolUp");
+        assert_eq!(key_to_string(&Key::Unknown(172)), "Play");
+        // Media keys are kept even when other unmapped keys are dropped
+        let stats = StatsManager::new_in_memory();
+        assert_eq!(key_name(&Key::Unknown(121), UnmappedKeys::Drop, &stats), Some("Mute".to_string()));
+        assert_eq!(key_name(&Key::Unknown(191), UnmappedKeys::Drop, &stats), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn side_buttons_are_named() {
+        assert_eq!(button_to_string(&Button::Unknown(8)), "Back");
+        assert_eq!(button_to_string(&Button::Unknown(9)), "Forward");
+        assert_eq!(button_to_string(&Button::Unknown(12)), "Button(12)");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn only_the_evdev_guidance_mentions_the_input_group() {
+        assert!(!permission_guidance(ListenerBackend::Rdev).contains("input group"));
+        assert_eq!(permission_guidance(ListenerBackend::Evdev).contains("input group"), cfg!(feature = "evdev-backend"));
+    }
+
+    #[test]
+    fn aliased_keys_skip_the_unmapped_policy() {
+        let stats = StatsManager::new_in_memory();
+        stats.set_key_aliases(KeyAliases::new([("Key(191)".to_string(), "Macro1".to_string())].into_iter().collect()).unwrap());
+        // Named as reported; the alias is applied once, when the key is recorded
+        assert_eq!(key_name(&Key::Unknown(191), UnmappedKeys::Other, &stats), Some("Key(191)".to_string()));
+        assert_eq!(key_name(&Key::Unknown(192), UnmappedKeys::Other, &stats), Some("Other".to_string()));
+        assert_eq!(key_name(&Key::KeyA, UnmappedKeys::Other, &stats), Some("A".to_string()));
+
+        stats.record_key(key_name(&Key::Unknown(191), UnmappedKeys::Other, &stats).unwrap());
+        assert_eq!(stats.snapshot().key_counts.get("Macro1"), Some(&1));
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn no_permission_needed_outside_macos() {
+        assert_eq!(check_permissions(), PermissionStatus::Granted);
+    }
+
+    #[test]
+    fn detects_wayland_sessions() {
+        let set = |value: &str| Some(value.to_string());
+        assert!(wayland_session_from(set("wayland-0"), None));
+        assert!(wayland_session_from(None, set("Wayland")));
+        assert!(!wayland_session_from(None, set("x11")));
+        assert!(!wayland_session_from(set(""), None));
+        assert!(!wayland_session_from(None, None));
     }
 }
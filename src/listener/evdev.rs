@@ -0,0 +1,394 @@
+use evdev::{Device, EventSummary, KeyCode, RelativeAxisCode, SynchronizationCode};
+use rdev::{display_size, Button, Key};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::SyncSender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::{button_to_string, forward, key_name, DeviceEvent, InputEvent};
+use crate::config::UnmappedKeys;
+use crate::stats::StatsManager;
+
+/// Directory of the kernel's input event devices
+const INPUT_DIR: &str = "/dev/input";
+
+/// How often to look for keyboards and mice plugged in since the last scan
+const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// X11 keycodes are evdev key codes plus this offset
+const X11_KEYCODE_OFFSET: u32 = 8;
+
+/// Cursor position reconstructed from the relative motion of all mice.
+/// Mice report device units rather than pixels and pointer acceleration is
+/// not applied, so distances differ from those of the rdev backend.
+struct Cursor {
+    x: f64,
+    y: f64,
+    display: Option<(u64, u64)>,
+}
+
+impl Cursor {
+    /// Start in the middle of the display, if its size is known
+    fn new(display: Option<(u64, u64)>) -> Self {
+        let (width, height) = display.unwrap_or((0, 0));
+        Self { x: width as f64 / 2.0, y: height as f64 / 2.0, display }
+    }
+
+    /// Move by a relative motion, staying on the display
+    fn move_by(&mut self, dx: i32, dy: i32) -> (f64, f64) {
+        self.x += f64::from(dx);
+        self.y += f64::from(dy);
+        if let Some((width, height)) = self.display {
+            self.x = self.x.clamp(0.0, (width - 1) as f64);
+            self.y = self.y.clamp(0.0, (height - 1) as f64);
+        }
+        (self.x, self.y)
+    }
+}
+
+/// Turns the events of one device into `InputEvent`s
+struct Translator {
+    unmapped: UnmappedKeys,
+    /// Consulted for key aliases, which keep unmapped keys
+    stats: StatsManager,
+    cursor: Arc<Mutex<Cursor>>,
+    /// Relative motion since the last `SYN_REPORT`, applied as one move
+    motion: (i32, i32),
+}
+
+impl Translator {
+    fn translate(&mut self, event: EventSummary) -> Option<InputEvent> {
+        match event {
+            // Codes from BTN_0 on are buttons rather than keyboard keys
+            EventSummary::Key(_, code, value) if code < KeyCode::BTN_0 => {
+                let name = key_name(&key_from_evdev(code), self.unmapped, &self.stats)?;
+                // 1 is a press and 2 an auto-repeat, reported like rdev does
+                match value {
+                    0 => Some(InputEvent::KeyRelease(name)),
+                    _ => Some(InputEvent::KeyPress(name)),
+                }
+            }
+            EventSummary::Key(_, code, value) if (KeyCode::BTN_LEFT..=KeyCode::BTN_TASK).contains(&code) => {
+                let name = button_to_string(&button_from_evdev(code));
+                match value {
+                    0 => Some(InputEvent::MouseRelease(name)),
+                    1 => Some(InputEvent::MouseClick(name)),
+                    _ => None,
+                }
+            }
+            EventSummary::RelativeAxis(_, RelativeAxisCode::REL_X, value) => {
+                self.motion.0 += value;
+                None
+            }
+            EventSummary::RelativeAxis(_, RelativeAxisCode::REL_Y, value) => {
+                self.motion.1 += value;
+                None
+            }
+            // Positive values scroll up and right, as the rdev deltas do
+            EventSummary::RelativeAxis(_, RelativeAxisCode::REL_WHEEL, value) => {
+                Some(InputEvent::Scroll { delta_x: 0, delta_y: i64::from(value) })
+            }
+            EventSummary::RelativeAxis(_, RelativeAxisCode::REL_HWHEEL, value) => {
+                Some(InputEvent::Scroll { delta_x: i64::from(value), delta_y: 0 })
+            }
+            EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) if self.motion != (0, 0) => {
+                let (dx, dy) = std::mem::take(&mut self.motion);
+                let (x, y) = self.cursor.lock().ok()?.move_by(dx, dy);
+                Some(InputEvent::MouseMove { x, y })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Read every keyboard and mouse and forward converted events to the
+/// consumer, like rdev does on X11. Works under Wayland and without a
+/// display, but needs read access to `/dev/input`.
+/// This function will block - run it in a separate thread
+pub(super) fn capture(stats: StatsManager, sender: SyncSender<DeviceEvent>, unmapped: UnmappedKeys) {
+    log::info!("Starting the evdev input listener on {}...", INPUT_DIR);
+
+    let display = display_size().ok().filter(|(width, height)| *width > 0 && *height > 0);
+    let cursor = Arc::new(Mutex::new(Cursor::new(display)));
+    // Devices with a running reader; a reader removes its device when it is unplugged
+    let open: Arc<Mutex<HashSet<PathBuf>>> = Arc::default();
+    let mut first_scan = true;
+
+    loop {
+        let devices: Vec<(PathBuf, Device)> = evdev::enumerate().filter(|(_, device)| is_keyboard_or_mouse(device)).collect();
+        if first_scan && devices.is_empty() {
+            let error = if has_event_devices() {
+                format!(
+                    "No permission to read {}. Add your user to the 'input' group (sudo usermod -aG input $USER) and log in again",
+                    INPUT_DIR
+                )
+            } else {
+                format!("No keyboard or mouse found in {}", INPUT_DIR)
+            };
+            log::error!("Error in input listener: {}", error);
+            stats.set_listener_error(error);
+            stats.set_listener_active(false);
+            return;
+        }
+        first_scan = false;
+
+        for (path, device) in devices {
+            let Ok(mut open_paths) = open.lock() else {
+                return;
+            };
+            if !open_paths.insert(path.clone()) {
+                continue;
+            }
+            log::info!("Reading {} ({})", path.display(), device.name().unwrap_or("unnamed"));
+            let translator = Translator { unmapped, stats: stats.clone(), cursor: cursor.clone(), motion: (0, 0) };
+            let (stats, sender, open) = (stats.clone(), sender.clone(), open.clone());
+            thread::spawn(move || {
+                let name: Arc<str> = Arc::from(device.name().map_or_else(|| path.display().to_string(), str::to_string));
+                stats.set_device_connected(&name, true);
+                read_device(&path, &name, device, translator, &stats, &sender);
+                stats.set_device_connected(&name, false);
+                if let Ok(mut open_paths) = open.lock() {
+                    open_paths.remove(&path);
+                }
+            });
+        }
+        thread::sleep(RESCAN_INTERVAL);
+    }
+}
+
+/// Whether the device has typing keys or a pointer; skips power buttons,
+/// lid switches, touchscreens and the like
+fn is_keyboard_or_mouse(device: &Device) -> bool {
+    let keyboard = device.supported_keys().is_some_and(|keys| keys.contains(KeyCode::KEY_A) || keys.contains(KeyCode::KEY_MUTE));
+    let mouse = device.supported_relative_axes().is_some_and(|axes| axes.contains(RelativeAxisCode::REL_X));
+    keyboard || mouse
+}
+
+/// Whether `/dev/input` has event devices at all, to tell missing
+/// permissions from missing devices
+fn has_event_devices() -> bool {
+    fs::read_dir(INPUT_DIR).is_ok_and(|entries| {
+        entries.flatten().any(|entry| entry.file_name().to_string_lossy().starts_with("event"))
+    })
+}
+
+/// Forward the events of one device until it is unplugged or fails
+fn read_device(
+    path: &Path,
+    name: &Arc<str>,
+    mut device: Device,
+    mut translator: Translator,
+    stats: &StatsManager,
+    sender: &SyncSender<DeviceEvent>,
+) {
+    loop {
+        let events = match device.fetch_events() {
+            Ok(events) => events,
+            Err(e) => {
+                log::info!("Stopped reading {}: {}", path.display(), e);
+                return;
+            }
+        };
+        for event in events {
+            if let Some(input) = translator.translate(event.destructure()) {
+                forward(stats, sender, Some(name), input);
+            }
+        }
+    }
+}
+
+/// rdev key for an evdev key code. Named keys follow the X11 keycode table
+/// of rdev's X11 backend and others become `Key::Unknown` with their X11
+/// keycode, so both backends record the same key names.
+fn key_from_evdev(code: KeyCode) -> Key {
+    match code {
+        KeyCode::KEY_ESC => Key::Escape,
+        KeyCode::KEY_1 => Key::Num1,
+        KeyCode::KEY_2 => Key::Num2,
+        KeyCode::KEY_3 => Key::Num3,
+        KeyCode::KEY_4 => Key::Num4,
+        KeyCode::KEY_5 => Key::Num5,
+        KeyCode::KEY_6 => Key::Num6,
+        KeyCode::KEY_7 => Key::Num7,
+        KeyCode::KEY_8 => Key::Num8,
+        KeyCode::KEY_9 => Key::Num9,
+        KeyCode::KEY_0 => Key::Num0,
+        KeyCode::KEY_MINUS => Key::Minus,
+        KeyCode::KEY_EQUAL => Key::Equal,
+        KeyCode::KEY_BACKSPACE => Key::Backspace,
+        KeyCode::KEY_TAB => Key::Tab,
+        KeyCode::KEY_Q => Key::KeyQ,
+        KeyCode::KEY_W => Key::KeyW,
+        KeyCode::KEY_E => Key::KeyE,
+        KeyCode::KEY_R => Key::KeyR,
+        KeyCode::KEY_T => Key::KeyT,
+        KeyCode::KEY_Y => Key::KeyY,
+        KeyCode::KEY_U => Key::KeyU,
+        KeyCode::KEY_I => Key::KeyI,
+        KeyCode::KEY_O => Key::KeyO,
+        KeyCode::KEY_P => Key::KeyP,
+        KeyCode::KEY_LEFTBRACE => Key::LeftBracket,
+        KeyCode::KEY_RIGHTBRACE => Key::RightBracket,
+        KeyCode::KEY_ENTER => Key::Return,
+        KeyCode::KEY_LEFTCTRL => Key::ControlLeft,
+        KeyCode::KEY_A => Key::KeyA,
+        KeyCode::KEY_S => Key::KeyS,
+        KeyCode::KEY_D => Key::KeyD,
+        KeyCode::KEY_F => Key::KeyF,
+        KeyCode::KEY_G => Key::KeyG,
+        KeyCode::KEY_H => Key::KeyH,
+        KeyCode::KEY_J => Key::KeyJ,
+        KeyCode::KEY_K => Key::KeyK,
+        KeyCode::KEY_L => Key::KeyL,
+        KeyCode::KEY_SEMICOLON => Key::SemiColon,
+        KeyCode::KEY_APOSTROPHE => Key::Quote,
+        KeyCode::KEY_GRAVE => Key::BackQuote,
+        KeyCode::KEY_LEFTSHIFT => Key::ShiftLeft,
+        KeyCode::KEY_BACKSLASH => Key::BackSlash,
+        KeyCode::KEY_Z => Key::KeyZ,
+        KeyCode::KEY_X => Key::KeyX,
+        KeyCode::KEY_C => Key::KeyC,
+        KeyCode::KEY_V => Key::KeyV,
+        KeyCode::KEY_B => Key::KeyB,
+        KeyCode::KEY_N => Key::KeyN,
+        KeyCode::KEY_M => Key::KeyM,
+        KeyCode::KEY_COMMA => Key::Comma,
+        KeyCode::KEY_DOT => Key::Dot,
+        KeyCode::KEY_SLASH => Key::Slash,
+        KeyCode::KEY_RIGHTSHIFT => Key::ShiftRight,
+        KeyCode::KEY_KPASTERISK => Key::KpMultiply,
+        KeyCode::KEY_LEFTALT => Key::Alt,
+        KeyCode::KEY_SPACE => Key::Space,
+        KeyCode::KEY_CAPSLOCK => Key::CapsLock,
+        KeyCode::KEY_F1 => Key::F1,
+        KeyCode::KEY_F2 => Key::F2,
+        KeyCode::KEY_F3 => Key::F3,
+        KeyCode::KEY_F4 => Key::F4,
+        KeyCode::KEY_F5 => Key::F5,
+        KeyCode::KEY_F6 => Key::F6,
+        KeyCode::KEY_F7 => Key::F7,
+        KeyCode::KEY_F8 => Key::F8,
+        KeyCode::KEY_F9 => Key::F9,
+        KeyCode::KEY_F10 => Key::F10,
+        KeyCode::KEY_NUMLOCK => Key::NumLock,
+        KeyCode::KEY_SCROLLLOCK => Key::ScrollLock,
+        KeyCode::KEY_KP7 => Key::Kp7,
+        KeyCode::KEY_KP8 => Key::Kp8,
+        KeyCode::KEY_KP9 => Key::Kp9,
+        KeyCode::KEY_KPMINUS => Key::KpMinus,
+        KeyCode::KEY_KP4 => Key::Kp4,
+        KeyCode::KEY_KP5 => Key::Kp5,
+        KeyCode::KEY_KP6 => Key::Kp6,
+        KeyCode::KEY_KPPLUS => Key::KpPlus,
+        KeyCode::KEY_KP1 => Key::Kp1,
+        KeyCode::KEY_KP2 => Key::Kp2,
+        KeyCode::KEY_KP3 => Key::Kp3,
+        KeyCode::KEY_KP0 => Key::Kp0,
+        KeyCode::KEY_KPDOT => Key::KpDelete,
+        KeyCode::KEY_102ND => Key::IntlBackslash,
+        KeyCode::KEY_F11 => Key::F11,
+        KeyCode::KEY_F12 => Key::F12,
+        KeyCode::KEY_KPENTER => Key::KpReturn,
+        KeyCode::KEY_RIGHTCTRL => Key::ControlRight,
+        KeyCode::KEY_KPSLASH => Key::KpDivide,
+        KeyCode::KEY_SYSRQ => Key::PrintScreen,
+        KeyCode::KEY_RIGHTALT => Key::AltGr,
+        KeyCode::KEY_HOME => Key::Home,
+        KeyCode::KEY_UP => Key::UpArrow,
+        KeyCode::KEY_PAGEUP => Key::PageUp,
+        KeyCode::KEY_LEFT => Key::LeftArrow,
+        KeyCode::KEY_RIGHT => Key::RightArrow,
+        KeyCode::KEY_END => Key::End,
+        KeyCode::KEY_DOWN => Key::DownArrow,
+        KeyCode::KEY_PAGEDOWN => Key::PageDown,
+        KeyCode::KEY_INSERT => Key::Insert,
+        KeyCode::KEY_DELETE => Key::Delete,
+        KeyCode::KEY_PAUSE => Key::Pause,
+        KeyCode::KEY_LEFTMETA => Key::MetaLeft,
+        other => Key::Unknown(u32::from(other.code()) + X11_KEYCODE_OFFSET),
+    }
+}
+
+/// rdev button for an evdev mouse button, numbered like X11 buttons
+fn button_from_evdev(code: KeyCode) -> Button {
+    match code {
+        KeyCode::BTN_LEFT => Button::Left,
+        KeyCode::BTN_RIGHT => Button::Right,
+        KeyCode::BTN_MIDDLE => Button::Middle,
+        // Side and extra buttons are X11 buttons 8 and 9 (Back and Forward)
+        other => Button::Unknown((other.code() - KeyCode::BTN_SIDE.code()) as u8 + 8),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use evdev::EventType;
+
+    fn event(kind: EventType, code: u16, value: i32) -> EventSummary {
+        evdev::InputEvent::new(kind.0, code, value).destructure()
+    }
+
+    fn translator() -> Translator {
+        Translator {
+            unmapped: UnmappedKeys::Keep,
+            stats: StatsManager::new_in_memory(),
+            cursor: Arc::new(Mutex::new(Cursor::new(Some((100, 100))))),
+            motion: (0, 0),
+        }
+    }
+
+    fn name(code: KeyCode) -> Option<String> {
+        key_name(&key_from_evdev(code), UnmappedKeys::Keep, &StatsManager::new_in_memory())
+    }
+
+    #[test]
+    fn scancodes_get_the_rdev_key_names() {
+        assert_eq!(name(KeyCode::KEY_A).as_deref(), Some("A"));
+        assert_eq!(name(KeyCode::KEY_1).as_deref(), Some("1"));
+        assert_eq!(name(KeyCode::KEY_KP1).as_deref(), Some("KP1"));
+        assert_eq!(name(KeyCode::KEY_LEFTSHIFT).as_deref(), Some("Shift"));
+        assert_eq!(name(KeyCode::KEY_ENTER).as_deref(), Some("Enter"));
+        assert_eq!(name(KeyCode::KEY_PAUSE).as_deref(), Some("PauseBreak"));
+        // Media keys are named by their X11 keycode, like on X11
+        assert_eq!(name(KeyCode::KEY_MUTE).as_deref(), Some("Mute"));
+        assert_eq!(name(KeyCode::KEY_PLAYPAUSE).as_deref(), Some("Play"));
+        assert_eq!(key_from_evdev(KeyCode::KEY_RIGHTMETA), Key::Unknown(134));
+        assert_eq!(button_from_evdev(KeyCode::BTN_EXTRA), Button::Unknown(9));
+    }
+
+    #[test]
+    fn translates_keys_and_buttons() {
+        let mut translator = translator();
+        let key = |value| event(EventType::KEY, KeyCode::KEY_A.code(), value);
+        assert!(matches!(translator.translate(key(1)), Some(InputEvent::KeyPress(name)) if name == "A"));
+        assert!(matches!(translator.translate(key(2)), Some(InputEvent::KeyPress(name)) if name == "A"));
+        assert!(matches!(translator.translate(key(0)), Some(InputEvent::KeyRelease(name)) if name == "A"));
+        let left = event(EventType::KEY, KeyCode::BTN_LEFT.code(), 1);
+        assert!(matches!(translator.translate(left), Some(InputEvent::MouseClick(name)) if name == "Left"));
+        let back = event(EventType::KEY, KeyCode::BTN_SIDE.code(), 0);
+        assert!(matches!(translator.translate(back), Some(InputEvent::MouseRelease(name)) if name == "Back"));
+        // A touchpad touch is not a click
+        assert!(translator.translate(event(EventType::KEY, KeyCode::BTN_TOUCH.code(), 1)).is_none());
+    }
+
+    #[test]
+    fn motion_moves_the_cursor_once_per_report() {
+        let mut translator = translator();
+        let rel = |axis: RelativeAxisCode, value| event(EventType::RELATIVE, axis.0, value);
+        let report = || event(EventType::SYNCHRONIZATION, SynchronizationCode::SYN_REPORT.0, 0);
+        assert!(translator.translate(rel(RelativeAxisCode::REL_X, 10)).is_none());
+        assert!(translator.translate(rel(RelativeAxisCode::REL_Y, -5)).is_none());
+        assert!(matches!(translator.translate(report()), Some(InputEvent::MouseMove { x, y }) if x == 60.0 && y == 45.0));
+        // Nothing moved since the last report
+        assert!(translator.translate(report()).is_none());
+        // The cursor stays on the display
+        translator.translate(rel(RelativeAxisCode::REL_X, 500));
+        assert!(matches!(translator.translate(report()), Some(InputEvent::MouseMove { x, .. }) if x == 99.0));
+        let wheel = rel(RelativeAxisCode::REL_WHEEL, -1);
+        assert!(matches!(translator.translate(wheel), Some(InputEvent::Scroll { delta_x: 0, delta_y: -1 })));
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::stats::store;
+
+/// Size at which the log file is rotated
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+/// Rotated logs kept next to the current one (`finger.log.1` is the newest)
+const KEPT_LOGS: usize = 3;
+
+/// Where the log goes when logging to a file
+static SINK: Mutex<Sink> = Mutex::new(Sink::Pending(Vec::new()));
+
+/// Set up logging to stderr, or to `finger.log` in the data directory when
+/// `to_file` is set. The data directory depends on the config, so until
+/// `open_file` is called the log goes to stderr and is kept for the file.
+pub fn init(to_file: bool) {
+    let mut builder = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"));
+    builder.format_timestamp_secs();
+    if to_file {
+        builder.target(env_logger::Target::Pipe(Box::new(SinkWriter)));
+    }
+    builder.init();
+}
+
+/// Continue the log in `finger.log` in the data directory, starting with
+/// what was logged so far; called once the data directory is known. Falls
+/// back to stderr if the file cannot be opened.
+pub fn open_file() {
+    let path = store::data_dir().join("finger.log");
+    let result = match SINK.lock() {
+        Ok(mut sink) => sink.open(path.clone()),
+        Err(_) => return,
+    };
+    if let Err(e) = result {
+        log::error!("Cannot log to {}: {}, logging to stderr instead", path.display(), e);
+    }
+}
+
+/// Destination of the log when logging to a file
+enum Sink {
+    /// The file is not open yet: lines go to stderr and are kept for it
+    Pending(Vec<u8>),
+    File(RotatingFile),
+    /// The file could not be opened
+    Stderr,
+}
+
+impl Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Pending(kept) => {
+                kept.extend_from_slice(buf);
+                io::stderr().write(buf)
+            }
+            Sink::File(file) => file.write(buf),
+            Sink::Stderr => io::stderr().write(buf),
+        }
+    }
+    
+    /// Open the log file at `path` and write the lines kept so far to it
+    fn open(&mut self, path: PathBuf) -> io::Result<()> {
+        let kept = match std::mem::replace(self, Sink::Stderr) {
+            Sink::Pending(kept) => kept,
+            other => {
+                *self = other;
+                return Ok(());
+            }
+        };
+        let mut file = RotatingFile::open(path, MAX_LOG_SIZE)?;
+        file.write_all(&kept)?;
+        *self = Sink::File(file);
+        Ok(())
+    }
+}
+
+/// `Write` handle on `SINK` for the logger
+struct SinkWriter;
+
+impl Write for SinkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match SINK.lock() {
+            Ok(mut sink) => sink.write(buf),
+            Err(_) => io::stderr().write(buf),
+        }
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        match SINK.lock() {
+            Ok(mut sink) => match &mut *sink {
+                Sink::File(file) => file.flush(),
+                _ => io::stderr().flush(),
+            },
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Log file that moves itself aside once it grows past `max_size`
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_size: u64) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size, max_size })
+    }
+    
+    /// Shift `finger.log.N` to `finger.log.N+1`, dropping the oldest, and
+    /// start a new empty log
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for n in (1..KEPT_LOGS).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `finger.log.n` next to `path`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_when_full() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("finger.log");
+        let mut log = RotatingFile::open(path.clone(), 10).unwrap();
+
+        for line in ["first\n", "second\n", "third\n", "fourth\n", "fifth\n"] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+        log.flush().unwrap();
+
+        // Each line overflows the 10 bytes, so every line ends up in its own file
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fifth\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 1)).unwrap(), "fourth\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 3)).unwrap(), "second\n");
+        assert!(!rotated_path(&path, 4).exists());
+
+        // Reopening appends to the current file
+        let mut log = RotatingFile::open(path.clone(), 100).unwrap();
+        log.write_all(b"sixth\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fifth\nsixth\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn lines_before_the_file_is_opened_are_kept() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-log-sink-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("finger.log");
+        let mut sink = Sink::Pending(Vec::new());
+        sink.write(b"early\n").unwrap();
+        sink.open(path.clone()).unwrap();
+        sink.write(b"late\n").unwrap();
+        assert!(matches!(sink, Sink::File(_)));
+        drop(sink);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "early\nlate\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,52 +1,202 @@
+mod args;
+mod config;
+mod format;
 mod listener;
+mod logging;
+mod metrics;
+mod notifications;
+mod platform;
+mod report;
+mod single_instance;
 mod stats;
+mod summary;
 mod ui;
 
+use args::Args;
+use config::Config;
 use listener::InputListener;
+use single_instance::Startup;
 use stats::StatsManager;
 
+use chrono::Datelike;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
 fn main() {
-    // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-        .format_timestamp_secs()
-        .init();
+    // Initialize logger before the arguments are parsed, so warnings about
+    // them are logged; the log file is opened once the data directory is known
+    let log_to_file = std::env::args().any(|arg| arg == "--log-file");
+    logging::init(log_to_file);
     
     log::info!("Starting Finger Monitor...");
     
+    let args = Args::parse();
+    let file_config = Config::load(&args);
+    let config = Config::effective(file_config.as_ref(), &args);
+    log::debug!("Effective config: {:?}", config);
+    if let Some(dir) = &config.data_dir {
+        stats::store::set_data_dir(dir.clone());
+    }
+    if log_to_file {
+        logging::open_file();
+    }
+    
+    // A second instance would record every event twice and overwrite the
+    // stats of the first, so it raises the first one's window instead. The
+    // commands would overwrite them too, so they do not run alongside it.
+    let command = args.import.is_some() || args.normalize || args.report;
+    let instance = match single_instance::acquire(!command) {
+        Ok(Startup::First(lock)) => Some(lock),
+        Ok(Startup::Running(pid)) if command => {
+            eprintln!("Finger Monitor is running (process {}); quit it before running this command", pid);
+            std::process::exit(1);
+        }
+        Ok(Startup::Running(pid)) => {
+            log::info!("Finger Monitor is already running (process {}), showing its window", pid);
+            return;
+        }
+        Err(e) => {
+            log::warn!("Could not check for another running instance: {}", e);
+            None
+        }
+    };
+    // Only a recording run counts as a start worth a backup
+    if !command {
+        stats::store::back_up_on_startup(args.storage, config.startup_backups);
+    }
+    
     // Create stats manager
-    let stats_manager = StatsManager::new();
+    let stats_manager = match stats::store::open(args.storage) {
+        Ok(store) => StatsManager::with_store(store),
+        Err(e) => {
+            log::error!("Failed to open {:?} storage: {}, falling back to JSON", args.storage, e);
+            StatsManager::new()
+        }
+    };
+    
+    stats_manager.set_key_aliases(config.key_aliases.clone());
+    
+    if command {
+        let result = if let Some(path) = &args.import {
+            import(&stats_manager, path)
+        } else if args.normalize {
+            normalize(&stats_manager)
+        } else {
+            year_review(&stats_manager, args.year, args.output.as_deref())
+        };
+        if let Some(lock) = &instance {
+            lock.release();
+        }
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    
+    stats_manager.set_count_repeats(config.count_repeats);
+    if args.privacy {
+        stats_manager.set_privacy_mode(true);
+    }
+    stats_manager.set_excluded_apps(config.excluded_apps.clone());
+    stats_manager.set_scroll_unit(config.scroll_unit);
+    stats_manager.set_wpm_smoothing(config.wpm_smoothing);
+    stats_manager.set_word_length(config.word_length);
+    stats_manager.set_pause_in_fullscreen(config.pause_in_fullscreen);
+    stats_manager.set_save_interval(Duration::from_secs(config.save_interval_secs));
+    stats_manager.set_dedup_window(Duration::from_millis(config.dedup_window_ms));
+    stats_manager.set_multi_click_window(Duration::from_millis(config.multi_click_ms));
+    stats_manager.set_max_movement(config.max_movement_px);
+    stats_manager.set_record_events(config.record_events);
+    stats_manager.set_daily_reports(args.daily_report);
+    stats_manager.set_retention_days(config.retention_days);
+    stats_manager.set_streak_min_keys(config.streak_min_keys);
+    if stats_manager.is_privacy_mode() {
+        log::info!("Privacy mode is on, key identities will not be recorded");
+    }
     
     // Start input listener in background thread
-    InputListener::start(stats_manager.clone());
+    InputListener::start(stats_manager.clone(), config.unmapped_keys, config.backend);
     
-    // Set up periodic save
-    let save_manager = stats_manager.clone();
-    thread::spawn(move || {
-        loop {
-            thread::sleep(Duration::from_secs(60));
-            if let Err(e) = save_manager.save() {
-                log::error!("Failed to save stats: {}", e);
-            } else {
-                log::debug!("Stats saved successfully");
-            }
+    // Daily recap at the configured hour
+    summary::spawn_daily_summary(stats_manager.clone(), args.summary_hour);
+    
+    // Desktop notifications for milestones reached and finished days
+    notifications::spawn_notifier(stats_manager.clone());
+    
+    // Opt-in Prometheus endpoint
+    if let Some(port) = args.metrics_port {
+        if let Err(e) = metrics::spawn_server(stats_manager.clone(), port) {
+            log::error!("Could not serve metrics on port {}: {}", port, e);
         }
-    });
+    }
+    
+    // Periodic and idle-triggered saves
+    stats_manager.spawn_autosave();
     
     // Save stats on exit
     let exit_manager = stats_manager.clone();
+    let exit_instance = instance.clone();
     ctrlc::set_handler(move || {
         log::info!("Shutting down, saving stats...");
-        let _ = exit_manager.save();
+        let _ = exit_manager.force_save();
+        if let Some(lock) = &exit_instance {
+            lock.release();
+        }
         std::process::exit(0);
     }).expect("Error setting Ctrl-C handler");
     
+    if args.headless {
+        // No window: keep collecting until the Ctrl-C handler saves and exits
+        log::info!("Running headless (no UI), press Ctrl-C to stop");
+        loop {
+            thread::park();
+        }
+    }
+    
     // Run GPUI application (blocks until window closes)
-    ui::app::run(stats_manager.clone());
+    let show_requests = instance
+        .as_ref()
+        .map(|lock| lock.show_requests())
+        .unwrap_or_else(|| Arc::new(AtomicBool::new(false)));
+    ui::app::run(stats_manager.clone(), config, file_config, show_requests);
     
     // Save before exit
     log::info!("Saving final stats...");
-    let _ = stats_manager.save();
+    let _ = stats_manager.force_save();
+    if let Some(lock) = &instance {
+        lock.release();
+    }
+}
+
+/// Rename the recorded keys by the configured aliases, print what changed and save
+fn normalize(stats_manager: &StatsManager) -> Result<(), String> {
+    let report = stats_manager.normalize_keys().map_err(|e| format!("Normalizing key names failed: {}", e))?;
+    println!("{}", report);
+    Ok(())
+}
+
+/// Write the Year in Review of `year` (default: the current one) to `output`,
+/// or print it as Markdown
+fn year_review(stats_manager: &StatsManager, year: Option<i32>, output: Option<&Path>) -> Result<(), String> {
+    let stats = stats_manager.snapshot();
+    let year = year.unwrap_or_else(|| stats.today().year());
+    let Some(path) = output else {
+        print!("{}", report::year_review(&stats, year).render(report::ReportFormat::Markdown));
+        return Ok(());
+    };
+    report::write_year_review(&stats, year, path).map_err(|e| format!("Writing {} failed: {}", path.display(), e))?;
+    println!("Wrote the {} Year in Review to {}", year, path.display());
+    Ok(())
+}
+
+/// Merge another stats file into the local stats, print the report and save
+fn import(stats_manager: &StatsManager, path: &Path) -> Result<(), String> {
+    let report = stats_manager.import_and_merge(path).map_err(|e| format!("Import of {} failed: {}", path.display(), e))?;
+    println!("Imported {}", path.display());
+    println!("{}", report);
+    Ok(())
 }
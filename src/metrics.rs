@@ -0,0 +1,148 @@
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::stats::{Stats, StatsManager};
+
+/// How long to wait for a scraper to send its request or read the response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Label value with backslashes, double quotes and line feeds escaped
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Append the HELP and TYPE lines of a metric
+fn describe(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// The stats in the Prometheus text exposition format
+pub fn render(stats: &Stats) -> String {
+    let mut out = String::new();
+    describe(&mut out, "finger_keys_total", "counter", "Key presses recorded, including those in privacy mode.");
+    let _ = writeln!(out, "finger_keys_total {}", stats.total_keys());
+    
+    describe(&mut out, "finger_clicks_total", "counter", "Mouse clicks recorded, by button.");
+    let mut buttons: Vec<_> = stats.mouse_clicks.iter().collect();
+    buttons.sort();
+    for (button, count) in buttons {
+        let _ = writeln!(out, "finger_clicks_total{{button=\"{}\"}} {}", escape_label(&button.to_lowercase()), count);
+    }
+    
+    describe(&mut out, "finger_mouse_distance_pixels", "counter", "Cursor travel in pixels.");
+    let _ = writeln!(out, "finger_mouse_distance_pixels {}", stats.mouse_distance);
+    
+    describe(&mut out, "finger_wpm", "gauge", "Typing speed over the last minute, in words per minute.");
+    let _ = writeln!(out, "finger_wpm {}", stats.current_wpm());
+    out
+}
+
+/// Serve `GET /metrics` on `127.0.0.1:port` in the background, returning the
+/// bound address (port 0 picks a free one)
+pub fn spawn_server(stats_manager: StatsManager, port: u16) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    let address = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = respond(stream, &stats_manager) {
+                log::debug!("Metrics request failed: {}", e);
+            }
+        }
+    });
+    log::info!("Serving metrics on http://{}/metrics", address);
+    Ok(address)
+}
+
+/// Answer one HTTP request; only `GET /metrics` is served
+fn respond(stream: TcpStream, stats_manager: &StatsManager) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers; a request body is not expected
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+    
+    let mut parts = request_line.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", CONTENT_TYPE, render(&stats_manager.snapshot())),
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "Not found, try /metrics\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "Only GET is supported\n".to_string()),
+    };
+    let mut stream = reader.into_inner();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn renders_exposition_format() {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 40);
+        stats.anonymous_keys = 2;
+        stats.mouse_clicks.insert("Left".to_string(), 7);
+        stats.mouse_clicks.insert("Button \"8\"".to_string(), 1);
+        stats.mouse_distance = 1234.5;
+
+        let text = render(&stats);
+        assert!(text.contains("# HELP finger_keys_total "));
+        assert!(text.contains("# TYPE finger_keys_total counter\nfinger_keys_total 42\n"));
+        assert!(text.contains("# TYPE finger_clicks_total counter\n"));
+        assert!(text.contains("finger_clicks_total{button=\"button \\\"8\\\"\"} 1\nfinger_clicks_total{button=\"left\"} 7\n"));
+        assert!(text.contains("finger_mouse_distance_pixels 1234.5\n"));
+        assert!(text.contains("# TYPE finger_wpm gauge\nfinger_wpm 0\n"));
+        // Every sample line belongs to a described metric
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {} ", name)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+
+    fn get(address: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_metrics_over_http() {
+        let manager = StatsManager::with_store(Box::new(crate::stats::store::MemoryStore::default()));
+        manager.record_key("A".to_string());
+        let address = spawn_server(manager, 0).unwrap();
+
+        let response = get(address, "/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(response.contains("\r\n\r\n# HELP finger_keys_total "));
+        assert!(response.contains("finger_keys_total 1\n"));
+        assert!(get(address, "/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
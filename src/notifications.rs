@@ -0,0 +1,33 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::stats::StatsManager;
+
+/// How often queued announcements are looked for
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Spawn a background thread that shows unlocked milestones and finished
+/// days as they are announced
+pub fn spawn_notifier(stats: StatsManager) {
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        for message in stats.take_announcements() {
+            show(&message);
+        }
+    });
+}
+
+/// Show a desktop notification; does nothing in builds without notifications
+#[cfg(feature = "notifications")]
+pub fn show(text: &str) {
+    if let Err(e) = notify_rust::Notification::new()
+        .summary("Finger Monitor")
+        .body(text)
+        .show()
+    {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
+
+#[cfg(not(feature = "notifications"))]
+pub fn show(_text: &str) {}
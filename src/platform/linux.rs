@@ -0,0 +1,260 @@
+use std::ffi::CString;
+use std::fs;
+use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+use std::ptr;
+use std::slice;
+
+use raw_window_handle::RawWindowHandle;
+use x11_dl::xlib::{self, Xlib};
+
+/// Xlib and a connection to the X server, opened on first use and kept
+/// for the life of the thread. Xlib connections are not shared between
+/// threads, so each thread querying the desktop gets its own.
+struct Connection {
+    xlib: Xlib,
+    display: *mut xlib::Display,
+}
+
+impl Connection {
+    /// `None` without Xlib or an X server (e.g. pure Wayland sessions)
+    fn open() -> Option<Self> {
+        let xlib = Xlib::open().ok()?;
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            return None;
+        }
+        Some(Self { xlib, display })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+    }
+}
+
+thread_local! {
+    /// Not retried when the first attempt fails
+    static CONNECTION: Option<Connection> = Connection::open();
+}
+
+/// Run `query` with this thread's X connection, or return `None` without one
+fn with_display<T>(query: impl FnOnce(&Xlib, *mut xlib::Display) -> T) -> Option<T> {
+    CONNECTION.with(|connection| connection.as_ref().map(|c| query(&c.xlib, c.display)))
+}
+
+/// Name of the process owning the active X11 window.
+/// Returns `None` without an X server (e.g. pure Wayland sessions).
+pub fn foreground_app() -> Option<String> {
+    with_display(|xlib, display| unsafe { active_window_app(xlib, display) }).flatten()
+}
+
+/// Whether the active X11 window is in `_NET_WM_STATE_FULLSCREEN`.
+/// Returns `None` without an X server.
+pub fn foreground_fullscreen() -> Option<bool> {
+    with_display(|xlib, display| unsafe {
+        active_window(xlib, display).is_some_and(|window| is_fullscreen(xlib, display, window))
+    })
+}
+
+/// Whether `/proc` has an entry for the process
+pub fn process_alive(pid: u32) -> Option<bool> {
+    Some(fs::metadata(format!("/proc/{}", pid)).is_ok())
+}
+
+/// Ask the window manager to keep an X11 window above all others
+/// (`_NET_WM_STATE_ABOVE`). Returns `false` for Wayland windows or without an X server.
+pub fn set_always_on_top(window: RawWindowHandle, on_top: bool) -> bool {
+    let Some(window) = x11_window(window) else {
+        return false;
+    };
+    with_display(|xlib, display| unsafe { set_wm_state(xlib, display, window, "_NET_WM_STATE_ABOVE", on_top) })
+        .unwrap_or(false)
+}
+
+/// Set the smallest size the window manager lets an X11 window be resized
+/// to (`WM_NORMAL_HINTS`). Returns `false` for Wayland windows or without an X server.
+pub fn set_min_size(window: RawWindowHandle, width: u32, height: u32) -> bool {
+    let Some(window) = x11_window(window) else {
+        return false;
+    };
+    with_display(|xlib, display| unsafe {
+        let hints = (xlib.XAllocSizeHints)();
+        if hints.is_null() {
+            return false;
+        }
+        // Keep the other hints the window already has
+        let mut supplied: c_long = 0;
+        (xlib.XGetWMNormalHints)(display, window, hints, &mut supplied);
+        (*hints).flags |= xlib::PMinSize;
+        (*hints).min_width = width as c_int;
+        (*hints).min_height = height as c_int;
+        (xlib.XSetWMNormalHints)(display, window, hints);
+        (xlib.XFree)(hints as *mut _);
+        (xlib.XFlush)(display);
+        true
+    })
+    .unwrap_or(false)
+}
+
+/// The X11 window behind a handle, `None` for Wayland windows
+fn x11_window(window: RawWindowHandle) -> Option<xlib::Window> {
+    match window {
+        RawWindowHandle::Xlib(handle) => Some(handle.window as xlib::Window),
+        RawWindowHandle::Xcb(handle) => Some(handle.window.get() as xlib::Window),
+        _ => None,
+    }
+}
+
+/// Add or remove a `_NET_WM_STATE` flag through a client message to the root window
+unsafe fn set_wm_state(xlib: &Xlib, display: *mut xlib::Display, window: xlib::Window, state: &str, enable: bool) -> bool {
+    let (Ok(wm_state), Ok(state)) = (CString::new("_NET_WM_STATE"), CString::new(state)) else {
+        return false;
+    };
+    let wm_state = (xlib.XInternAtom)(display, wm_state.as_ptr(), xlib::False);
+    let state = (xlib.XInternAtom)(display, state.as_ptr(), xlib::False);
+    
+    let mut message: xlib::XClientMessageEvent = std::mem::zeroed();
+    message.type_ = xlib::ClientMessage;
+    message.window = window;
+    message.message_type = wm_state;
+    message.format = 32;
+    // Action (1 = add, 0 = remove), the property, no second property, source: application
+    message.data.set_long(0, enable as c_long);
+    message.data.set_long(1, state as c_long);
+    message.data.set_long(3, 1);
+    
+    let mut event = xlib::XEvent { client_message: message };
+    let root = (xlib.XDefaultRootWindow)(display);
+    let mask = xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask;
+    let status = (xlib.XSendEvent)(display, root, xlib::False, mask, &mut event);
+    (xlib.XFlush)(display);
+    status != 0
+}
+
+unsafe fn active_window(xlib: &Xlib, display: *mut xlib::Display) -> Option<xlib::Window> {
+    let root = (xlib.XDefaultRootWindow)(display);
+    let window = *read_property(xlib, display, root, "_NET_ACTIVE_WINDOW", xlib::XA_WINDOW)?
+        .longs()
+        .first()? as xlib::Window;
+    (window != 0).then_some(window)
+}
+
+unsafe fn is_fullscreen(xlib: &Xlib, display: *mut xlib::Display, window: xlib::Window) -> bool {
+    let Ok(name) = CString::new("_NET_WM_STATE_FULLSCREEN") else {
+        return false;
+    };
+    let fullscreen = (xlib.XInternAtom)(display, name.as_ptr(), xlib::True);
+    if fullscreen == 0 {
+        return false;
+    }
+    read_property(xlib, display, window, "_NET_WM_STATE", xlib::XA_ATOM)
+        .is_some_and(|state| state.longs().contains(&fullscreen))
+}
+
+unsafe fn active_window_app(xlib: &Xlib, display: *mut xlib::Display) -> Option<String> {
+    let window = active_window(xlib, display)?;
+    
+    // Prefer the process name, fall back to the window class
+    let pid = read_property(xlib, display, window, "_NET_WM_PID", xlib::XA_CARDINAL)
+        .and_then(|p| p.longs().first().copied());
+    if let Some(comm) = pid.and_then(|pid| fs::read_to_string(format!("/proc/{}/comm", pid)).ok()) {
+        return Some(comm.trim().to_string());
+    }
+    
+    // WM_CLASS holds "instance\0class\0"
+    let class = read_property(xlib, display, window, "WM_CLASS", xlib::XA_STRING)?;
+    class.bytes()
+        .split(|b| *b == 0)
+        .rfind(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned())
+}
+
+/// Raw window property data owned by Xlib
+struct Property<'a> {
+    xlib: &'a Xlib,
+    data: *mut c_uchar,
+    format: c_int,
+    items: c_ulong,
+}
+
+impl Property<'_> {
+    /// 32-bit format items, which Xlib stores as C longs
+    fn longs(&self) -> &[c_ulong] {
+        if self.format != 32 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.data as *const c_ulong, self.items as usize) }
+    }
+    
+    fn bytes(&self) -> &[u8] {
+        if self.format != 8 {
+            return &[];
+        }
+        unsafe { slice::from_raw_parts(self.data, self.items as usize) }
+    }
+}
+
+impl Drop for Property<'_> {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xlib.XFree)(self.data as *mut _);
+        }
+    }
+}
+
+unsafe fn read_property<'a>(
+    xlib: &'a Xlib,
+    display: *mut xlib::Display,
+    window: xlib::Window,
+    name: &str,
+    req_type: xlib::Atom,
+) -> Option<Property<'a>> {
+    let name = CString::new(name).ok()?;
+    let atom = (xlib.XInternAtom)(display, name.as_ptr(), xlib::True);
+    if atom == 0 {
+        return None;
+    }
+    
+    let mut actual_type: xlib::Atom = 0;
+    let mut format: c_int = 0;
+    let mut items: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut data: *mut c_uchar = ptr::null_mut();
+    
+    let status = (xlib.XGetWindowProperty)(
+        display,
+        window,
+        atom,
+        0,
+        1024 as c_long,
+        xlib::False,
+        req_type,
+        &mut actual_type,
+        &mut format,
+        &mut items,
+        &mut bytes_after,
+        &mut data,
+    );
+    
+    if status != xlib::Success as c_int || data.is_null() {
+        return None;
+    }
+    
+    let property = Property { xlib, data, format, items };
+    if items == 0 {
+        return None;
+    }
+    Some(property)
+}
+
+/// No permission is needed beyond access to the input devices
+pub fn accessibility_trusted() -> Option<bool> {
+    None
+}
+
+pub fn open_accessibility_settings() -> bool {
+    false
+}
@@ -0,0 +1,56 @@
+use objc2_app_kit::NSWorkspace;
+use raw_window_handle::RawWindowHandle;
+use std::ffi::c_void;
+use std::process::Command;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrustedWithOptions(options: *const c_void) -> u8;
+}
+
+/// Settings page listing the apps allowed to control the computer
+const ACCESSIBILITY_SETTINGS_URL: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility";
+
+/// Localized name of the frontmost application
+pub fn foreground_app() -> Option<String> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let app = workspace.frontmostApplication()?;
+    let name = app.localizedName()?;
+    Some(name.to_string())
+}
+
+/// Not available: AppKit only reports the fullscreen state of this
+/// process's own windows
+pub fn foreground_fullscreen() -> Option<bool> {
+    None
+}
+
+/// Not available without libc; callers fall back to other checks
+pub fn process_alive(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// Not implemented yet: would need the window level of the `NSWindow`
+/// behind the view handle
+pub fn set_always_on_top(_window: RawWindowHandle, _on_top: bool) -> bool {
+    false
+}
+
+/// Not implemented yet: would need `setContentMinSize:` on the `NSWindow`
+pub fn set_min_size(_window: RawWindowHandle, _width: u32, _height: u32) -> bool {
+    false
+}
+
+/// Whether the process is in the Accessibility list, without prompting
+pub fn accessibility_trusted() -> Option<bool> {
+    // No options dictionary: check only, do not show the system prompt
+    Some(unsafe { AXIsProcessTrustedWithOptions(std::ptr::null()) } != 0)
+}
+
+/// Open System Settings → Privacy & Security → Accessibility
+pub fn open_accessibility_settings() -> bool {
+    Command::new("open")
+        .arg(ACCESSIBILITY_SETTINGS_URL)
+        .status()
+        .is_ok_and(|status| status.success())
+}
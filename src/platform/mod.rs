@@ -0,0 +1,174 @@
+//! Platform-specific queries about the desktop session
+
+use raw_window_handle::RawWindowHandle;
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "linux")]
+use linux as imp;
+
+#[cfg(target_os = "windows")]
+mod windows;
+#[cfg(target_os = "windows")]
+use windows as imp;
+
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "macos")]
+use macos as imp;
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+mod imp {
+    use raw_window_handle::RawWindowHandle;
+    
+    pub fn foreground_app() -> Option<String> {
+        None
+    }
+    
+    pub fn foreground_fullscreen() -> Option<bool> {
+        None
+    }
+    
+    pub fn set_always_on_top(_window: RawWindowHandle, _on_top: bool) -> bool {
+        false
+    }
+    
+    pub fn set_min_size(_window: RawWindowHandle, _width: u32, _height: u32) -> bool {
+        false
+    }
+    
+    pub fn process_alive(_pid: u32) -> Option<bool> {
+        None
+    }
+    
+    pub fn accessibility_trusted() -> Option<bool> {
+        None
+    }
+    
+    pub fn open_accessibility_settings() -> bool {
+        false
+    }
+}
+
+/// Bucket used when the foreground application cannot be determined
+pub const UNKNOWN_APP: &str = "Unknown";
+
+/// Minimum time between foreground application queries
+const QUERY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keep one of our windows above all others, or release it again.
+/// Returns `false` where this is not supported.
+pub fn set_always_on_top(window: RawWindowHandle, on_top: bool) -> bool {
+    imp::set_always_on_top(window, on_top)
+}
+
+/// Change the smallest size the user can resize one of our windows to.
+/// Returns `false` where this is not supported.
+pub fn set_min_size(window: RawWindowHandle, width: u32, height: u32) -> bool {
+    imp::set_min_size(window, width, height)
+}
+
+/// Whether a process with this id is running, or `None` where this cannot
+/// be checked
+pub fn process_alive(pid: u32) -> Option<bool> {
+    imp::process_alive(pid)
+}
+
+/// Whether the user allowed this app to monitor input (macOS Accessibility),
+/// or `None` where no such permission exists
+pub fn accessibility_trusted() -> Option<bool> {
+    imp::accessibility_trusted()
+}
+
+/// Open the system settings page where input monitoring is allowed.
+/// Returns `false` where there is no such page or it could not be opened.
+pub fn open_accessibility_settings() -> bool {
+    imp::open_accessibility_settings()
+}
+
+/// Caches the foreground application name so the OS is queried at most once per second
+#[derive(Debug)]
+pub struct ActiveAppCache {
+    name: String,
+    last_query: Option<Instant>,
+    warned: bool,
+}
+
+impl ActiveAppCache {
+    pub fn new() -> Self {
+        Self {
+            name: UNKNOWN_APP.to_string(),
+            last_query: None,
+            warned: false,
+        }
+    }
+    
+    /// Name of the application that currently has focus
+    pub fn current(&mut self) -> String {
+        let now = Instant::now();
+        let stale = self.last_query
+            .map(|t| now.duration_since(t) >= QUERY_INTERVAL)
+            .unwrap_or(true);
+        
+        if stale {
+            self.last_query = Some(now);
+            self.name = match imp::foreground_app() {
+                Some(name) => name,
+                None => {
+                    // Expected on Wayland and unsupported platforms, so only say it once
+                    if !self.warned {
+                        log::warn!("Could not determine the foreground application, input will be counted as \"{}\"", UNKNOWN_APP);
+                        self.warned = true;
+                    }
+                    UNKNOWN_APP.to_string()
+                }
+            };
+        }
+        
+        self.name.clone()
+    }
+}
+
+/// Caches whether the foreground window is fullscreen, querying the OS at most once per second
+#[derive(Debug)]
+pub struct FullscreenCache {
+    fullscreen: bool,
+    last_query: Option<Instant>,
+    warned: bool,
+}
+
+impl FullscreenCache {
+    pub fn new() -> Self {
+        Self {
+            fullscreen: false,
+            last_query: None,
+            warned: false,
+        }
+    }
+    
+    /// Whether the focused window covers its whole screen.
+    /// Always `false` where this cannot be detected.
+    pub fn is_fullscreen(&mut self) -> bool {
+        let now = Instant::now();
+        let stale = self.last_query
+            .map(|t| now.duration_since(t) >= QUERY_INTERVAL)
+            .unwrap_or(true);
+        
+        if stale {
+            self.last_query = Some(now);
+            self.fullscreen = match imp::foreground_fullscreen() {
+                Some(fullscreen) => fullscreen,
+                None => {
+                    if !self.warned {
+                        log::warn!("Fullscreen windows cannot be detected here, input is recorded as usual");
+                        self.warned = true;
+                    }
+                    false
+                }
+            };
+        }
+        
+        self.fullscreen
+    }
+}
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use raw_window_handle::RawWindowHandle;
+use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ACCESS_DENIED, HWND, MAX_PATH, RECT, STILL_ACTIVE};
+use windows_sys::Win32::Graphics::Gdi::{GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST};
+use windows_sys::Win32::System::Threading::{
+    GetExitCodeProcess, OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    GetDesktopWindow, GetForegroundWindow, GetShellWindow, GetWindowRect, GetWindowThreadProcessId, SetWindowPos,
+    HWND_NOTOPMOST, HWND_TOPMOST, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+};
+
+/// Executable name (without extension) of the process owning the foreground window
+pub fn foreground_app() -> Option<String> {
+    unsafe {
+        let window = GetForegroundWindow();
+        if window.is_null() {
+            return None;
+        }
+        
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(window, &mut pid);
+        if pid == 0 {
+            return None;
+        }
+        
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+        
+        let mut buffer = [0u16; MAX_PATH as usize];
+        let mut len = buffer.len() as u32;
+        let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+        
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        Path::new(&path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+    }
+}
+
+/// Whether the process has not exited yet
+pub fn process_alive(pid: u32) -> Option<bool> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            // Processes of other users exist but cannot be opened
+            return Some(GetLastError() == ERROR_ACCESS_DENIED);
+        }
+        
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(process, &mut code);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+        Some(code == STILL_ACTIVE as u32)
+    }
+}
+
+/// Whether the foreground window covers its whole monitor
+pub fn foreground_fullscreen() -> Option<bool> {
+    unsafe {
+        let window = GetForegroundWindow();
+        // The desktop also spans the monitor but is not an application
+        if window.is_null() || window == GetDesktopWindow() || window == GetShellWindow() {
+            return Some(false);
+        }
+        
+        let mut rect: RECT = std::mem::zeroed();
+        if GetWindowRect(window, &mut rect) == 0 {
+            return None;
+        }
+        
+        let monitor = MonitorFromWindow(window, MONITOR_DEFAULTTONEAREST);
+        let mut info: MONITORINFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(monitor, &mut info) == 0 {
+            return None;
+        }
+        
+        let screen = info.rcMonitor;
+        Some(rect.left <= screen.left && rect.top <= screen.top && rect.right >= screen.right && rect.bottom >= screen.bottom)
+    }
+}
+
+/// Move a window into or out of the topmost band
+pub fn set_always_on_top(window: RawWindowHandle, on_top: bool) -> bool {
+    let RawWindowHandle::Win32(handle) = window else {
+        return false;
+    };
+    let insert_after = if on_top { HWND_TOPMOST } else { HWND_NOTOPMOST };
+    unsafe {
+        SetWindowPos(
+            handle.hwnd.get() as HWND,
+            insert_after,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE,
+        ) != 0
+    }
+}
+
+/// Not implemented: GPUI answers `WM_GETMINMAXINFO` with the minimum size
+/// the window was created with
+pub fn set_min_size(_window: RawWindowHandle, _width: u32, _height: u32) -> bool {
+    false
+}
+
+/// No permission is needed beyond access to the input devices
+pub fn accessibility_trusted() -> Option<bool> {
+    None
+}
+
+pub fn open_accessibility_settings() -> bool {
+    false
+}
@@ -0,0 +1,360 @@
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+use crate::stats::daily_report::peak_hour;
+use crate::stats::{DailyStats, Stats};
+
+/// Keys listed in the top keys table
+const TOP_KEYS: usize = 10;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Output format of a report, picked from the file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// HTML for `.html` and `.htm` files, Markdown otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("html" | "htm") => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+/// Totals of one month of a year
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthRow {
+    /// 1-12
+    pub month: u32,
+    pub keys: u64,
+    pub clicks: u64,
+    /// Cursor travel in kilometers
+    pub distance_km: f64,
+}
+
+/// "Year in Review" of one calendar year
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearReview {
+    pub year: i32,
+    /// First and last day with recorded activity; `None` if there is none
+    pub covered: Option<(NaiveDate, NaiveDate)>,
+    pub total_keys: u64,
+    pub total_clicks: u64,
+    /// Cursor travel in kilometers
+    pub distance_km: f64,
+    /// Day with the most key presses
+    pub busiest_day: Option<(NaiveDate, u64)>,
+    /// Hour of the day with the most key presses and clicks over the year
+    pub busiest_hour: Option<(u8, u64)>,
+    /// Most pressed keys, from the per-day key counts, which are kept for
+    /// `DAILY_KEY_RETENTION_DAYS` only
+    pub top_keys: Vec<(String, u64)>,
+    /// Every month with activity, in calendar order
+    pub months: Vec<MonthRow>,
+}
+
+impl YearReview {
+    /// Whether the recorded days do not span the whole year
+    pub fn is_partial(&self) -> bool {
+        match self.covered {
+            Some((first, last)) => (first.month(), first.day(), last.month(), last.day()) != (1, 1, 12, 31),
+            None => true,
+        }
+    }
+    
+    /// Key presses per day over the covered range, counting idle days in it
+    pub fn average_daily_keys(&self) -> u64 {
+        match self.covered {
+            Some((first, last)) => self.total_keys / ((last - first).num_days() as u64 + 1),
+            None => 0,
+        }
+    }
+    
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+    
+    /// Sentence describing the covered date range of a partial year
+    fn coverage_note(&self) -> Option<String> {
+        if !self.is_partial() {
+            return None;
+        }
+        Some(match self.covered {
+            Some((first, last)) => format!("Covers {} to {}, the days with recorded activity.", first, last),
+            None => format!("No activity was recorded in {}.", self.year),
+        })
+    }
+    
+    /// Label and value of each headline number
+    fn highlights(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Total keys", self.total_keys.to_string()),
+            ("Total clicks", self.total_clicks.to_string()),
+            ("Mouse distance", format!("{:.2} km", self.distance_km)),
+            ("Average daily keys", self.average_daily_keys().to_string()),
+            ("Busiest day", match self.busiest_day {
+                Some((date, keys)) => format!("{} ({} keys)", date.format("%A, %B %-d"), keys),
+                None => "—".to_string(),
+            }),
+            ("Busiest hour", match self.busiest_hour {
+                Some((hour, actions)) => format!("{:02}:00–{:02}:59 ({} keys and clicks)", hour, hour, actions),
+                None => "—".to_string(),
+            }),
+        ]
+    }
+    
+    pub fn to_markdown(&self) -> String {
+        let mut report = format!("# Finger Monitor: {} in Review\n\n", self.year);
+        if let Some(note) = self.coverage_note() {
+            let _ = writeln!(report, "_{}_\n", note);
+        }
+        
+        report.push_str("| | |\n|---|---|\n");
+        for (label, value) in self.highlights() {
+            let _ = writeln!(report, "| {} | {} |", label, value);
+        }
+        
+        report.push_str("\n## Top keys\n\n");
+        if self.top_keys.is_empty() {
+            report.push_str("No per-key counts were kept for this year.\n");
+        } else {
+            report.push_str("| # | Key | Presses |\n|---|---|---|\n");
+            for (rank, (key, count)) in self.top_keys.iter().enumerate() {
+                let _ = writeln!(report, "| {} | `{}` | {} |", rank + 1, key, count);
+            }
+        }
+        
+        report.push_str("\n## Month by month\n\n");
+        report.push_str("| Month | Keys | Clicks | Distance |\n|---|---|---|---|\n");
+        for row in &self.months {
+            let _ = writeln!(
+                report,
+                "| {} | {} | {} | {:.2} km |",
+                MONTH_NAMES[row.month as usize - 1], row.keys, row.clicks, row.distance_km
+            );
+        }
+        report
+    }
+    
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        if let Some(note) = self.coverage_note() {
+            let _ = writeln!(body, "<p class=\"note\">{}</p>", escape_html(&note));
+        }
+        
+        body.push_str("<table>\n");
+        for (label, value) in self.highlights() {
+            let _ = writeln!(body, "<tr><th>{}</th><td>{}</td></tr>", label, escape_html(&value));
+        }
+        body.push_str("</table>\n<h2>Top keys</h2>\n");
+        if self.top_keys.is_empty() {
+            body.push_str("<p>No per-key counts were kept for this year.</p>\n");
+        } else {
+            body.push_str("<table>\n<tr><th>#</th><th>Key</th><th>Presses</th></tr>\n");
+            for (rank, (key, count)) in self.top_keys.iter().enumerate() {
+                let _ = writeln!(body, "<tr><td>{}</td><td><kbd>{}</kbd></td><td>{}</td></tr>", rank + 1, escape_html(key), count);
+            }
+            body.push_str("</table>\n");
+        }
+        
+        body.push_str("<h2>Month by month</h2>\n<table>\n<tr><th>Month</th><th>Keys</th><th>Clicks</th><th>Distance</th></tr>\n");
+        for row in &self.months {
+            let _ = writeln!(
+                body,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2} km</td></tr>",
+                MONTH_NAMES[row.month as usize - 1], row.keys, row.clicks, row.distance_km
+            );
+        }
+        body.push_str("</table>\n");
+        
+        HTML_TEMPLATE
+            .replace("{title}", &format!("Finger Monitor: {} in Review", self.year))
+            .replace("{body}", &body)
+    }
+}
+
+const HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 720px; margin: 2rem auto; padding: 0 1rem; color: #1f2328; background: #f6f8fa; }
+h1 { font-size: 1.8rem; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0; background: #fff; }
+th, td { text-align: left; padding: 0.4rem 0.8rem; border-bottom: 1px solid #d0d7de; }
+kbd { font-family: ui-monospace, monospace; padding: 0.1rem 0.4rem; border: 1px solid #d0d7de; border-radius: 4px; background: #f6f8fa; }
+.note { color: #59636e; font-style: italic; }
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}</body>
+</html>
+"#;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Summarize the days of `year` in `stats`
+pub fn year_review(stats: &Stats, year: i32) -> YearReview {
+    let days: Vec<(NaiveDate, &DailyStats)> = stats
+        .daily_stats
+        .iter()
+        .filter_map(|(date, day)| Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, day)))
+        .filter(|(date, day)| date.year() == year && (day.total_keys > 0 || day.total_clicks > 0 || day.total_distance > 0.0))
+        .collect();
+    
+    let mut total = DailyStats::default();
+    let mut months: HashMap<u32, DailyStats> = HashMap::new();
+    for (date, day) in &days {
+        total.add(day);
+        months.entry(date.month()).or_default().add(day);
+    }
+    let mut months: Vec<MonthRow> = months
+        .into_iter()
+        .map(|(month, day)| MonthRow {
+            month,
+            keys: day.total_keys,
+            clicks: day.total_clicks,
+            distance_km: day.total_distance / 1_000_000.0,
+        })
+        .collect();
+    months.sort_by_key(|row| row.month);
+    
+    let mut key_counts: HashMap<&str, u64> = HashMap::new();
+    for (date, counts) in &stats.daily_key_counts {
+        if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|date| date.year() == year) {
+            for (key, count) in counts {
+                *key_counts.entry(key.as_str()).or_insert(0) += count;
+            }
+        }
+    }
+    let mut top_keys: Vec<(String, u64)> = key_counts.into_iter().map(|(key, count)| (key.to_string(), count)).collect();
+    top_keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_keys.truncate(TOP_KEYS);
+    
+    YearReview {
+        year,
+        covered: days.iter().map(|(date, _)| *date).min().zip(days.iter().map(|(date, _)| *date).max()),
+        total_keys: total.total_keys,
+        total_clicks: total.total_clicks,
+        distance_km: total.total_distance / 1_000_000.0,
+        busiest_day: days
+            .iter()
+            .filter(|(_, day)| day.total_keys > 0)
+            .max_by_key(|(date, day)| (day.total_keys, std::cmp::Reverse(*date)))
+            .map(|(date, day)| (*date, day.total_keys)),
+        busiest_hour: peak_hour(&total),
+        top_keys,
+        months,
+    }
+}
+
+/// Write the review of `year` to `path`, as HTML or Markdown by its extension
+pub fn write_year_review(stats: &Stats, year: i32, path: &Path) -> io::Result<()> {
+    std::fs::write(path, year_review(stats, year).render(ReportFormat::from_path(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(keys: u64, clicks: u64, distance: f64, hour: u8) -> DailyStats {
+        DailyStats {
+            total_keys: keys,
+            total_clicks: clicks,
+            total_distance: distance,
+            hourly_keys: HashMap::from([(hour, keys)]),
+            hourly_clicks: HashMap::from([(hour, clicks)]),
+            ..Default::default()
+        }
+    }
+
+    fn seeded_stats() -> Stats {
+        let mut stats = Stats::new();
+        stats.daily_stats.insert("2023-12-31".to_string(), day(9_999, 0, 0.0, 3));
+        stats.daily_stats.insert("2024-03-10".to_string(), day(1_000, 100, 500_000.0, 9));
+        stats.daily_stats.insert("2024-03-12".to_string(), day(3_000, 50, 1_500_000.0, 14));
+        stats.daily_stats.insert("2024-05-01".to_string(), day(2_000, 10, 250_000.0, 14));
+        stats.daily_key_counts.insert("2024-03-10".to_string(), HashMap::from([("E".to_string(), 40), ("<".to_string(), 5)]));
+        stats.daily_key_counts.insert("2024-05-01".to_string(), HashMap::from([("E".to_string(), 2), ("A".to_string(), 30)]));
+        stats.daily_key_counts.insert("2023-12-31".to_string(), HashMap::from([("Z".to_string(), 900)]));
+        stats
+    }
+
+    #[test]
+    fn review_aggregates_only_the_requested_year() {
+        let review = year_review(&seeded_stats(), 2024);
+        assert_eq!(review.total_keys, 6_000);
+        assert_eq!(review.total_clicks, 160);
+        assert!((review.distance_km - 2.25).abs() < 1e-9);
+        assert_eq!(review.busiest_day, Some((NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(), 3_000)));
+        assert_eq!(review.busiest_hour, Some((14, 5_060)));
+        assert_eq!(review.top_keys, vec![("E".to_string(), 42), ("A".to_string(), 30), ("<".to_string(), 5)]);
+        let months: Vec<(u32, u64)> = review.months.iter().map(|row| (row.month, row.keys)).collect();
+        assert_eq!(months, vec![(3, 4_000), (5, 2_000)]);
+    }
+
+    #[test]
+    fn partial_year_states_the_covered_range() {
+        let review = year_review(&seeded_stats(), 2024);
+        assert!(review.is_partial());
+        // March 10 to May 1 is 53 days
+        assert_eq!(review.average_daily_keys(), 6_000 / 53);
+        let markdown = review.to_markdown();
+        assert!(markdown.starts_with("# Finger Monitor: 2024 in Review\n"));
+        assert!(markdown.contains("_Covers 2024-03-10 to 2024-05-01, the days with recorded activity._"));
+        assert!(markdown.contains("| Busiest day | Tuesday, March 12 (3000 keys) |"));
+        assert!(markdown.contains("| 1 | `E` | 42 |"));
+        assert!(markdown.contains("| March | 4000 | 150 | 2.00 km |"));
+
+        let mut full = seeded_stats();
+        full.daily_stats.insert("2024-01-01".to_string(), day(1, 0, 0.0, 0));
+        full.daily_stats.insert("2024-12-31".to_string(), day(1, 0, 0.0, 0));
+        assert!(!year_review(&full, 2024).is_partial());
+    }
+
+    #[test]
+    fn empty_year_has_placeholders() {
+        let review = year_review(&seeded_stats(), 2022);
+        assert_eq!(review.covered, None);
+        assert_eq!(review.average_daily_keys(), 0);
+        let markdown = review.to_markdown();
+        assert!(markdown.contains("_No activity was recorded in 2022._"));
+        assert!(markdown.contains("| Busiest day | — |"));
+        assert!(markdown.contains("No per-key counts were kept for this year."));
+    }
+
+    #[test]
+    fn html_escapes_key_names() {
+        let html = year_review(&seeded_stats(), 2024).to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Finger Monitor: 2024 in Review</title>"));
+        assert!(html.contains("<kbd>&lt;</kbd>"));
+        assert!(!html.contains("{body}"));
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert_eq!(ReportFormat::from_path(Path::new("review.HTML")), ReportFormat::Html);
+        assert_eq!(ReportFormat::from_path(Path::new("review.htm")), ReportFormat::Html);
+        assert_eq!(ReportFormat::from_path(Path::new("review.md")), ReportFormat::Markdown);
+        assert_eq!(ReportFormat::from_path(Path::new("review")), ReportFormat::Markdown);
+    }
+}
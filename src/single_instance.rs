@@ -0,0 +1,247 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::platform;
+use crate::stats::store;
+
+/// Message a second instance sends to the running one
+const SHOW_MESSAGE: &str = "show";
+
+/// Message checking that the running instance answers, without showing it
+const PING_MESSAGE: &str = "ping";
+
+/// How long to wait for the running instance to accept the message
+const SIGNAL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Outcome of trying to become the only running instance
+pub enum Startup {
+    /// No other instance is running; keep the lock until shutdown
+    First(InstanceLock),
+    /// Another instance with this process id is running, and was asked to
+    /// show its window if `acquire` was
+    Running(u32),
+}
+
+/// Process id and signal port of the instance holding the lock, as written
+/// to the lock file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LockOwner {
+    pid: u32,
+    port: u16,
+}
+
+impl LockOwner {
+    fn parse(content: &str) -> Option<Self> {
+        let mut lines = content.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let port = lines.next()?.trim().parse().ok()?;
+        Some(Self { pid, port })
+    }
+    
+    fn to_file_content(self) -> String {
+        format!("{}\n{}\n", self.pid, self.port)
+    }
+}
+
+/// `finger.lock` in the data directory, held by the running instance
+#[derive(Clone)]
+pub struct InstanceLock {
+    path: PathBuf,
+    owner: LockOwner,
+    show_requested: Arc<AtomicBool>,
+}
+
+impl InstanceLock {
+    /// Set whenever another instance asked for the window to be shown;
+    /// the UI clears it once the window is raised
+    pub fn show_requests(&self) -> Arc<AtomicBool> {
+        self.show_requested.clone()
+    }
+    
+    /// Remove the lock file, unless another instance has taken it over
+    pub fn release(&self) {
+        let ours = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| LockOwner::parse(&content))
+            .is_some_and(|owner| owner == self.owner);
+        if ours {
+            if let Err(e) = fs::remove_file(&self.path) {
+                log::warn!("Failed to remove {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Take the lock in the data directory, or find the instance holding it and
+/// ask it to show its window if `show` is set
+pub fn acquire(show: bool) -> io::Result<Startup> {
+    acquire_at(&store::data_dir().join("finger.lock"), show)
+}
+
+fn acquire_at(path: &Path, show: bool) -> io::Result<Startup> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let owner = LockOwner { pid: std::process::id(), port: listener.local_addr()?.port() };
+    
+    // A stale lock is removed and the lock taken on the second attempt
+    for _ in 0..2 {
+        if create_lock_file(path, owner)? {
+            let show_requested = Arc::new(AtomicBool::new(false));
+            spawn_signal_listener(listener, show_requested.clone());
+            return Ok(Startup::First(InstanceLock { path: path.to_path_buf(), owner, show_requested }));
+        }
+        
+        // An unreadable lock is as stale as one whose process has exited
+        match fs::read_to_string(path).ok().and_then(|content| LockOwner::parse(&content)) {
+            Some(existing) if !is_stale(&existing, platform::process_alive) => match signal(existing.port, show) {
+                Ok(()) => return Ok(Startup::Running(existing.pid)),
+                // The id was reused by an unrelated process, or ours hangs
+                Err(e) => log::warn!("Instance {} holding {} does not answer ({}), taking over", existing.pid, path.display(), e),
+            },
+            _ => log::info!("Removing stale lock {}", path.display()),
+        }
+        match fs::remove_file(path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} keeps being recreated", path.display())))
+}
+
+/// Whether the lock was left behind by an instance whose process is known to
+/// have exited. A process whose state cannot be checked counts as running.
+fn is_stale(owner: &LockOwner, process_alive: impl Fn(u32) -> Option<bool>) -> bool {
+    process_alive(owner.pid) == Some(false)
+}
+
+/// Atomically create the lock file with the owner written to it. Returns
+/// `false` if the file already exists.
+///
+/// The content goes to a temp file first that is then hard-linked into
+/// place, so another instance never reads a half-written lock.
+fn create_lock_file(path: &Path, owner: LockOwner) -> io::Result<bool> {
+    let tmp_path = store::sibling_path(path, &format!("{}.tmp", owner.pid));
+    fs::write(&tmp_path, owner.to_file_content())?;
+    let linked = fs::hard_link(&tmp_path, path);
+    let _ = fs::remove_file(&tmp_path);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Reach the instance listening on `port`, asking it to show its window if
+/// `show` is set
+fn signal(port: u16, show: bool) -> io::Result<()> {
+    let mut stream = TcpStream::connect_timeout(&(Ipv4Addr::LOCALHOST, port).into(), SIGNAL_TIMEOUT)?;
+    stream.set_write_timeout(Some(SIGNAL_TIMEOUT))?;
+    writeln!(stream, "{}", if show { SHOW_MESSAGE } else { PING_MESSAGE })
+}
+
+/// Set `show_requested` for every show message arriving on `listener`
+fn spawn_signal_listener(listener: TcpListener, show_requested: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(SIGNAL_TIMEOUT));
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_ok() && line.trim() == SHOW_MESSAGE {
+                log::info!("Another instance was started, showing the window");
+                show_requested.store(true, Ordering::Relaxed);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_lock_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-finger-lock-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("finger.lock")
+    }
+
+    #[test]
+    fn parses_lock_owner() {
+        assert_eq!(LockOwner::parse("1234\n5678\n"), Some(LockOwner { pid: 1234, port: 5678 }));
+        let owner = LockOwner { pid: 1, port: 2 };
+        assert_eq!(LockOwner::parse(&owner.to_file_content()), Some(owner));
+        assert_eq!(LockOwner::parse(""), None);
+        assert_eq!(LockOwner::parse("1234"), None);
+        assert_eq!(LockOwner::parse("pid\nport"), None);
+    }
+
+    #[test]
+    fn stale_when_the_process_exited() {
+        let owner = LockOwner { pid: 42, port: 1 };
+        assert!(is_stale(&owner, |_| Some(false)));
+        assert!(!is_stale(&owner, |_| Some(true)));
+        assert!(!is_stale(&owner, |pid| Some(pid == 42)));
+        // Unknown liveness is left to the signal to decide
+        assert!(!is_stale(&owner, |_| None));
+        assert!(!is_stale(&LockOwner { pid: std::process::id(), port: 1 }, platform::process_alive));
+    }
+
+    #[test]
+    fn takes_over_stale_lock() {
+        let path = test_lock_path("stale");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        // Left by a process that exited, and an unreadable file
+        for stale in ["4294967294\n1\n", "garbage"] {
+            fs::write(&path, stale).unwrap();
+            let Startup::First(lock) = acquire_at(&path, true).unwrap() else {
+                panic!("stale lock {:?} was not taken over", stale);
+            };
+            let owner = LockOwner::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+            assert_eq!(owner.pid, std::process::id());
+
+            lock.release();
+            assert!(!path.exists());
+        }
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn second_instance_signals_the_first() {
+        let path = test_lock_path("second");
+        let Startup::First(lock) = acquire_at(&path, true).unwrap() else {
+            panic!("first instance did not get the lock");
+        };
+        let show_requested = lock.show_requests();
+
+        // A command run alongside finds the instance without raising it
+        match acquire_at(&path, false).unwrap() {
+            Startup::Running(pid) => assert_eq!(pid, std::process::id()),
+            Startup::First(_) => panic!("lock was taken twice"),
+        }
+        thread::sleep(Duration::from_millis(100));
+        assert!(!show_requested.load(Ordering::Relaxed));
+
+        match acquire_at(&path, true).unwrap() {
+            Startup::Running(pid) => assert_eq!(pid, std::process::id()),
+            Startup::First(_) => panic!("lock was taken twice"),
+        }
+        for _ in 0..100 {
+            if show_requested.load(Ordering::Relaxed) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(show_requested.load(Ordering::Relaxed));
+
+        lock.release();
+        assert!(!path.exists());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
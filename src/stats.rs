@@ -1,25 +1,95 @@
-use chrono::{DateTime, Local, NaiveDate};
+pub mod achievements;
+pub mod autosave;
+pub mod bigrams;
+pub mod categories;
+pub mod clock;
+pub mod daily_report;
+pub mod event_log;
+pub mod fingers;
+pub mod hands;
+pub mod key_aliases;
+pub mod migrate;
+pub mod mouse_grid;
+pub mod multi_click;
+pub mod sessions;
+pub mod store;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock, atomic::{AtomicBool, Ordering}};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::thread;
 use std::time::{Duration, Instant};
 
+use crate::platform::{ActiveAppCache, FullscreenCache};
+use crate::format::{format_count, format_distance};
+use achievements::{Measure, Milestone};
+use autosave::AutosaveSchedule;
+use bigrams::BigramTracker;
+use categories::{KeyCategory, PrivacyBucket};
+use clock::{Clock, SharedClock, SystemClock};
+use event_log::EventLog;
+use fingers::{Attribution, Finger, FingerMap};
+use hands::Hand;
+use key_aliases::KeyAliases;
+use mouse_grid::PositionSampler;
+use multi_click::{ClickTracker, DEFAULT_MULTI_CLICK_WINDOW};
+use sessions::SessionRecord;
+use store::{EventKind, JsonStore, StatsStore, StoredEvent};
+
+/// Version of the persisted stats format understood by this build
+pub const SCHEMA_VERSION: u32 = 2;
+
 /// Statistics data that can be persisted
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct Stats {
-    /// Key press counts per key name
+    /// Format version of the persisted data, see `migrate`
+    pub schema_version: u32,
+    
+    /// Key press counts per key name (physical presses only)
     pub key_counts: HashMap<String, u64>,
     
+    /// OS auto-repeat events per key name, generated while a key is held
+    pub key_repeats: HashMap<String, u64>,
+    
     /// Mouse button click counts (left, right, middle, etc.)
     pub mouse_clicks: HashMap<String, u64>,
     
+    /// Double and triple clicks per button, part of `mouse_clicks`: a triple
+    /// click counts three clicks there and one triple click here
+    pub double_clicks: HashMap<String, u64>,
+    pub triple_clicks: HashMap<String, u64>,
+    
     /// Total mouse movement distance in pixels
     pub mouse_distance: f64,
     
-    /// Total scroll distance
-    pub scroll_distance: i64,
+    /// Part of `mouse_distance` moved while a mouse button was held, in pixels
+    pub drag_distance: f64,
+    
+    /// Press-to-release cycles of the mouse buttons that moved more than
+    /// `DRAG_THRESHOLD` pixels
+    pub drag_count: u64,
+    
+    /// Time spent typing or clicking, in seconds: the pauses between
+    /// consecutive actions, unless longer than `ACTIVE_GAP`
+    pub total_active_seconds: f64,
+    
+    /// Cursor samples per screen cell, `mouse_grid::GRID_COLUMNS` per row
+    /// (empty until the cursor was first seen)
+    #[serde(with = "mouse_grid::serde_sparse")]
+    pub mouse_position_grid: Vec<u64>,
+    
+    /// Clicks per screen cell, on the same grid
+    #[serde(with = "mouse_grid::serde_sparse")]
+    pub click_position_grid: Vec<u64>,
+    
+    /// Total scroll distance upwards and downwards, in wheel notches
+    /// (the listener normalizes platform deltas to notches)
+    pub scroll_up: i64,
+    pub scroll_down: i64,
     
     /// Hourly statistics (hour 0-23 -> counts)
     pub hourly_key_counts: HashMap<u8, u64>,
@@ -28,266 +98,3990 @@ pub struct Stats {
     /// Daily statistics
     pub daily_stats: HashMap<String, DailyStats>,
     
+    /// Streaks among the days dropped from `daily_stats` by the retention
+    /// setting, per minimum of keys a day. A minimum first used after days
+    /// were dropped only counts the days kept.
+    pub pruned_streaks: HashMap<u64, PrunedStreak>,
+    
+    /// Key presses per day (same date keys as `daily_stats`) and key name,
+    /// for the recency-weighted heatmap. This costs one entry per distinct
+    /// key per active day, so days older than `DAILY_KEY_RETENTION_DAYS`
+    /// are dropped when the day rolls over.
+    pub daily_key_counts: HashMap<String, HashMap<String, u64>>,
+    
+    /// Key presses and clicks per foreground application
+    pub app_key_counts: HashMap<String, u64>,
+    pub app_click_counts: HashMap<String, u64>,
+    
+    /// Key presses and clicks per input device, and when each device was last
+    /// used. Backends that cannot tell devices apart record under `DEFAULT_DEVICE`.
+    pub device_key_counts: HashMap<String, u64>,
+    pub device_click_counts: HashMap<String, u64>,
+    pub device_last_seen: HashMap<String, DateTime<Local>>,
+    
+    /// Consecutive key pairs typed within `bigrams::BIGRAM_WINDOW`
+    #[serde(with = "bigrams::serde_keys")]
+    pub bigram_counts: HashMap<(String, String), u64>,
+    
+    /// Key presses recorded without key identities (privacy mode)
+    pub anonymous_keys: u64,
+    
+    /// Breakdown of `anonymous_keys` into coarse categories
+    pub category_counts: HashMap<PrivacyBucket, u64>,
+    
+    /// When enabled, key names are not stored, only aggregate counts
+    pub privacy_mode: bool,
+    
+    /// Periods during which key identities were not collected
+    pub privacy_periods: Vec<PrivacyPeriod>,
+    
+    /// Last finished day whose summary was announced, so it is announced once
+    pub last_daily_report: Option<String>,
+    
+    /// Milestones unlocked so far, by id, with when they were reached
+    pub achieved: HashMap<String, DateTime<Local>>,
+    
+    /// Summaries of past sessions, oldest first, at most
+    /// `sessions::SESSION_HISTORY_LIMIT`
+    pub sessions: Vec<SessionRecord>,
+    
     /// Session start time
     #[serde(skip)]
     pub session_start: Option<Instant>,
     
-    /// Keys pressed in current minute (for WPM calculation)
+    /// Key presses per key since the app started, for the session heatmap
+    #[serde(skip)]
+    pub session_key_counts: HashMap<String, u64>,
+    
+    /// Totals of the running session, stored in `sessions` by `checkpoint_session`
+    #[serde(skip)]
+    pub current_session: Option<SessionRecord>,
+    
+    /// Character keys pressed in current minute (for WPM calculation)
     #[serde(skip)]
     pub recent_keys: Vec<Instant>,
+    
+    /// Key presses and clicks in current minute (for APM calculation)
+    #[serde(skip)]
+    pub recent_actions: Vec<Instant>,
+    
+    /// Backspace presses in current minute (for the corrected WPM)
+    #[serde(skip)]
+    pub recent_corrections: Vec<Instant>,
+    
+    /// Characters per word for the WPM, `DEFAULT_WORD_LENGTH` if unset
+    #[serde(skip)]
+    pub word_length: Option<f64>,
+    
+    /// Exponential moving average of `current_wpm`, for display
+    #[serde(skip)]
+    pub smoothed_wpm: f64,
+    
+    /// `current_wpm` sampled every `WPM_SAMPLE_INTERVAL`, covering at most `WPM_HISTORY_SPAN`
+    #[serde(skip)]
+    wpm_history: VecDeque<(Instant, f64)>,
+    
+    /// Day that new activity is recorded under; advanced by `roll_over`.
+    /// `None` follows the system date.
+    #[serde(skip)]
+    pub current_date: Option<NaiveDate>,
+    
+    /// Time source, the system clock unless replaced in tests
+    #[serde(skip)]
+    pub clock: SharedClock,
+}
+
+/// Default weight of the newest value in the smoothed WPM
+pub const DEFAULT_WPM_SMOOTHING: f64 = 0.1;
+
+/// Average word length in characters used to turn keystrokes into WPM
+pub const DEFAULT_WORD_LENGTH: f64 = 5.0;
+
+/// Window over which the current WPM and APM are measured
+const WPM_WINDOW: Duration = Duration::from_secs(60);
+
+/// Window over which the typing intensity is measured
+const INTENSITY_WINDOW: Duration = Duration::from_secs(3);
+
+/// Character keys per second at which the typing intensity is full (about 100 WPM)
+const INTENSITY_FULL_RATE: f64 = 8.0;
+
+/// Days of per-key counts kept in `daily_key_counts`; with the default
+/// half-life a day this old weighs less than a millionth of today
+pub const DAILY_KEY_RETENTION_DAYS: u64 = 365;
+
+/// Default half-life in days of the recency-weighted key counts
+pub const DEFAULT_RECENT_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Default key presses a day needs to count towards a streak
+pub const DEFAULT_STREAK_MIN_KEYS: u64 = 1;
+
+/// Mouse buttons the listener has names for
+const NAMED_BUTTONS: [&str; 5] = ["Left", "Right", "Middle", "Back", "Forward"];
+
+/// Movement in pixels while a button is held above which a click counts as a drag
+pub const DRAG_THRESHOLD: f64 = 5.0;
+
+/// Default longest cursor step in pixels counted as travel; longer ones are
+/// jumps between screens or warps
+pub const DEFAULT_MAX_MOVEMENT: f64 = 3000.0;
+
+/// Device name used by backends that cannot tell input devices apart
+pub const DEFAULT_DEVICE: &str = "default";
+
+/// Fewest days `StatsManager::set_retention_days` keeps, so the calendar,
+/// the charts and today's comparison with yesterday stay complete
+pub const MIN_RETENTION_DAYS: u32 = 31;
+
+/// Longest pause between two actions that still counts as active time
+const ACTIVE_GAP: Duration = Duration::from_secs(30);
+
+/// Default window in which a repeated identical key press or click is dropped
+pub const DEFAULT_DEDUP_WINDOW: Duration = Duration::from_millis(50);
+
+/// Longest gap between two events of a held key; a press after a longer gap
+/// is a new press whose release was lost. Above the usual OS repeat delay.
+const HOLD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Gap below which a second press of a held key is a copy of the same
+/// press, e.g. from both the global listener and the focused dashboard.
+/// Shorter than the fastest OS auto-repeat interval.
+const DUPLICATE_PRESS_WINDOW: Duration = Duration::from_millis(15);
+
+/// Interval between two samples of the WPM history
+const WPM_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How far back the WPM history reaches
+const WPM_HISTORY_SPAN: Duration = Duration::from_secs(60 * 60);
+
+/// Lines moved by one wheel notch with default system settings
+const LINES_PER_NOTCH: f64 = 3.0;
+
+/// Estimated text lines visible in one screen-height
+const LINES_PER_SCREEN: f64 = 40.0;
+
+/// Unit in which the scroll distance is shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollUnit {
+    /// Raw wheel notches as reported by the platform
+    Notches,
+    /// Text lines, at three lines per notch
+    Lines,
+    /// Estimated screen-heights scrolled
+    #[default]
+    Screens,
+}
+
+/// A span of time recorded in privacy mode (`end` is `None` while ongoing)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPeriod {
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+}
+
+/// Why input is currently not being recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    /// An application from the excluded list has focus
+    ExcludedApp,
+    /// A fullscreen window has focus and pausing in fullscreen is enabled
+    Fullscreen,
+}
+
+/// Outcome of renaming the recorded keys to their aliases
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeReport {
+    /// Copy of the stats made before renaming, `None` if nothing was
+    /// renamed or nothing had been saved yet
+    pub backup: Option<PathBuf>,
+    /// Renamed keys as (old name, new name, count), sorted by old name
+    pub renamed: Vec<(String, String, u64)>,
+}
+
+impl std::fmt::Display for NormalizeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.renamed.is_empty() {
+            return write!(f, "No keys to rename");
+        }
+        if let Some(backup) = &self.backup {
+            writeln!(f, "Backup:  {}", backup.display())?;
+        }
+        write!(f, "Renamed: {} key(s)", self.renamed.len())?;
+        for (from, to, count) in &self.renamed {
+            write!(f, "\n  {} -> {} ({})", from, to, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Usage totals of one input device
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceUsage {
+    pub name: String,
+    pub keys: u64,
+    pub clicks: u64,
+    pub last_seen: Option<DateTime<Local>>,
+}
+
+/// Outcome of merging another stats file
+#[derive(Debug, Clone, Default)]
+pub struct MergeReport {
+    /// Key presses added from the other file
+    pub keys_added: u64,
+    /// Mouse clicks added from the other file
+    pub clicks_added: u64,
+    /// Days present in the other file
+    pub days_merged: usize,
+    /// Dates present in both files, whose totals were added together
+    pub overlapping_days: Vec<String>,
+}
+
+impl std::fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Keys added:   {}", self.keys_added)?;
+        writeln!(f, "Clicks added: {}", self.clicks_added)?;
+        writeln!(f, "Days merged:  {}", self.days_merged)?;
+        write!(f, "Conflicts:    {} overlapping day(s)", self.overlapping_days.len())?;
+        if !self.overlapping_days.is_empty() {
+            write!(f, " ({})", self.overlapping_days.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Streak state of the days dropped from the daily history, for one
+/// minimum of keys a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunedStreak {
+    /// Latest day dropped so far
+    pub last_day: NaiveDate,
+    /// Active days in a row ending on `last_day`
+    pub run: u32,
+    /// Longest run of active days among the dropped days
+    pub longest: u32,
+}
+
+impl PrunedStreak {
+    /// State before any day was dropped
+    fn empty() -> Self {
+        Self { last_day: NaiveDate::MIN, run: 0, longest: 0 }
+    }
+    
+    /// Take in the days dropped up to `last_day`, given the ones with at least
+    /// the minimum of keys. Days up to the previous `last_day` are skipped.
+    fn advance(&mut self, active: impl Iterator<Item = NaiveDate>, last_day: NaiveDate) {
+        let mut previous = (self.run > 0).then_some(self.last_day);
+        for day in active.filter(|day| *day > self.last_day && *day <= last_day) {
+            self.run = match previous.and_then(|p| p.succ_opt()) {
+                Some(next) if next == day => self.run + 1,
+                _ => 1,
+            };
+            self.longest = self.longest.max(self.run);
+            previous = Some(day);
+        }
+        if previous != Some(last_day) {
+            self.run = 0;
+        }
+        self.last_day = self.last_day.max(last_day);
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct DailyStats {
     pub total_keys: u64,
     pub total_clicks: u64,
     pub total_distance: f64,
+    /// Highest typing speed reached during the day
+    pub peak_wpm: f64,
+    /// Highest actions per minute reached during the day
+    pub peak_apm: f64,
+    /// Backspace and Delete presses, for the correction rate
+    pub backspaces: u64,
+    /// Drags and the distance moved while dragging
+    pub drag_count: u64,
+    pub drag_distance: f64,
+    /// Seconds between actions at most `ACTIVE_GAP` apart
+    pub active_seconds: f64,
+    /// Key presses and clicks per hour of the day
+    pub hourly_keys: HashMap<u8, u64>,
+    pub hourly_clicks: HashMap<u8, u64>,
+}
+
+impl DailyStats {
+    /// Add another day's activity: counts are summed, peaks take the higher value
+    pub fn add(&mut self, other: &DailyStats) {
+        self.total_keys += other.total_keys;
+        self.total_clicks += other.total_clicks;
+        self.total_distance += other.total_distance;
+        self.backspaces += other.backspaces;
+        self.drag_count += other.drag_count;
+        self.drag_distance += other.drag_distance;
+        self.active_seconds += other.active_seconds;
+        self.peak_wpm = self.peak_wpm.max(other.peak_wpm);
+        self.peak_apm = self.peak_apm.max(other.peak_apm);
+        add_counts(&mut self.hourly_keys, &other.hourly_keys);
+        add_counts(&mut self.hourly_clicks, &other.hourly_clicks);
+    }
+}
+
+/// Days of an inclusive date range with their combined totals, as written by
+/// `StatsManager::export_range`
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeExport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Sums over all days in the range (peaks are the highest of any day)
+    pub totals: DailyStats,
+    pub daily_stats: BTreeMap<String, DailyStats>,
 }
 
 impl Stats {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+    
+    /// Empty stats reading the time from `clock`
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
-            session_start: Some(Instant::now()),
+            schema_version: SCHEMA_VERSION,
+            session_start: Some(clock.now_instant()),
+            current_session: Some(SessionRecord::new(clock.now_local())),
+            clock: SharedClock(clock),
             ..Default::default()
         }
     }
     
     /// Record a key press event
     pub fn record_key(&mut self, key_name: String) {
-        // Update key count
-        *self.key_counts.entry(key_name).or_insert(0) += 1;
+        let is_character = categories::produces_character(&key_name);
+        let is_correction = key_name == "Backspace";
+        let is_deletion = is_deletion_key(&key_name);
+        
+        // Update key count (only the total in privacy mode)
+        if self.privacy_mode {
+            self.anonymous_keys += 1;
+            if let Some(bucket) = PrivacyBucket::of(&key_name) {
+                *self.category_counts.entry(bucket).or_insert(0) += 1;
+            }
+        } else {
+            *self.daily_key_counts.entry(self.today_key()).or_default().entry(key_name.clone()).or_insert(0) += 1;
+            *self.session_key_counts.entry(key_name.clone()).or_insert(0) += 1;
+            *self.key_counts.entry(key_name).or_insert(0) += 1;
+        }
         
         // Update hourly stats
-        let hour = Local::now().hour() as u8;
+        let hour = self.clock.now_local().hour() as u8;
         *self.hourly_key_counts.entry(hour).or_insert(0) += 1;
         
+        // Track recent character keys for WPM; shortcuts, navigation and
+        // corrections would otherwise inflate it
+        let now = self.clock.now_instant();
+        self.recent_keys.retain(|t| now.duration_since(*t) < WPM_WINDOW);
+        self.recent_corrections.retain(|t| now.duration_since(*t) < WPM_WINDOW);
+        if is_character {
+            self.recent_keys.push(now);
+        } else if is_correction {
+            self.recent_corrections.push(now);
+        }
+        let wpm = self.current_wpm();
+        let apm = self.record_action(now);
+        
         // Update daily stats
-        let date = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .entry(date)
-            .or_insert_with(DailyStats::default)
-            .total_keys += 1;
+        let day = self.daily_stats.entry(self.today_key()).or_default();
+        day.total_keys += 1;
+        if is_deletion {
+            day.backspaces += 1;
+        }
+        day.peak_wpm = day.peak_wpm.max(wpm);
+        day.peak_apm = day.peak_apm.max(apm);
+        *day.hourly_keys.entry(hour).or_insert(0) += 1;
         
-        // Track recent keys for WPM
-        let now = Instant::now();
-        self.recent_keys.retain(|t| now.duration_since(*t) < Duration::from_secs(60));
-        self.recent_keys.push(now);
+        if let Some(session) = &mut self.current_session {
+            session.keys += 1;
+            session.peak_wpm = session.peak_wpm.max(wpm);
+        }
+    }
+    
+    /// Record an auto-repeat event for a key that is being held down
+    pub fn record_repeat(&mut self, key_name: String) {
+        if self.privacy_mode {
+            return;
+        }
+        *self.key_repeats.entry(key_name).or_insert(0) += 1;
     }
     
     /// Record a mouse click event
     pub fn record_click(&mut self, button: String) {
         *self.mouse_clicks.entry(button).or_insert(0) += 1;
         
-        let hour = Local::now().hour() as u8;
+        let hour = self.clock.now_local().hour() as u8;
         *self.hourly_click_counts.entry(hour).or_insert(0) += 1;
         
-        let date = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .entry(date)
-            .or_insert_with(DailyStats::default)
-            .total_clicks += 1;
-    }
-    
-    /// Record mouse movement
-    pub fn record_movement(&mut self, distance: f64) {
-        self.mouse_distance += distance;
+        let apm = self.record_action(self.clock.now_instant());
         
-        let date = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .entry(date)
-            .or_insert_with(DailyStats::default)
-            .total_distance += distance;
+        let day = self.daily_stats.entry(self.today_key()).or_default();
+        day.total_clicks += 1;
+        day.peak_apm = day.peak_apm.max(apm);
+        *day.hourly_clicks.entry(hour).or_insert(0) += 1;
+        
+        if let Some(session) = &mut self.current_session {
+            session.clicks += 1;
+        }
     }
     
-    /// Record scroll event
-    pub fn record_scroll(&mut self, delta: i64) {
-        self.scroll_distance += delta.abs();
+    /// Add a key press or click to the APM window, returning the new APM
+    fn record_action(&mut self, now: Instant) -> f64 {
+        if let Some(last) = self.recent_actions.last() {
+            let pause = now.duration_since(*last);
+            if pause <= ACTIVE_GAP {
+                self.total_active_seconds += pause.as_secs_f64();
+                self.daily_stats.entry(self.today_key()).or_default().active_seconds += pause.as_secs_f64();
+            }
+        }
+        self.recent_actions.retain(|t| now.duration_since(*t) < WPM_WINDOW);
+        self.recent_actions.push(now);
+        self.current_apm()
     }
     
-    /// Calculate current typing speed (words per minute)
-    /// Assumes average word length of 5 characters
-    pub fn current_wpm(&self) -> f64 {
-        let now = Instant::now();
-        let keys_in_minute: usize = self.recent_keys
-            .iter()
-            .filter(|t| now.duration_since(**t) < Duration::from_secs(60))
-            .count();
-        
-        // Characters per minute / 5 = WPM
-        keys_in_minute as f64 / 5.0
+    /// Record two keys typed in quick succession
+    pub fn record_bigram(&mut self, first: String, second: String) {
+        *self.bigram_counts.entry((first, second)).or_insert(0) += 1;
     }
     
-    /// Get total key presses for today
-    pub fn today_keys(&self) -> u64 {
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .get(&today)
-            .map(|s| s.total_keys)
-            .unwrap_or(0)
+    /// Attribute a key press to an application
+    pub fn record_app_key(&mut self, app: String) {
+        *self.app_key_counts.entry(app).or_insert(0) += 1;
     }
     
-    /// Get total clicks for today
-    pub fn today_clicks(&self) -> u64 {
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .get(&today)
-            .map(|s| s.total_clicks)
-            .unwrap_or(0)
+    /// Attribute a mouse click to an application
+    pub fn record_app_click(&mut self, app: String) {
+        *self.app_click_counts.entry(app).or_insert(0) += 1;
     }
     
-    /// Get total mouse distance for today
-    pub fn today_distance(&self) -> f64 {
-        let today = Local::now().format("%Y-%m-%d").to_string();
-        self.daily_stats
-            .get(&today)
-            .map(|s| s.total_distance)
-            .unwrap_or(0.0)
+    /// Attribute a key press to an input device
+    pub fn record_device_key(&mut self, device: String) {
+        *self.device_key_counts.entry(device.clone()).or_insert(0) += 1;
+        self.device_last_seen.insert(device, self.clock.now_local());
     }
     
-    /// Get top N most pressed keys
-    pub fn top_keys(&self, n: usize) -> Vec<(String, u64)> {
-        let mut sorted: Vec<_> = self.key_counts.iter()
-            .map(|(k, v)| (k.clone(), *v))
-            .collect();
-        sorted.sort_by(|a, b| b.1.cmp(&a.1));
-        sorted.truncate(n);
-        sorted
+    /// Attribute a mouse click to an input device
+    pub fn record_device_click(&mut self, device: String) {
+        *self.device_click_counts.entry(device.clone()).or_insert(0) += 1;
+        self.device_last_seen.insert(device, self.clock.now_local());
     }
     
-    /// Get session duration
-    pub fn session_duration(&self) -> Duration {
-        self.session_start
-            .map(|start| start.elapsed())
-            .unwrap_or_default()
-    }
-}
-
-/// Thread-safe statistics manager
-#[derive(Clone)]
-pub struct StatsManager {
-    stats: Arc<RwLock<Stats>>,
-    data_path: PathBuf,
-    pub listener_active: Arc<AtomicBool>,
-    pub last_error: Arc<RwLock<Option<String>>>,
-    // Deduplication state
-    last_key: Arc<RwLock<Option<(String, Instant)>>>,
-    last_click: Arc<RwLock<Option<(String, Instant)>>>,
-}
-
-impl StatsManager {
-    pub fn new() -> Self {
-        let data_path = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("rust-finger")
-            .join("stats.json");
-        
-        // Ensure directory exists
-        if let Some(parent) = data_path.parent() {
-            let _ = fs::create_dir_all(parent);
+    /// Record mouse movement, `dragging` while a mouse button is held
+    pub fn record_movement(&mut self, distance: f64, dragging: bool) {
+        self.mouse_distance += distance;
+        if dragging {
+            self.drag_distance += distance;
         }
         
-        // Load existing stats or create new
-        let stats = Self::load_from_file(&data_path).unwrap_or_else(|_| Stats::new());
+        let day = self.daily_stats.entry(self.today_key()).or_default();
+        day.total_distance += distance;
+        if dragging {
+            day.drag_distance += distance;
+        }
         
-        Self {
-            stats: Arc::new(RwLock::new(stats)),
-            data_path,
-            listener_active: Arc::new(AtomicBool::new(false)),
-            last_error: Arc::new(RwLock::new(None)),
-            last_key: Arc::new(RwLock::new(None)),
-            last_click: Arc::new(RwLock::new(None)),
+        if let Some(session) = &mut self.current_session {
+            session.distance += distance;
         }
     }
     
-    pub fn set_listener_active(&self, active: bool) {
-        self.listener_active.store(active, Ordering::SeqCst);
+    /// Record a completed drag
+    pub fn record_drag(&mut self) {
+        self.drag_count += 1;
+        self.daily_stats.entry(self.today_key()).or_default().drag_count += 1;
     }
     
-    pub fn set_listener_error(&self, error: String) {
-        if let Ok(mut lock) = self.last_error.write() {
-            *lock = Some(error);
+    /// Count the click completing a double (`run` 2) or triple (`run` 3)
+    /// click. Each run counts once, under its final length: a triple click
+    /// takes back the double click its second press completed.
+    pub fn record_multi_click(&mut self, button: &str, run: u32) {
+        match run {
+            2 => *self.double_clicks.entry(button.to_string()).or_insert(0) += 1,
+            3 => {
+                if let Some(doubles) = self.double_clicks.get_mut(button) {
+                    *doubles = doubles.saturating_sub(1);
+                    if *doubles == 0 {
+                        self.double_clicks.remove(button);
+                    }
+                }
+                *self.triple_clicks.entry(button.to_string()).or_insert(0) += 1;
+            }
+            _ => {}
         }
     }
     
-    pub fn is_listener_active(&self) -> bool {
-        self.listener_active.load(Ordering::SeqCst)
-    }
-    
-    pub fn get_listener_error(&self) -> Option<String> {
-        self.last_error.read().ok()?.clone()
+    /// Count a cursor sample in a cell of the screen grid
+    pub fn record_mouse_position(&mut self, cell: usize) {
+        mouse_grid::increment(&mut self.mouse_position_grid, cell);
     }
     
-    fn load_from_file(path: &PathBuf) -> Result<Stats, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        let mut stats: Stats = serde_json::from_str(&content)?;
-        stats.session_start = Some(Instant::now());
-        Ok(stats)
+    /// Count a click in a cell of the screen grid
+    pub fn record_click_position(&mut self, cell: usize) {
+        mouse_grid::increment(&mut self.click_position_grid, cell);
     }
     
-    /// Save stats to file
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let stats = self.stats.read().map_err(|e| e.to_string())?;
-        let json = serde_json::to_string_pretty(&*stats)?;
-        fs::write(&self.data_path, json)?;
-        Ok(())
+    /// Record scroll event
+    pub fn record_scroll(&mut self, delta: i64) {
+        if delta > 0 {
+            self.scroll_up += delta;
+        } else {
+            self.scroll_down -= delta;
+        }
     }
     
-    /// Record a key press with deduplication
-    pub fn record_key(&self, key_name: String) {
-        // Simple deduplication (50ms window)
-        let now = Instant::now();
-        if let Ok(mut last) = self.last_key.write() {
-            if let Some((last_name, last_time)) = &*last {
-                if last_name == &key_name && now.duration_since(*last_time) < Duration::from_millis(50) {
-                    return;
+    /// Add another history (e.g. from a second machine) into this one.
+    ///
+    /// Counts are added, not deduplicated: merging the same history twice
+    /// counts it twice. The current session (start time, WPM window) is kept.
+    pub fn merge(&mut self, other: &Stats) -> MergeReport {
+        let mut report = MergeReport {
+            keys_added: other.total_keys(),
+            clicks_added: other.mouse_clicks.values().sum(),
+            ..Default::default()
+        };
+        
+        add_counts(&mut self.key_counts, &other.key_counts);
+        add_counts(&mut self.key_repeats, &other.key_repeats);
+        add_counts(&mut self.bigram_counts, &other.bigram_counts);
+        add_counts(&mut self.mouse_clicks, &other.mouse_clicks);
+        add_counts(&mut self.double_clicks, &other.double_clicks);
+        add_counts(&mut self.triple_clicks, &other.triple_clicks);
+        add_counts(&mut self.hourly_key_counts, &other.hourly_key_counts);
+        add_counts(&mut self.hourly_click_counts, &other.hourly_click_counts);
+        add_counts(&mut self.app_key_counts, &other.app_key_counts);
+        add_counts(&mut self.app_click_counts, &other.app_click_counts);
+        add_counts(&mut self.device_key_counts, &other.device_key_counts);
+        add_counts(&mut self.device_click_counts, &other.device_click_counts);
+        for (device, theirs) in &other.device_last_seen {
+            let seen = self.device_last_seen.entry(device.clone()).or_insert(*theirs);
+            *seen = (*seen).max(*theirs);
+        }
+        self.mouse_distance += other.mouse_distance;
+        self.total_active_seconds += other.total_active_seconds;
+        self.drag_distance += other.drag_distance;
+        self.drag_count += other.drag_count;
+        mouse_grid::add(&mut self.mouse_position_grid, &other.mouse_position_grid);
+        mouse_grid::add(&mut self.click_position_grid, &other.click_position_grid);
+        self.scroll_up += other.scroll_up;
+        self.scroll_down += other.scroll_down;
+        self.anonymous_keys += other.anonymous_keys;
+        add_counts(&mut self.category_counts, &other.category_counts);
+        self.privacy_periods.extend(other.privacy_periods.iter().cloned());
+        sessions::merge(&mut self.sessions, &other.sessions);
+        for (id, theirs) in &other.achieved {
+            let reached = self.achieved.entry(id.clone()).or_insert(*theirs);
+            *reached = (*reached).min(*theirs);
+        }
+        
+        for (min_keys, theirs) in &other.pruned_streaks {
+            let ours = self.pruned_streaks.entry(*min_keys).or_insert(*theirs);
+            ours.longest = ours.longest.max(theirs.longest);
+        }
+        for (date, theirs) in &other.daily_key_counts {
+            add_counts(self.daily_key_counts.entry(date.clone()).or_default(), theirs);
+        }
+        for (date, theirs) in &other.daily_stats {
+            report.days_merged += 1;
+            match self.daily_stats.get_mut(date) {
+                Some(ours) => {
+                    report.overlapping_days.push(date.clone());
+                    ours.add(theirs);
+                }
+                None => {
+                    self.daily_stats.insert(date.clone(), theirs.clone());
                 }
             }
-            *last = Some((key_name.clone(), now));
         }
+        report.overlapping_days.sort();
         
-        if let Ok(mut stats) = self.stats.write() {
-            stats.record_key(key_name);
-        }
+        report
     }
     
-    /// Record a mouse click with deduplication
-    pub fn record_click(&self, button: String) {
-        // Simple deduplication (50ms window)
-        let now = Instant::now();
-        if let Ok(mut last) = self.last_click.write() {
-            if let Some((last_name, last_time)) = &*last {
-                if last_name == &button && now.duration_since(*last_time) < Duration::from_millis(50) {
-                    return;
-                }
-            }
-            *last = Some((button.clone(), now));
+    /// Enable or disable privacy mode, tracking the covered period
+    pub fn set_privacy_mode(&mut self, enabled: bool) {
+        if enabled == self.privacy_mode {
+            return;
         }
+        self.privacy_mode = enabled;
         
-        if let Ok(mut stats) = self.stats.write() {
-            stats.record_click(button);
+        let now = self.clock.now_local();
+        if enabled {
+            self.privacy_periods.push(PrivacyPeriod { start: now, end: None });
+        } else if let Some(period) = self.privacy_periods.last_mut() {
+            period.end.get_or_insert(now);
         }
     }
     
-    /// Record mouse movement
-    pub fn record_movement(&self, distance: f64) {
-        if let Ok(mut stats) = self.stats.write() {
-            stats.record_movement(distance);
+    /// All-time key presses, including those recorded in privacy mode
+    pub fn total_keys(&self) -> u64 {
+        self.key_counts.values().sum::<u64>() + self.anonymous_keys
+    }
+    
+    /// All-time key presses per minute of active time, 0 before the first
+    /// active minute
+    pub fn avg_keys_per_minute(&self) -> f64 {
+        self.per_active_minute(self.total_keys())
+    }
+    
+    /// All-time clicks per minute of active time, 0 before the first active minute
+    pub fn avg_clicks_per_minute(&self) -> f64 {
+        self.per_active_minute(self.mouse_clicks.values().sum())
+    }
+    
+    fn per_active_minute(&self, count: u64) -> f64 {
+        let minutes = self.total_active_seconds / 60.0;
+        if minutes < 1.0 {
+            return 0.0;
         }
+        count as f64 / minutes
     }
     
-    /// Record scroll
-    pub fn record_scroll(&self, delta: i64) {
-        if let Ok(mut stats) = self.stats.write() {
-            stats.record_scroll(delta);
+    /// Total scroll distance in either direction, in wheel notches
+    pub fn scroll_distance(&self) -> i64 {
+        self.scroll_up + self.scroll_down
+    }
+    
+    /// Scroll distance converted to `unit`, with the unit label
+    pub fn scroll_display(&self, unit: ScrollUnit) -> String {
+        let notches = self.scroll_distance() as f64;
+        match unit {
+            ScrollUnit::Notches => format!("{} notches", self.scroll_distance()),
+            ScrollUnit::Lines => format!("{:.0} lines", notches * LINES_PER_NOTCH),
+            ScrollUnit::Screens => format!("{:.1} screens", notches * LINES_PER_NOTCH / LINES_PER_SCREEN),
         }
     }
     
-    /// Get a snapshot of current stats
-    pub fn snapshot(&self) -> Stats {
-        self.stats.read()
-            .map(|s| s.clone())
-            .unwrap_or_default()
+    /// Calculate current typing speed (words per minute) from the character
+    /// keys of the last minute
+    pub fn current_wpm(&self) -> f64 {
+        let chars = self.in_rate_window(&self.recent_keys);
+        
+        // Characters per minute / word length = WPM
+        chars as f64 / self.chars_per_word()
     }
-}
-
-use chrono::Timelike;
+    
+    /// Typing speed with every Backspace of the last minute taking back one character
+    pub fn corrected_wpm(&self) -> f64 {
+        let chars = self.in_rate_window(&self.recent_keys);
+        let corrections = self.in_rate_window(&self.recent_corrections);
+        chars.saturating_sub(corrections) as f64 / self.chars_per_word()
+    }
+    
+    /// Key presses plus clicks in the last minute (actions per minute)
+    pub fn current_apm(&self) -> f64 {
+        self.in_rate_window(&self.recent_actions) as f64
+    }
+    
+    /// How hard the user is typing right now, from 0.0 (idle) to 1.0
+    /// (`INTENSITY_FULL_RATE` or faster), over the last `INTENSITY_WINDOW`.
+    ///
+    /// Each key counts less the older it is, so the value eases off when
+    /// typing stops instead of dropping when a key leaves the window.
+    pub fn typing_intensity(&self) -> f32 {
+        let now = self.clock.now_instant();
+        let window = INTENSITY_WINDOW.as_secs_f64();
+        let weight: f64 = self.recent_keys
+            .iter()
+            .map(|t| 1.0 - now.duration_since(*t).as_secs_f64() / window)
+            .filter(|w| *w > 0.0)
+            .sum();
+        // A steady full rate fills half the window's weight
+        (weight / (INTENSITY_FULL_RATE * window / 2.0)).min(1.0) as f32
+    }
+    
+    fn in_rate_window(&self, times: &[Instant]) -> usize {
+        let now = self.clock.now_instant();
+        times.iter().filter(|t| now.duration_since(**t) < WPM_WINDOW).count()
+    }
+    
+    fn chars_per_word(&self) -> f64 {
+        self.word_length.unwrap_or(DEFAULT_WORD_LENGTH)
+    }
+    
+    /// Blend the current WPM into the moving average. `factor` (0-1) is the
+    /// weight of the new value; smaller values give a steadier display.
+    pub fn update_smoothed_wpm(&mut self, factor: f64) {
+        let factor = factor.clamp(0.0, 1.0);
+        self.smoothed_wpm += (self.current_wpm() - self.smoothed_wpm) * factor;
+    }
+    
+    /// Smoothed WPM for display; use `current_wpm` for the exact value
+    pub fn display_wpm(&self) -> f64 {
+        self.smoothed_wpm
+    }
+    
+    /// Append `current_wpm` to the history unless the last sample is younger
+    /// than `WPM_SAMPLE_INTERVAL`, dropping samples older than `WPM_HISTORY_SPAN`
+    pub fn sample_wpm(&mut self) {
+        let now = self.clock.now_instant();
+        if let Some((last, _)) = self.wpm_history.back() {
+            if now.duration_since(*last) < WPM_SAMPLE_INTERVAL {
+                return;
+            }
+        }
+        let wpm = self.current_wpm();
+        self.wpm_history.push_back((now, wpm));
+        while let Some((time, _)) = self.wpm_history.front() {
+            if now.duration_since(*time) <= WPM_HISTORY_SPAN {
+                break;
+            }
+            self.wpm_history.pop_front();
+        }
+    }
+    
+    /// Sampled WPM values of the last `span`, oldest first
+    pub fn wpm_history(&self, span: Duration) -> Vec<f64> {
+        let now = self.clock.now_instant();
+        self.wpm_history
+            .iter()
+            .filter(|(time, _)| now.duration_since(*time) <= span)
+            .map(|(_, wpm)| *wpm)
+            .collect()
+    }
+    
+    /// Highest typing speed reached today
+    pub fn peak_wpm_today(&self) -> f64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map_or(0.0, |s| s.peak_wpm)
+    }
+    
+    /// Highest actions per minute reached today
+    pub fn peak_apm_today(&self) -> f64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map_or(0.0, |s| s.peak_apm)
+    }
+    
+    /// The day new activity is recorded under
+    pub fn today(&self) -> NaiveDate {
+        self.current_date.unwrap_or_else(|| self.clock.now_local().date_naive())
+    }
+    
+    /// `daily_stats` key of the current day
+    fn today_key(&self) -> String {
+        self.today().format("%Y-%m-%d").to_string()
+    }
+    
+    /// Start recording under a new day. Yesterday's totals stay in
+    /// `daily_stats`; the typing-speed window starts over.
+    /// Returns the previous day if the date changed.
+    pub fn roll_over(&mut self, today: NaiveDate) -> Option<NaiveDate> {
+        let previous = self.today();
+        self.current_date = Some(today);
+        if previous == today {
+            return None;
+        }
+        self.recent_keys.clear();
+        self.recent_corrections.clear();
+        self.recent_actions.clear();
+        self.smoothed_wpm = 0.0;
+        self.prune_daily_key_counts();
+        Some(previous)
+    }
+    
+    /// Drop per-key counts of days more than `DAILY_KEY_RETENTION_DAYS` before today
+    pub fn prune_daily_key_counts(&mut self) {
+        let oldest = self.today() - Days::new(DAILY_KEY_RETENTION_DAYS);
+        self.daily_key_counts.retain(|date, _| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|date| date >= oldest)
+        });
+    }
+    
+    /// Drop days more than `retention_days` before today from the daily
+    /// history. Their streaks are kept in `pruned_streaks` for
+    /// `streak_min_keys` and every minimum kept before; the all-time counts
+    /// are kept apart and stay complete. Returns the number of days dropped.
+    pub fn prune_daily_stats(&mut self, retention_days: u32, streak_min_keys: u64) -> usize {
+        let oldest = self.retention_start(retention_days);
+        let expired = |date: &String| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|date| date < oldest);
+        let dropped: BTreeMap<NaiveDate, u64> = self.daily_stats.iter()
+            .filter_map(|(date, day)| Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, day.total_keys)))
+            .filter(|(date, _)| *date < oldest)
+            .collect();
+        if let Some(last_day) = oldest.pred_opt().filter(|_| !dropped.is_empty()) {
+            self.pruned_streaks.entry(streak_min_keys.max(1)).or_insert_with(PrunedStreak::empty);
+            for (min_keys, streak) in &mut self.pruned_streaks {
+                let active = dropped.iter().filter(|(_, keys)| **keys >= *min_keys).map(|(date, _)| *date);
+                streak.advance(active, last_day);
+            }
+        }
+        self.daily_stats.retain(|date, _| !expired(date));
+        self.daily_key_counts.retain(|date, _| !expired(date));
+        dropped.len()
+    }
+    
+    /// First day kept under a retention of `retention_days`
+    pub fn retention_start(&self, retention_days: u32) -> NaiveDate {
+        self.today() - Days::new(u64::from(retention_days))
+    }
+    
+    /// Key presses weighted by how recent they are: a day's presses count
+    /// fully today and half as much every `half_life_days` after that
+    pub fn decayed_key_counts(&self, half_life_days: f64) -> HashMap<String, f64> {
+        let today = self.today();
+        let mut decayed = HashMap::new();
+        for (date, counts) in &self.daily_key_counts {
+            let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+            let age = (today - date).num_days().max(0) as f64;
+            let weight = 0.5f64.powf(age / half_life_days.max(f64::MIN_POSITIVE));
+            for (key, count) in counts {
+                *decayed.entry(key.clone()).or_insert(0.0) += *count as f64 * weight;
+            }
+        }
+        decayed
+    }
+    
+    /// Key presses per hour of the current day
+    pub fn today_hourly_keys(&self) -> HashMap<u8, u64> {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|d| d.hourly_keys.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Clicks per hour of the current day
+    pub fn today_hourly_clicks(&self) -> HashMap<u8, u64> {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|d| d.hourly_clicks.clone())
+            .unwrap_or_default()
+    }
+    
+    /// Get total key presses for today
+    pub fn today_keys(&self) -> u64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|s| s.total_keys)
+            .unwrap_or(0)
+    }
+    
+    /// Get total clicks for today
+    pub fn today_clicks(&self) -> u64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|s| s.total_clicks)
+            .unwrap_or(0)
+    }
+    
+    /// Get total mouse distance for today
+    pub fn today_distance(&self) -> f64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|s| s.total_distance)
+            .unwrap_or(0.0)
+    }
+    
+    /// Drags today and the distance moved while dragging
+    pub fn today_drags(&self) -> (u64, f64) {
+        self.daily_stats
+            .get(&self.today_key())
+            .map(|s| (s.drag_count, s.drag_distance))
+            .unwrap_or_default()
+    }
+    
+    /// Clicks of buttons without a name of their own, e.g. extra buttons of
+    /// gaming mice recorded as `Button(n)`
+    pub fn other_button_clicks(&self) -> u64 {
+        self.mouse_clicks
+            .iter()
+            .filter(|(button, _)| !NAMED_BUTTONS.contains(&button.as_str()))
+            .map(|(_, count)| count)
+            .sum()
+    }
+    
+    /// Share of all key presses that were Backspace or Delete (0-1).
+    /// Presses in privacy mode count towards the total only.
+    pub fn correction_ratio(&self) -> f64 {
+        let deletions: u64 = self.key_counts
+            .iter()
+            .filter(|(key, _)| is_deletion_key(key))
+            .map(|(_, count)| count)
+            .sum();
+        ratio(deletions, self.total_keys())
+    }
+    
+    /// Share of today's key presses that were Backspace or Delete (0-1)
+    pub fn today_correction_ratio(&self) -> f64 {
+        self.daily_stats
+            .get(&self.today_key())
+            .map_or(0.0, |s| ratio(s.backspaces, s.total_keys))
+    }
+    
+    /// Stats of the day before today, if any were recorded
+    fn yesterday_stats(&self) -> Option<&DailyStats> {
+        let yesterday = self.today().pred_opt()?;
+        self.daily_stats.get(&yesterday.format("%Y-%m-%d").to_string())
+    }
+    
+    /// Get total key presses for yesterday
+    pub fn yesterday_keys(&self) -> u64 {
+        self.yesterday_stats().map_or(0, |s| s.total_keys)
+    }
+    
+    /// Get total clicks for yesterday
+    pub fn yesterday_clicks(&self) -> u64 {
+        self.yesterday_stats().map_or(0, |s| s.total_clicks)
+    }
+    
+    /// Get total mouse distance for yesterday
+    pub fn yesterday_distance(&self) -> f64 {
+        self.yesterday_stats().map_or(0.0, |s| s.total_distance)
+    }
+    
+    /// Key presses per calendar day
+    pub fn keys_per_day(&self) -> HashMap<NaiveDate, u64> {
+        self.daily_stats
+            .iter()
+            .filter_map(|(date, day)| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").ok().map(|date| (date, day.total_keys))
+            })
+            .collect()
+    }
+    
+    /// Days with at least `min_keys` keystrokes (and at least one), in
+    /// chronological order. Days whose date does not parse are skipped.
+    fn active_days(&self, min_keys: u64) -> BTreeSet<NaiveDate> {
+        self.daily_stats
+            .iter()
+            .filter(|(_, day)| day.total_keys >= min_keys.max(1))
+            .filter_map(|(date, _)| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .collect()
+    }
+    
+    /// Streaks among the pruned days for `min_keys`, if days were pruned
+    fn pruned_streak(&self, min_keys: u64) -> Option<PrunedStreak> {
+        self.pruned_streaks.get(&min_keys.max(1)).copied()
+    }
+    
+    /// Consecutive days with at least `min_keys` keys ending today, or
+    /// yesterday while today has not reached `min_keys` yet
+    pub fn current_streak(&self, min_keys: u64) -> u32 {
+        self.current_streak_on(self.today(), min_keys)
+    }
+    
+    fn current_streak_on(&self, today: NaiveDate, min_keys: u64) -> u32 {
+        let days = self.active_days(min_keys);
+        let Some(yesterday) = today.pred_opt() else {
+            return u32::from(days.contains(&today));
+        };
+        // The run up to yesterday, continued by the pruned days before it
+        let mut streak = self.pruned_streak(min_keys).unwrap_or_else(PrunedStreak::empty);
+        streak.advance(days.iter().copied(), yesterday);
+        streak.run + u32::from(days.contains(&today))
+    }
+    
+    /// Longest run of consecutive days with at least `min_keys` keys ever
+    /// recorded, including the days pruned since `min_keys` was in use
+    pub fn longest_streak(&self, min_keys: u64) -> u32 {
+        let days = self.active_days(min_keys);
+        let mut streak = self.pruned_streak(min_keys).unwrap_or_else(PrunedStreak::empty);
+        if let Some(last) = days.last() {
+            streak.advance(days.iter().copied(), *last);
+        }
+        streak.longest
+    }
+    
+    /// Hour of the day with the most key presses, with its count
+    pub fn peak_hour(&self) -> Option<(u8, u64)> {
+        self.hourly_key_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(hour, count)| (*hour, *count))
+            .max_by_key(|(hour, count)| (*count, std::cmp::Reverse(*hour)))
+    }
+    
+    /// Date with the most key presses, with its count
+    pub fn peak_day(&self) -> Option<(String, u64)> {
+        self.daily_stats
+            .iter()
+            .filter(|(_, day)| day.total_keys > 0)
+            .max_by(|(a_date, a), (b_date, b)| a.total_keys.cmp(&b.total_keys).then(b_date.cmp(a_date)))
+            .map(|(date, day)| (date.clone(), day.total_keys))
+    }
+    
+    /// Days from `from` to `to` (inclusive) with their totals; an error if
+    /// the range is inverted
+    pub fn range_export(&self, from: NaiveDate, to: NaiveDate) -> Result<RangeExport, String> {
+        if from > to {
+            return Err(format!("start date {} is after end date {}", from, to));
+        }
+        let daily_stats: BTreeMap<String, DailyStats> = self.daily_stats
+            .iter()
+            .filter(|(date, _)| {
+                NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok_and(|date| (from..=to).contains(&date))
+            })
+            .map(|(date, day)| (date.clone(), day.clone()))
+            .collect();
+        let mut totals = DailyStats::default();
+        for day in daily_stats.values() {
+            totals.add(day);
+        }
+        Ok(RangeExport { from, to, totals, daily_stats })
+    }
+    
+    /// Totals of all weekdays and of all weekend days (Saturday, Sunday), as
+    /// (weekdays, weekends). Only keys, clicks and distance are summed.
+    pub fn weekday_weekend_split(&self) -> (DailyStats, DailyStats) {
+        let mut weekdays = DailyStats::default();
+        let mut weekends = DailyStats::default();
+        for (date, day) in &self.daily_stats {
+            let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+            let total = match date.weekday() {
+                Weekday::Sat | Weekday::Sun => &mut weekends,
+                _ => &mut weekdays,
+            };
+            total.total_keys += day.total_keys;
+            total.total_clicks += day.total_clicks;
+            total.total_distance += day.total_distance;
+        }
+        (weekdays, weekends)
+    }
+    
+    /// Key presses per hand as (left, right, both).
+    /// Keys without a hand mapping are left out.
+    pub fn hand_balance(&self) -> (u64, u64, u64) {
+        let mut balance = (0, 0, 0);
+        for (key, count) in &self.key_counts {
+            match hands::classify(key) {
+                Some(Hand::Left) => balance.0 += count,
+                Some(Hand::Right) => balance.1 += count,
+                Some(Hand::Both) => balance.2 += count,
+                None => {}
+            }
+        }
+        balance
+    }
+    
+    /// Estimated load per finger using the standard QWERTY map. Keys outside
+    /// the touch-typing rows count for the nearest finger, and shared
+    /// modifiers (Shift, Ctrl, Meta) are split between both hands.
+    pub fn finger_load(&self) -> HashMap<Finger, u64> {
+        self.finger_load_with(&FingerMap::default())
+    }
+    
+    /// Estimated load per finger using a custom key-to-finger map
+    pub fn finger_load_with(&self, map: &FingerMap) -> HashMap<Finger, u64> {
+        let mut load = HashMap::new();
+        for (key, count) in &self.key_counts {
+            match map.estimate(key) {
+                Some(Attribution::One(finger)) => *load.entry(finger).or_insert(0) += count,
+                Some(Attribution::Split(left, right)) => {
+                    *load.entry(left).or_insert(0) += count - count / 2;
+                    *load.entry(right).or_insert(0) += count / 2;
+                }
+                None => {}
+            }
+        }
+        load
+    }
+    
+    /// One-line recap of today's activity
+    pub fn daily_summary_text(&self) -> String {
+        self.summary_text_for(&self.today_key())
+    }
+    
+    /// Today's activity for pasting elsewhere: one line, or with `verbose`
+    /// one figure per line plus the all-time totals
+    pub fn summary_text(&self, verbose: bool) -> String {
+        let day = self.daily_stats.get(&self.today_key()).cloned().unwrap_or_default();
+        let travel = format_distance(day.total_distance / 1000.0);
+        if !verbose {
+            return format!(
+                "Today: {} keys, {} clicks, {} mouse travel, peak {:.0} WPM",
+                format_count(day.total_keys),
+                format_count(day.total_clicks),
+                travel,
+                day.peak_wpm,
+            );
+        }
+        
+        let minutes = (day.active_seconds / 60.0) as u64;
+        let all_clicks: u64 = self.mouse_clicks.values().sum();
+        [
+            format!("Finger Monitor · {}", self.today_key()),
+            format!("Keys: {}", format_count(day.total_keys)),
+            format!("Clicks: {}", format_count(day.total_clicks)),
+            format!("Mouse travel: {}", travel),
+            format!("Peak WPM: {:.0}", day.peak_wpm),
+            format!("Active time: {}h {:02}m", minutes / 60, minutes % 60),
+            format!(
+                "All time: {} keys, {} clicks, {} mouse travel",
+                format_count(self.total_keys()),
+                format_count(all_clicks),
+                format_distance(self.mouse_distance / 1000.0),
+            ),
+        ].join("\n")
+    }
+    
+    fn summary_text_for(&self, date: &str) -> String {
+        let day = self.daily_stats.get(date).cloned().unwrap_or_default();
+        format!(
+            "Daily summary for {}: {} keys, {} clicks, {:.2} m mouse distance, peak {:.0} WPM",
+            date,
+            day.total_keys,
+            day.total_clicks,
+            day.total_distance / 1000.0,
+            day.peak_wpm,
+        )
+    }
+    
+    /// Rename keys in the per-key counts to their aliases, adding up keys
+    /// recorded under an old name and its new one. Returns the renamed
+    /// `key_counts` entries as (old name, new name, count).
+    pub fn apply_key_aliases(&mut self, aliases: &KeyAliases) -> Vec<(String, String, u64)> {
+        let mut renamed_keys: Vec<_> = self.key_counts
+            .iter()
+            .filter_map(|(key, count)| aliases.resolve(key).map(|alias| (key.clone(), alias.to_string(), *count)))
+            .collect();
+        renamed_keys.sort();
+        
+        let rename = |counts: &mut HashMap<String, u64>| {
+            if counts.keys().any(|key| aliases.resolve(key).is_some()) {
+                let mut renamed = HashMap::new();
+                for (key, count) in counts.drain() {
+                    *renamed.entry(aliases.apply(&key)).or_insert(0) += count;
+                }
+                *counts = renamed;
+            }
+        };
+        rename(&mut self.key_counts);
+        rename(&mut self.key_repeats);
+        self.daily_key_counts.values_mut().for_each(rename);
+        if self.bigram_counts.keys().any(|(a, b)| aliases.resolve(a).is_some() || aliases.resolve(b).is_some()) {
+            let mut renamed = HashMap::new();
+            for ((first, second), count) in self.bigram_counts.drain() {
+                *renamed.entry((aliases.apply(&first), aliases.apply(&second))).or_insert(0) += count;
+            }
+            self.bigram_counts = renamed;
+        }
+        renamed_keys
+    }
+    
+    /// Get top N most pressed keys within a category (`KeyCategory::All` for every key)
+    pub fn top_keys_filtered(&self, n: usize, category: KeyCategory) -> Vec<(String, u64)> {
+        let mut sorted: Vec<_> = self.key_counts.iter()
+            .filter(|(k, _)| category.matches(k))
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        // Ties by name, so equal counts keep their ranks from one refresh to the next
+        sorted.sort_by(|(key_a, a), (key_b, b)| b.cmp(a).then_with(|| key_a.cmp(key_b)));
+        sorted.truncate(n);
+        sorted
+    }
+    
+    /// Keys of `category` whose name, or the label `label` shows it under,
+    /// contains `query` (ignoring case), with their rank (from 1) among all
+    /// keys of the category; the top `n` keys when `query` is empty
+    pub fn search_top_keys<'a>(
+        &self,
+        query: &str,
+        n: usize,
+        category: KeyCategory,
+        label: impl Fn(&str) -> Option<&'a str>,
+    ) -> Vec<(usize, String, u64)> {
+        let query = query.trim().to_lowercase();
+        let matches = |name: &str| name.to_lowercase().contains(&query);
+        let ranked = self.top_keys_filtered(if query.is_empty() { n } else { usize::MAX }, category);
+        ranked
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (key, _))| matches(key) || label(key).is_some_and(matches))
+            .map(|(i, (key, count))| (i + 1, key, count))
+            .collect()
+    }
+    
+    /// Privacy-mode key presses per category, most used first
+    pub fn category_breakdown(&self) -> Vec<(PrivacyBucket, u64)> {
+        let mut sorted: Vec<_> = self.category_counts.iter().map(|(b, c)| (*b, *c)).collect();
+        sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        sorted
+    }
+    
+    /// Get top N most frequent key pairs as (first, second, count)
+    pub fn top_bigrams(&self, n: usize) -> Vec<(String, String, u64)> {
+        let mut sorted: Vec<_> = self.bigram_counts.iter()
+            .map(|((first, second), count)| (first.clone(), second.clone(), *count))
+            .collect();
+        sorted.sort_by_key(|(_, _, count)| std::cmp::Reverse(*count));
+        sorted.truncate(n);
+        sorted
+    }
+    
+    /// Get top N applications by combined key presses and clicks,
+    /// as (app, keys, clicks)
+    pub fn top_apps(&self, n: usize) -> Vec<(String, u64, u64)> {
+        let apps: HashSet<&String> = self.app_key_counts.keys()
+            .chain(self.app_click_counts.keys())
+            .collect();
+        let mut sorted: Vec<_> = apps.into_iter()
+            .map(|app| (
+                app.clone(),
+                self.app_key_counts.get(app).copied().unwrap_or(0),
+                self.app_click_counts.get(app).copied().unwrap_or(0),
+            ))
+            .collect();
+        sorted.sort_by_key(|(_, keys, clicks)| std::cmp::Reverse(keys + clicks));
+        sorted.truncate(n);
+        sorted
+    }
+    
+    /// Get all input devices seen so far, busiest first
+    pub fn devices(&self) -> Vec<DeviceUsage> {
+        let names: HashSet<&String> = self.device_key_counts.keys()
+            .chain(self.device_click_counts.keys())
+            .collect();
+        let mut devices: Vec<_> = names.into_iter()
+            .map(|name| DeviceUsage {
+                name: name.clone(),
+                keys: self.device_key_counts.get(name).copied().unwrap_or(0),
+                clicks: self.device_click_counts.get(name).copied().unwrap_or(0),
+                last_seen: self.device_last_seen.get(name).copied(),
+            })
+            .collect();
+        devices.sort_by(|a, b| (b.keys + b.clicks).cmp(&(a.keys + a.clicks)).then_with(|| a.name.cmp(&b.name)));
+        devices
+    }
+    
+    /// Get session duration
+    pub fn session_duration(&self) -> Duration {
+        self.session_start
+            .map(|start| self.clock.now_instant().duration_since(start))
+            .unwrap_or_default()
+    }
+    
+    /// Current value of what `measure` counts, with streak days needing
+    /// `streak_min_keys` keys
+    pub fn milestone_progress(&self, measure: Measure, streak_min_keys: u64) -> f64 {
+        match measure {
+            Measure::Keys => self.total_keys() as f64,
+            Measure::Clicks => self.mouse_clicks.values().sum::<u64>() as f64,
+            Measure::Distance => self.mouse_distance / 1_000_000.0,
+            Measure::Streak => self.current_streak(streak_min_keys) as f64,
+        }
+    }
+    
+    /// Next key milestone on `achievements::key_ladder`, the current total,
+    /// and the way (0-1) from the previous one to it; `None` once the last
+    /// one is reached
+    pub fn next_milestone(&self) -> Option<(u64, u64, f32)> {
+        let current = self.total_keys();
+        let (previous, target) = achievements::key_ladder_span(current)?;
+        let progress = (current - previous) as f64 / (target - previous) as f64;
+        Some((target, current, progress as f32))
+    }
+    
+    /// Unlock the milestones of `measure` reached by now, returning those
+    /// that were not unlocked before
+    pub fn unlock_milestones(&mut self, measure: Measure, streak_min_keys: u64) -> Vec<&'static Milestone> {
+        let progress = self.milestone_progress(measure, streak_min_keys);
+        let unlocked: Vec<_> = achievements::reached(measure, progress)
+            .filter(|milestone| !self.achieved.contains_key(milestone.id))
+            .collect();
+        let now = self.clock.now_local();
+        for milestone in &unlocked {
+            self.achieved.insert(milestone.id.to_string(), now);
+        }
+        unlocked
+    }
+    
+    /// Store the running session in `sessions`, replacing its previous
+    /// checkpoint. A session without any activity is not stored.
+    pub fn checkpoint_session(&mut self) {
+        let now = self.clock.now_local();
+        let Some(session) = &mut self.current_session else {
+            return;
+        };
+        if session.is_empty() {
+            return;
+        }
+        session.ended_at = now;
+        sessions::checkpoint(&mut self.sessions, session);
+    }
+    
+    /// Past sessions and the running one (if anything was recorded in it),
+    /// most recent first
+    pub fn sessions(&self) -> Vec<SessionRecord> {
+        let running = self.current_session.as_ref()
+            .filter(|session| !session.is_empty())
+            .map(|session| SessionRecord { ended_at: self.clock.now_local(), ..session.clone() });
+        let started_at = running.as_ref().map(|session| session.started_at);
+        running.into_iter()
+            .chain(self.sessions.iter().rev().filter(|session| Some(session.started_at) != started_at).cloned())
+            .collect()
+    }
+}
+
+/// Thread-safe statistics manager
+#[derive(Clone)]
+pub struct StatsManager {
+    stats: Arc<RwLock<Stats>>,
+    store: Arc<dyn StatsStore>,
+    /// Key presses and clicks not yet appended to the store's event log
+    pending_events: Arc<Mutex<Vec<StoredEvent>>>,
+    /// Keep every key press and click with its time, in `events.jsonl` and
+    /// the store's event log (opt-in, `record_events`)
+    record_events: Arc<AtomicBool>,
+    /// Opt-in `events.jsonl` every key press and click is written to
+    event_log: Arc<Mutex<Option<EventLog>>>,
+    pub listener_active: Arc<AtomicBool>,
+    pub last_error: Arc<RwLock<Option<String>>>,
+    /// Shown while the listener runs but may miss input, e.g. on Wayland
+    pub listener_warning: Arc<RwLock<Option<String>>>,
+    /// Input events dropped because the listener channel was full
+    pub dropped_events: Arc<AtomicU64>,
+    /// Incremented by every change to the stats
+    changes: Arc<AtomicU64>,
+    /// Value of `changes` at the last successful save
+    saved_changes: Arc<AtomicU64>,
+    /// Time of the latest recorded input
+    last_activity: Arc<Mutex<Option<Instant>>>,
+    /// Time between periodic saves, read by the autosave thread
+    save_interval: Arc<RwLock<Duration>>,
+    /// Unit for displaying the scroll distance
+    scroll_unit: Arc<RwLock<ScrollUnit>>,
+    /// Weight of the newest value in the smoothed WPM
+    wpm_smoothing: Arc<RwLock<f64>>,
+    /// Days of `daily_stats` kept on save; `None` keeps every day
+    retention_days: Arc<RwLock<Option<u32>>>,
+    /// Set when the stored stats could not be used; saving is disabled so they are not overwritten
+    storage_error: Arc<RwLock<Option<String>>>,
+    /// Set when the stored stats were damaged and had to be set aside or restored
+    load_warning: Arc<RwLock<Option<String>>>,
+    // Deduplication state
+    dedup_window: Arc<RwLock<Duration>>,
+    last_key: Arc<RwLock<Option<(String, Instant)>>>,
+    last_click: Arc<RwLock<Option<(String, Instant)>>>,
+    /// Custom and legacy key names, applied to every recorded key
+    key_aliases: Arc<RwLock<KeyAliases>>,
+    /// Previous click, for double and triple click counting
+    clicks: Arc<Mutex<ClickTracker>>,
+    /// Longest gap between the presses of a double or triple click
+    multi_click_window: Arc<RwLock<Duration>>,
+    /// Longest cursor step counted as travel, in pixels; 0 counts every step
+    max_movement: Arc<RwLock<f64>>,
+    /// Keys currently held down with the time of their latest event, used to
+    /// detect auto-repeat
+    held_keys: Arc<RwLock<HashMap<String, Instant>>>,
+    /// Previous key press, for bigram counting
+    bigrams: Arc<Mutex<BigramTracker>>,
+    /// Rate limit for cursor positions on the screen grid
+    positions: Arc<Mutex<PositionSampler>>,
+    /// Count auto-repeat events as key presses (legacy behavior)
+    count_repeats: Arc<AtomicBool>,
+    /// Foreground application lookup, shared by all recording paths
+    active_app: Arc<Mutex<ActiveAppCache>>,
+    /// Lowercased application name patterns in which nothing is recorded
+    excluded_apps: Arc<RwLock<Vec<String>>>,
+    /// Pause recording while a fullscreen window has focus
+    pause_in_fullscreen: Arc<AtomicBool>,
+    /// Fullscreen state of the focused window, queried only while pausing in fullscreen
+    fullscreen: Arc<Mutex<FullscreenCache>>,
+    /// Why the last event was not recorded, if it was not
+    pause_reason: Arc<RwLock<Option<PauseReason>>>,
+    /// Input device the listener is forwarding events from, if it knows
+    current_device: Arc<RwLock<Option<String>>>,
+    /// Devices the listener is reading from, with the number of open handles each
+    connected_devices: Arc<RwLock<HashMap<String, usize>>>,
+    /// Unlocked milestones and finished days not yet shown as notifications
+    announcements: Arc<Mutex<Vec<String>>>,
+    /// Key presses left until the next key milestone, so the total is only
+    /// summed when one may have been reached
+    keys_to_milestone: Arc<AtomicU64>,
+    /// Keys a day needs to count towards a streak
+    streak_min_keys: Arc<AtomicU64>,
+    /// Directory daily Markdown reports are written to, if enabled
+    report_dir: Arc<RwLock<Option<PathBuf>>>,
+    /// Time source shared with the stats
+    clock: SharedClock,
+}
+
+impl StatsManager {
+    pub fn new() -> Self {
+        Self::with_data_path(store::data_dir().join("stats.json"))
+    }
+    
+    /// Create a manager that keeps its stats in memory only, for tests
+    #[cfg(test)]
+    pub fn new_in_memory() -> Self {
+        Self::with_store(Box::new(store::MemoryStore::default()))
+    }
+    
+    /// Create a manager backed by a specific stats file
+    pub fn with_data_path(data_path: PathBuf) -> Self {
+        Self::with_store(Box::new(JsonStore::new(data_path)))
+    }
+    
+    /// Create a manager persisting through the given store
+    pub fn with_store(store: Box<dyn StatsStore>) -> Self {
+        Self::with_store_and_clock(store, Arc::new(SystemClock))
+    }
+    
+    /// Create a manager persisting through the given store and reading the time from `clock`
+    pub fn with_store_and_clock(store: Box<dyn StatsStore>, clock: Arc<dyn Clock>) -> Self {
+        let clock = SharedClock(clock);
+        log::info!("Stats are stored in {}", store.path().display());
+        // Load existing stats or create new. Stats written by a newer build are
+        // left untouched rather than replaced by an empty history.
+        let mut storage_error = None;
+        let mut stats = store.load().unwrap_or_else(|e| {
+            if migrate::is_future_version(e.as_ref()) {
+                log::error!("Not using stored stats: {}", e);
+                storage_error = Some(e.to_string());
+            }
+            Stats::new()
+        });
+        let load_warning = store.take_load_warning();
+        stats.clock = clock.clone();
+        stats.session_start = Some(clock.now_instant());
+        stats.current_session = Some(SessionRecord::new(clock.now_local()));
+        stats.current_date = Some(clock.now_local().date_naive());
+        stats.prune_daily_key_counts();
+        // Milestones passed before they were tracked unlock without being
+        // announced; streaks wait for the configured minimum
+        let already_reached = Measure::ALL.iter()
+            .filter(|measure| **measure != Measure::Streak)
+            .map(|measure| stats.unlock_milestones(*measure, DEFAULT_STREAK_MIN_KEYS).len())
+            .sum::<usize>();
+        if already_reached > 0 {
+            log::info!("{} milestone(s) were already reached", already_reached);
+        }
+        let keys_to_milestone = keys_to_next_milestone(&stats);
+        
+        Self {
+            stats: Arc::new(RwLock::new(stats)),
+            store: Arc::from(store),
+            pending_events: Arc::new(Mutex::new(Vec::new())),
+            record_events: Arc::new(AtomicBool::new(false)),
+            event_log: Arc::new(Mutex::new(None)),
+            listener_active: Arc::new(AtomicBool::new(false)),
+            last_error: Arc::new(RwLock::new(None)),
+            listener_warning: Arc::new(RwLock::new(None)),
+            dropped_events: Arc::new(AtomicU64::new(0)),
+            changes: Arc::new(AtomicU64::new(0)),
+            saved_changes: Arc::new(AtomicU64::new(0)),
+            last_activity: Arc::new(Mutex::new(None)),
+            save_interval: Arc::new(RwLock::new(autosave::DEFAULT_SAVE_INTERVAL)),
+            scroll_unit: Arc::new(RwLock::new(ScrollUnit::default())),
+            wpm_smoothing: Arc::new(RwLock::new(DEFAULT_WPM_SMOOTHING)),
+            retention_days: Arc::new(RwLock::new(None)),
+            storage_error: Arc::new(RwLock::new(storage_error)),
+            load_warning: Arc::new(RwLock::new(load_warning)),
+            dedup_window: Arc::new(RwLock::new(DEFAULT_DEDUP_WINDOW)),
+            last_key: Arc::new(RwLock::new(None)),
+            last_click: Arc::new(RwLock::new(None)),
+            key_aliases: Arc::new(RwLock::new(KeyAliases::default())),
+            clicks: Arc::new(Mutex::new(ClickTracker::default())),
+            multi_click_window: Arc::new(RwLock::new(DEFAULT_MULTI_CLICK_WINDOW)),
+            max_movement: Arc::new(RwLock::new(DEFAULT_MAX_MOVEMENT)),
+            held_keys: Arc::new(RwLock::new(HashMap::new())),
+            bigrams: Arc::new(Mutex::new(BigramTracker::default())),
+            positions: Arc::new(Mutex::new(PositionSampler::default())),
+            count_repeats: Arc::new(AtomicBool::new(false)),
+            active_app: Arc::new(Mutex::new(ActiveAppCache::new())),
+            excluded_apps: Arc::new(RwLock::new(Vec::new())),
+            pause_in_fullscreen: Arc::new(AtomicBool::new(false)),
+            fullscreen: Arc::new(Mutex::new(FullscreenCache::new())),
+            pause_reason: Arc::new(RwLock::new(None)),
+            current_device: Arc::new(RwLock::new(None)),
+            connected_devices: Arc::new(RwLock::new(HashMap::new())),
+            announcements: Arc::new(Mutex::new(Vec::new())),
+            keys_to_milestone: Arc::new(AtomicU64::new(keys_to_milestone)),
+            streak_min_keys: Arc::new(AtomicU64::new(DEFAULT_STREAK_MIN_KEYS)),
+            report_dir: Arc::new(RwLock::new(None)),
+            clock,
+        }
+    }
+    
+    pub fn set_listener_active(&self, active: bool) {
+        self.listener_active.store(active, Ordering::SeqCst);
+    }
+    
+    /// Mark the listener as starting unless one is already active, clearing
+    /// the error of a previous attempt. Returns whether the caller may start it.
+    pub fn claim_listener(&self) -> bool {
+        let claimed = self.listener_active
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok();
+        if claimed {
+            if let Ok(mut lock) = self.last_error.write() {
+                *lock = None;
+            }
+        }
+        claimed
+    }
+    
+    pub fn set_listener_error(&self, error: String) {
+        if let Ok(mut lock) = self.last_error.write() {
+            *lock = Some(error);
+        }
+    }
+    
+    pub fn set_listener_warning(&self, warning: Option<String>) {
+        if let Ok(mut lock) = self.listener_warning.write() {
+            *lock = warning;
+        }
+    }
+    
+    /// Set the input device the following events come from; `None` records
+    /// them under `DEFAULT_DEVICE`
+    pub fn set_current_device(&self, device: Option<&str>) {
+        if let Ok(mut lock) = self.current_device.write() {
+            *lock = device.map(str::to_string);
+        }
+    }
+    
+    fn current_device(&self) -> String {
+        self.current_device.read()
+            .ok()
+            .and_then(|device| device.clone())
+            .unwrap_or_else(|| DEFAULT_DEVICE.to_string())
+    }
+    
+    /// Note that the listener opened (or closed) a handle to an input device.
+    /// A device stays connected while any of its handles is open.
+    pub fn set_device_connected(&self, device: &str, connected: bool) {
+        if !connected {
+            // Keys held on a closed handle are never released
+            self.release_held_keys();
+        }
+        let Ok(mut devices) = self.connected_devices.write() else {
+            return;
+        };
+        if connected {
+            *devices.entry(device.to_string()).or_insert(0) += 1;
+        } else if let Some(handles) = devices.get_mut(device) {
+            *handles -= 1;
+            if *handles == 0 {
+                devices.remove(device);
+            }
+        }
+    }
+    
+    /// Whether the listener is reading from the device at the moment
+    pub fn is_device_connected(&self, device: &str) -> bool {
+        self.connected_devices.read()
+            .map(|devices| devices.contains_key(device))
+            .unwrap_or(false)
+    }
+    
+    /// Unlock the milestones of `measure` that were just reached, queueing
+    /// them to be announced
+    fn check_milestones(&self, stats: &mut Stats, measure: Measure) {
+        let unlocked = stats.unlock_milestones(measure, self.streak_min_keys.load(Ordering::SeqCst));
+        if unlocked.is_empty() {
+            return;
+        }
+        for milestone in unlocked {
+            log::info!("Milestone unlocked: {}", milestone.title);
+            self.announce(format!("{} Milestone unlocked: {}", milestone.icon, milestone.title));
+        }
+    }
+    
+    /// Check the key milestones and count the keys left until the next one
+    fn check_key_milestones(&self, stats: &mut Stats) {
+        self.check_milestones(stats, Measure::Keys);
+        self.keys_to_milestone.store(keys_to_next_milestone(stats), Ordering::SeqCst);
+    }
+    
+    /// Queue a message to be shown as a desktop notification
+    fn announce(&self, message: String) {
+        if let Ok(mut queue) = self.announcements.lock() {
+            queue.push(message);
+        }
+    }
+    
+    /// Messages queued since the last call, to show them as notifications
+    pub fn take_announcements(&self) -> Vec<String> {
+        self.announcements.lock()
+            .map(|mut queue| std::mem::take(&mut *queue))
+            .unwrap_or_default()
+    }
+    
+    /// Write a Markdown report of each finished day to `reports/` in the data directory
+    pub fn set_daily_reports(&self, enabled: bool) {
+        self.set_report_dir(enabled.then(|| store::data_dir().join("reports")));
+    }
+    
+    fn set_report_dir(&self, dir: Option<PathBuf>) {
+        if let Some(dir) = &dir {
+            log::info!("Daily reports are written to {}", dir.display());
+        }
+        if let Ok(mut lock) = self.report_dir.write() {
+            *lock = dir;
+        }
+    }
+    
+    /// Announce the summary of a finished day, once per day, returning its
+    /// report and the file to write it to if reports are enabled. The report
+    /// is written by the caller once the stats lock is released.
+    fn finish_day(&self, stats: &mut Stats, date: &str) -> Option<(PathBuf, String)> {
+        if stats.last_daily_report.as_deref().is_some_and(|last| last >= date) {
+            return None;
+        }
+        stats.last_daily_report = Some(date.to_string());
+        let day = stats.daily_stats.get(date)?;
+        self.announce(daily_report::headline(date, day));
+        
+        let dir = self.report_dir.read().ok().and_then(|dir| dir.clone())?;
+        let top_key = stats.daily_key_counts.get(date).and_then(daily_report::top_key);
+        Some((dir.join(format!("{}.md", date)), daily_report::markdown(date, day, top_key)))
+    }
+    
+    /// Write a daily report, unless one was already written for that day
+    fn write_daily_report(path: &Path, report: &str) {
+        if path.exists() {
+            return;
+        }
+        let created = path.parent().map_or(Ok(()), fs::create_dir_all);
+        match created.and_then(|()| fs::write(path, report)) {
+            Ok(()) => log::info!("Daily report written to {}", path.display()),
+            Err(e) => log::error!("Failed to write {}: {}", path.display(), e),
+        }
+    }
+    
+    /// Set the keys a day needs to count towards a streak. Streak milestones
+    /// already reached with this minimum unlock without being announced.
+    pub fn set_streak_min_keys(&self, min_keys: u64) {
+        self.streak_min_keys.store(min_keys, Ordering::SeqCst);
+        if let Ok(mut stats) = self.stats.write() {
+            if !stats.unlock_milestones(Measure::Streak, min_keys).is_empty() {
+                self.mark_changed();
+            }
+        }
+    }
+    
+    /// Count auto-repeat events as regular key presses
+    pub fn set_count_repeats(&self, enabled: bool) {
+        self.count_repeats.store(enabled, Ordering::SeqCst);
+    }
+    
+    /// Stop storing key identities; the setting is persisted with the stats
+    pub fn set_privacy_mode(&self, enabled: bool) {
+        if let Ok(mut stats) = self.stats.write() {
+            stats.set_privacy_mode(enabled);
+            self.mark_changed();
+        }
+    }
+    
+    pub fn set_scroll_unit(&self, unit: ScrollUnit) {
+        if let Ok(mut lock) = self.scroll_unit.write() {
+            *lock = unit;
+        }
+    }
+    
+    pub fn scroll_unit(&self) -> ScrollUnit {
+        self.scroll_unit.read().map(|unit| *unit).unwrap_or_default()
+    }
+    
+    /// Set the smoothing factor for the displayed WPM (0-1, higher reacts faster)
+    pub fn set_wpm_smoothing(&self, factor: f64) {
+        if let Ok(mut lock) = self.wpm_smoothing.write() {
+            *lock = factor.clamp(0.0, 1.0);
+        }
+    }
+    
+    /// Keep only the last `days` days of daily stats, pruning older ones on
+    /// every save; `None` keeps them forever. At least `MIN_RETENTION_DAYS`
+    /// are kept.
+    pub fn set_retention_days(&self, days: Option<u32>) {
+        let days = days.map(|days| {
+            if days < MIN_RETENTION_DAYS {
+                log::warn!("Keeping {} days of daily stats instead of {}", MIN_RETENTION_DAYS, days);
+            }
+            days.max(MIN_RETENTION_DAYS)
+        });
+        if let Ok(mut lock) = self.retention_days.write() {
+            *lock = days;
+        }
+    }
+    
+    /// Set the average word length used for the WPM; non-positive values are ignored
+    pub fn set_word_length(&self, chars: f64) {
+        if chars <= 0.0 {
+            log::warn!("Ignoring invalid word length {}", chars);
+            return;
+        }
+        if let Ok(mut stats) = self.stats.write() {
+            stats.word_length = Some(chars);
+        }
+    }
+    
+    /// Move recording to the next day if the date changed; called on every
+    /// recorded event and dashboard refresh
+    pub fn check_rollover(&self) {
+        self.roll_over_to(self.clock.now_local().date_naive());
+    }
+    
+    fn roll_over_to(&self, today: NaiveDate) {
+        let unchanged = self.stats.read().map(|s| s.today() == today).unwrap_or(true);
+        if unchanged {
+            return;
+        }
+        let report = self.stats.write().ok().and_then(|mut stats| {
+            let previous = stats.roll_over(today)?.format("%Y-%m-%d").to_string();
+            log::info!("New day {}; {}", today, stats.summary_text_for(&previous));
+            self.finish_day(&mut stats, &previous)
+        });
+        // Input waiting for the stats lock does not wait for the disk
+        if let Some((path, report)) = report {
+            Self::write_daily_report(&path, &report);
+        }
+    }
+    
+    /// Advance the smoothed WPM and the WPM history; called on every dashboard refresh
+    pub fn update_smoothed_wpm(&self) {
+        let factor = self.wpm_smoothing.read().map(|f| *f).unwrap_or(DEFAULT_WPM_SMOOTHING);
+        if let Ok(mut stats) = self.stats.write() {
+            stats.update_smoothed_wpm(factor);
+            stats.sample_wpm();
+        }
+    }
+    
+    pub fn is_privacy_mode(&self) -> bool {
+        self.stats.read()
+            .map(|s| s.privacy_mode)
+            .unwrap_or(false)
+    }
+    
+    pub fn is_listener_active(&self) -> bool {
+        self.listener_active.load(Ordering::SeqCst)
+    }
+    
+    /// Time since the last recorded input, `None` before the first
+    pub fn idle_time(&self) -> Option<Duration> {
+        let last = (*self.last_activity.lock().ok()?)?;
+        Some(self.clock.now_instant().saturating_duration_since(last))
+    }
+    
+    pub fn get_listener_error(&self) -> Option<String> {
+        self.last_error.read().ok()?.clone()
+    }
+    
+    pub fn get_listener_warning(&self) -> Option<String> {
+        self.listener_warning.read().ok()?.clone()
+    }
+    
+    /// Count an input event that could not be queued for processing
+    pub fn record_dropped_event(&self) {
+        self.dropped_events.fetch_add(1, Ordering::Relaxed);
+        // The dropped event may have been a release
+        self.release_held_keys();
+    }
+    
+    /// Forget every held key, after key releases may have been lost
+    fn release_held_keys(&self) {
+        if let Ok(mut held) = self.held_keys.write() {
+            held.clear();
+        }
+    }
+    
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped_events.load(Ordering::Relaxed)
+    }
+    
+    /// Name of the focused application (cached, queried at most once per second)
+    pub fn current_app(&self) -> String {
+        self.active_app.lock()
+            .map(|mut cache| cache.current())
+            .unwrap_or_else(|_| crate::platform::UNKNOWN_APP.to_string())
+    }
+    
+    /// Set the applications in which no input is recorded
+    pub fn set_excluded_apps(&self, apps: Vec<String>) {
+        let patterns: Vec<String> = apps.iter()
+            .map(|app| app.trim().to_lowercase())
+            .filter(|app| !app.is_empty())
+            .collect();
+        if !patterns.is_empty() {
+            log::info!("Input is not recorded in: {}", patterns.join(", "));
+        }
+        if let Ok(mut excluded) = self.excluded_apps.write() {
+            *excluded = patterns;
+        }
+    }
+    
+    /// Pause recording while the focused window is fullscreen (games, video)
+    pub fn set_pause_in_fullscreen(&self, enabled: bool) {
+        self.pause_in_fullscreen.store(enabled, Ordering::Relaxed);
+    }
+    
+    pub fn pauses_in_fullscreen(&self) -> bool {
+        self.pause_in_fullscreen.load(Ordering::Relaxed)
+    }
+    
+    /// Why recording is paused at the moment, if it is
+    pub fn pause_reason(&self) -> Option<PauseReason> {
+        self.pause_reason.read().ok().and_then(|reason| *reason)
+    }
+    
+    /// Focused application name, or `None` while recording is paused
+    fn tracked_app(&self) -> Option<String> {
+        let app = self.current_app();
+        let excluded = self.excluded_apps.read()
+            .map(|excluded| is_excluded(&app, &excluded))
+            .unwrap_or(false);
+        let reason = if excluded {
+            Some(PauseReason::ExcludedApp)
+        } else if self.pauses_in_fullscreen() && self.foreground_fullscreen() {
+            Some(PauseReason::Fullscreen)
+        } else {
+            None
+        };
+        if let Ok(mut lock) = self.pause_reason.write() {
+            *lock = reason;
+        }
+        reason.is_none().then_some(app)
+    }
+    
+    fn foreground_fullscreen(&self) -> bool {
+        self.fullscreen.lock()
+            .map(|mut cache| cache.is_fullscreen())
+            .unwrap_or(false)
+    }
+    
+    /// File or database the stats are saved to
+    pub fn data_path(&self) -> &Path {
+        self.store.path()
+    }
+    
+    /// Why the stored stats are not being used (and not saved), if so
+    pub fn storage_error(&self) -> Option<String> {
+        self.storage_error.read().ok()?.clone()
+    }
+    
+    /// What was wrong with the stored stats when they were loaded, if anything
+    pub fn load_warning(&self) -> Option<String> {
+        self.load_warning.read().ok()?.clone()
+    }
+    
+    /// Save stats if anything changed since the last successful save
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.changes.load(Ordering::SeqCst) == self.saved_changes.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.force_save()
+    }
+    
+    /// Save stats through the store even if nothing changed, appending any
+    /// pending events and checkpointing the running session first
+    pub fn force_save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        // Taken even when saving is disabled, so they do not pile up
+        let events = self.pending_events.lock()
+            .map(|mut pending| std::mem::take(&mut *pending))
+            .unwrap_or_default();
+        if let Some(error) = self.storage_error() {
+            return Err(format!("saving disabled: {}", error).into());
+        }
+        
+        if let Err(e) = self.store.append_events(&events) {
+            // Queued again in front of the newer ones, for the next save
+            if let Ok(mut pending) = self.pending_events.lock() {
+                pending.splice(0..0, events);
+            }
+            return Err(e);
+        }
+        self.flush_event_log();
+        self.prune_daily_stats();
+        if let Ok(mut stats) = self.stats.write() {
+            stats.checkpoint_session();
+        }
+        
+        let stats = self.stats.read().map_err(|e| e.to_string())?;
+        // Changes are counted under the write lock, so this count matches the
+        // stats being saved; later changes keep them dirty for the next save
+        let changes = self.changes.load(Ordering::SeqCst);
+        self.store.save(&stats)?;
+        self.saved_changes.store(changes, Ordering::SeqCst);
+        Ok(())
+    }
+    
+    /// Apply the retention setting to the daily stats and the store's events
+    fn prune_daily_stats(&self) {
+        let Some(days) = self.retention_days.read().ok().and_then(|days| *days) else {
+            return;
+        };
+        let oldest = match self.stats.write() {
+            Ok(mut stats) => {
+                let pruned = stats.prune_daily_stats(days, self.streak_min_keys.load(Ordering::SeqCst));
+                if pruned > 0 {
+                    log::info!("Pruned {} day(s) of daily stats older than {} days", pruned, days);
+                }
+                stats.retention_start(days)
+            }
+            Err(_) => return,
+        };
+        let Some(cutoff) = oldest.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest() else {
+            return;
+        };
+        match self.store.prune_events(cutoff) {
+            Ok(0) => {}
+            Ok(pruned) => log::info!("Pruned {} event(s) older than {} days", pruned, days),
+            Err(e) => log::warn!("Failed to prune old events: {}", e),
+        }
+    }
+    
+    /// Note a change that needs saving. Called while holding the stats write
+    /// lock, after the change, so a save running meanwhile either includes
+    /// the change or leaves the stats marked as changed.
+    fn mark_changed(&self) {
+        self.changes.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut last) = self.last_activity.lock() {
+            *last = Some(self.clock.now_instant());
+        }
+    }
+    
+    /// Set the time between periodic saves; a running autosave thread picks it up
+    pub fn set_save_interval(&self, interval: Duration) {
+        if let Ok(mut lock) = self.save_interval.write() {
+            *lock = interval;
+        }
+    }
+    
+    fn save_interval(&self) -> Duration {
+        self.save_interval.read().map(|interval| *interval).unwrap_or(autosave::DEFAULT_SAVE_INTERVAL)
+    }
+    
+    /// Save in the background every save interval, and when a burst of activity ends
+    pub fn spawn_autosave(&self) {
+        let manager = self.clone();
+        thread::spawn(move || {
+            let mut schedule = AutosaveSchedule::new(manager.save_interval(), manager.clock.now_instant());
+            loop {
+                thread::sleep(autosave::TICK);
+                schedule.set_interval(manager.save_interval());
+                let last_activity = manager.last_activity.lock().ok().and_then(|last| *last);
+                let Some(reason) = schedule.poll(manager.clock.now_instant(), last_activity) else {
+                    continue;
+                };
+                match manager.save() {
+                    Ok(()) => log::debug!("Stats saved ({:?})", reason),
+                    Err(e) => log::error!("Failed to save stats: {}", e),
+                }
+            }
+        });
+    }
+    
+    /// Queue an event for the store's event log and write it to `events.jsonl`,
+    /// if event recording is on
+    fn push_event(&self, kind: EventKind, name: &str) {
+        if !self.record_events.load(Ordering::Relaxed) {
+            return;
+        }
+        let event = StoredEvent { time: self.clock.now_local(), kind, name: name.to_string() };
+        if let Ok(mut log) = self.event_log.lock() {
+            if let Some(writer) = log.as_mut() {
+                if let Err(e) = writer.append(&event) {
+                    log::error!("Failed to write to {}, no longer recording events: {}", writer.path().display(), e);
+                    *log = None;
+                }
+            }
+        }
+        if let Ok(mut pending) = self.pending_events.lock() {
+            pending.push(event);
+        }
+    }
+    
+    /// Append every recorded key press and click with its time to
+    /// `events.jsonl` in the data directory and to the store's event log, or
+    /// stop doing so. Key names are left out in privacy mode, like everywhere else.
+    pub fn set_record_events(&self, enabled: bool) {
+        self.set_event_log(enabled.then(|| store::data_dir().join("events.jsonl")));
+    }
+    
+    fn set_event_log(&self, path: Option<PathBuf>) {
+        self.record_events.store(path.is_some(), Ordering::Relaxed);
+        if path.is_some() && self.store.keeps_events() {
+            log::warn!(
+                "Recording every key press and click with its time to {}. This reveals what was typed and when",
+                self.store.path().display()
+            );
+        }
+        let log = match path.map(EventLog::open).transpose() {
+            Ok(log) => log,
+            Err(e) => {
+                log::error!("Cannot record events: {}", e);
+                None
+            }
+        };
+        if let Some(log) = &log {
+            log::warn!(
+                "Recording every key press and click with its time to {}. This reveals what was typed and when; delete the file when done",
+                log.path().display()
+            );
+        }
+        if let Ok(mut lock) = self.event_log.lock() {
+            if let Some(old) = lock.as_mut() {
+                let _ = old.flush();
+            }
+            *lock = log;
+        }
+    }
+    
+    /// Write buffered events to `events.jsonl`
+    fn flush_event_log(&self) {
+        if let Ok(mut log) = self.event_log.lock() {
+            if let Some(writer) = log.as_mut() {
+                if let Err(e) = writer.flush() {
+                    log::error!("Failed to write to {}: {}", writer.path().display(), e);
+                }
+            }
+        }
+    }
+    
+    /// Set the window in which an identical key press or click following
+    /// another is dropped as a duplicate event; zero disables deduplication.
+    ///
+    /// Auto-repeat is detected separately from key releases, so a held key is
+    /// never counted twice regardless of this window. The window only guards
+    /// against duplicate events from the platform, and a large value drops
+    /// genuine quick double presses (e.g. "ll" in fast typing).
+    pub fn set_dedup_window(&self, window: Duration) {
+        if let Ok(mut lock) = self.dedup_window.write() {
+            *lock = window;
+        }
+    }
+    
+    /// Set the key aliases applied to every recorded key and by `normalize_keys`
+    pub fn set_key_aliases(&self, aliases: KeyAliases) {
+        if let Ok(mut lock) = self.key_aliases.write() {
+            *lock = aliases;
+        }
+    }
+    
+    /// Whether an alias renames `key_name` when it is recorded
+    pub fn has_key_alias(&self, key_name: &str) -> bool {
+        self.key_aliases.read().is_ok_and(|aliases| aliases.resolve(key_name).is_some())
+    }
+    
+    /// `key_name` with its alias applied
+    fn alias(&self, key_name: String) -> String {
+        match self.key_aliases.read() {
+            Ok(aliases) => aliases.apply(&key_name),
+            Err(_) => key_name,
+        }
+    }
+    
+    /// Set the longest gap between the presses of a double or triple click
+    pub fn set_multi_click_window(&self, window: Duration) {
+        if let Ok(mut lock) = self.multi_click_window.write() {
+            *lock = window;
+        }
+    }
+    
+    /// Set the longest cursor step counted as travel; 0 counts every step
+    pub fn set_max_movement(&self, pixels: f64) {
+        if let Ok(mut lock) = self.max_movement.write() {
+            *lock = pixels.max(0.0);
+        }
+    }
+    
+    /// Longest cursor step counted as travel, in pixels; 0 counts every step
+    pub fn max_movement(&self) -> f64 {
+        self.max_movement.read().map(|max| *max).unwrap_or(DEFAULT_MAX_MOVEMENT)
+    }
+    
+    /// Whether `name` repeats the last event in `last` within the dedup window;
+    /// otherwise it becomes the new last event
+    fn is_duplicate(&self, last: &RwLock<Option<(String, Instant)>>, name: &str, now: Instant) -> bool {
+        let window = self.dedup_window.read().map(|w| *w).unwrap_or(DEFAULT_DEDUP_WINDOW);
+        let Ok(mut last) = last.write() else {
+            return false;
+        };
+        if let Some((last_name, last_time)) = &*last {
+            if last_name == name && now.duration_since(*last_time) < window {
+                return true;
+            }
+        }
+        *last = Some((name.to_string(), now));
+        false
+    }
+    
+    /// Record a key press with deduplication
+    pub fn record_key(&self, key_name: String) {
+        self.check_rollover();
+        let Some(app) = self.tracked_app() else {
+            return;
+        };
+        let key_name = self.alias(key_name);
+        
+        let now = self.clock.now_instant();
+        let window = self.dedup_window.read().map(|w| *w).unwrap_or(DEFAULT_DEDUP_WINDOW);
+        // A press for a key that was never released is an OS auto-repeat,
+        // unless it copies the press just seen or the key was quiet for so
+        // long that its release was lost. Repeats are classified before the
+        // dedup window, which would drop fast ones.
+        let is_repeat = match self.held_keys.write() {
+            Ok(mut held) => {
+                let gap = held.get(&key_name).map(|last| now.saturating_duration_since(*last));
+                if gap.is_some_and(|gap| gap < window.min(DUPLICATE_PRESS_WINDOW)) {
+                    return;
+                }
+                held.insert(key_name.clone(), now);
+                gap.is_some_and(|gap| gap <= HOLD_TIMEOUT)
+            }
+            Err(_) => false,
+        };
+        if !is_repeat && self.is_duplicate(&self.last_key, &key_name, now) {
+            return;
+        }
+        
+        if let Ok(mut stats) = self.stats.write() {
+            if is_repeat {
+                stats.record_repeat(key_name.clone());
+                if !self.count_repeats.load(Ordering::SeqCst) {
+                    self.mark_changed();
+                    return;
+                }
+            }
+            if !stats.privacy_mode {
+                self.push_event(EventKind::Key, &key_name);
+                let bigram = self.bigrams.lock().ok().and_then(|mut b| b.observe(&key_name, now));
+                if let Some((first, second)) = bigram {
+                    stats.record_bigram(first, second);
+                }
+            }
+            stats.record_key(key_name);
+            stats.record_app_key(app);
+            stats.record_device_key(self.current_device());
+            if self.keys_to_milestone.fetch_sub(1, Ordering::SeqCst) <= 1 {
+                self.check_key_milestones(&mut stats);
+            }
+            // The streak only grows with the key that makes the day count
+            if stats.today_keys() == self.streak_min_keys.load(Ordering::SeqCst).max(1) {
+                self.check_milestones(&mut stats, Measure::Streak);
+            }
+            self.mark_changed();
+        }
+    }
+    
+    /// Record a key release, ending the current hold
+    pub fn record_key_release(&self, key_name: &str) {
+        let key_name = self.alias(key_name.to_string());
+        if let Ok(mut held) = self.held_keys.write() {
+            held.remove(&key_name);
+        }
+    }
+    
+    /// Record a mouse click with deduplication
+    pub fn record_click(&self, button: String) {
+        self.record_click_at(button, None);
+    }
+    
+    /// Record a mouse click with deduplication, counting it in `cell` of the
+    /// screen grid if its position is known.
+    ///
+    /// Double and triple clicks are detected after deduplication, so switch
+    /// bounce and duplicate platform events never form a double click.
+    pub fn record_click_at(&self, button: String, cell: Option<usize>) {
+        self.check_rollover();
+        let Some(app) = self.tracked_app() else {
+            return;
+        };
+        
+        let now = self.clock.now_instant();
+        if self.is_duplicate(&self.last_click, &button, now) {
+            return;
+        }
+        let window = self.multi_click_window.read().map(|w| *w).unwrap_or(DEFAULT_MULTI_CLICK_WINDOW);
+        let run = self.clicks.lock().map(|mut c| c.observe(&button, now, window)).unwrap_or(1);
+        
+        self.push_event(EventKind::Click, &button);
+        if let Ok(mut stats) = self.stats.write() {
+            stats.record_multi_click(&button, run);
+            stats.record_click(button);
+            stats.record_app_click(app);
+            stats.record_device_click(self.current_device());
+            if let Some(cell) = cell {
+                stats.record_click_position(cell);
+            }
+            self.check_milestones(&mut stats, Measure::Clicks);
+            self.mark_changed();
+        }
+    }
+    
+    /// Record mouse movement, `dragging` while a mouse button is held
+    pub fn record_movement(&self, distance: f64, dragging: bool) {
+        self.check_rollover();
+        if self.tracked_app().is_none() {
+            return;
+        }
+        if let Ok(mut stats) = self.stats.write() {
+            stats.record_movement(distance, dragging);
+            self.check_milestones(&mut stats, Measure::Distance);
+            self.mark_changed();
+        }
+    }
+    
+    /// Record a completed drag
+    pub fn record_drag(&self) {
+        if self.tracked_app().is_none() {
+            return;
+        }
+        if let Ok(mut stats) = self.stats.write() {
+            stats.record_drag();
+            self.mark_changed();
+        }
+    }
+    
+    /// Record the cursor position on a display of `display` (width, height)
+    /// pixels, at most once per `mouse_grid::SAMPLE_INTERVAL`
+    pub fn record_mouse_position(&self, x: f64, y: f64, display: (u64, u64)) {
+        let now = self.clock.now_instant();
+        if !self.positions.lock().is_ok_and(|mut sampler| sampler.sample(now)) {
+            return;
+        }
+        if self.tracked_app().is_none() {
+            return;
+        }
+        if let Ok(mut stats) = self.stats.write() {
+            stats.record_mouse_position(mouse_grid::cell(x, y, display));
+            self.mark_changed();
+        }
+    }
+    
+    /// Record scroll
+    pub fn record_scroll(&self, delta: i64) {
+        if self.tracked_app().is_none() {
+            return;
+        }
+        if let Ok(mut stats) = self.stats.write() {
+            stats.record_scroll(delta);
+            self.mark_changed();
+        }
+    }
+    
+    /// Merge a stats file from another installation into the current stats
+    pub fn merge_from_file(&self, path: &Path) -> Result<MergeReport, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        let other = migrate::stats_from_json(&content)
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut stats = self.stats.write().map_err(|e| e.to_string())?;
+        let report = stats.merge(&other);
+        self.check_key_milestones(&mut stats);
+        self.mark_changed();
+        Ok(report)
+    }
+    
+    /// Write the days from `from` to `to` (inclusive) and their totals to
+    /// `path` as JSON, returning the number of days written
+    pub fn export_range(&self, from: NaiveDate, to: NaiveDate, path: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+        let export = self.snapshot().range_export(from, to)?;
+        fs::write(path, serde_json::to_string_pretty(&export)?)?;
+        log::info!("Exported {} day(s) from {} to {} to {}", export.daily_stats.len(), from, to, path.display());
+        Ok(export.daily_stats.len())
+    }
+    
+    /// Merge a stats file into the current stats and save the result
+    pub fn import_and_merge(&self, path: &Path) -> Result<MergeReport, Box<dyn std::error::Error>> {
+        let report = self.merge_from_file(path)?;
+        self.save()?;
+        log::info!("Imported {}: {} keys, {} day(s)", path.display(), report.keys_added, report.days_merged);
+        Ok(report)
+    }
+    
+    /// Rename the recorded keys to their aliases for good, summing keys that
+    /// end up with the same name. The stats store is copied aside first.
+    pub fn normalize_keys(&self) -> Result<NormalizeReport, Box<dyn std::error::Error>> {
+        let aliases = self.key_aliases.read().map_err(|e| e.to_string())?.clone();
+        let needed = {
+            let stats = self.stats.read().map_err(|e| e.to_string())?;
+            stats.key_counts.keys().chain(stats.key_repeats.keys()).any(|key| aliases.resolve(key).is_some())
+        };
+        if !needed {
+            return Ok(NormalizeReport::default());
+        }
+        
+        let path = self.store.path();
+        let mut backup = None;
+        if path.exists() {
+            let copy = store::sibling_path(path, &format!("{}.bak", self.clock.now_local().format("%Y%m%d-%H%M%S")));
+            fs::copy(path, &copy)?;
+            backup = Some(copy);
+        }
+        let renamed = {
+            let mut stats = self.stats.write().map_err(|e| e.to_string())?;
+            let renamed = stats.apply_key_aliases(&aliases);
+            self.mark_changed();
+            renamed
+        };
+        self.force_save()?;
+        log::info!("Renamed {} key(s)", renamed.len());
+        Ok(NormalizeReport { backup, renamed })
+    }
+    
+    /// Get a snapshot of current stats
+    pub fn snapshot(&self) -> Stats {
+        self.stats.read()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Relative change from `previous` to `current` in percent,
+/// or `None` when there is nothing to compare against
+pub fn percent_change(current: f64, previous: f64) -> Option<f64> {
+    (previous > 0.0).then(|| (current - previous) / previous * 100.0)
+}
+
+/// Keys that undo typing, counted for the correction rate
+fn is_deletion_key(key: &str) -> bool {
+    matches!(key, "Backspace" | "Delete")
+}
+
+/// `part / total`, or 0 when there is no total
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Key presses left until the next key milestone, `u64::MAX` past the last
+fn keys_to_next_milestone(stats: &Stats) -> u64 {
+    stats.next_milestone().map_or(u64::MAX, |(target, current, _)| target - current)
+}
+
+/// Add every count in `from` to the matching entry of `into`
+fn add_counts<K: Clone + Eq + std::hash::Hash>(into: &mut HashMap<K, u64>, from: &HashMap<K, u64>) {
+    for (key, count) in from {
+        *into.entry(key.clone()).or_insert(0) += count;
+    }
+}
+
+/// Case-insensitive substring match of an application name against lowercased patterns
+fn is_excluded(app: &str, patterns: &[String]) -> bool {
+    let app = app.to_lowercase();
+    patterns.iter().any(|pattern| app.contains(pattern.as_str()))
+}
+
+use chrono::Timelike;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clock::MockClock;
+
+    /// Empty directory under the system temp directory, removed on drop
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("rust-finger-test-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn join(&self, name: &str) -> PathBuf {
+            self.0.join(name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// In-memory manager reading the time from `clock`
+    fn clocked_manager(clock: Arc<MockClock>) -> StatsManager {
+        StatsManager::with_store_and_clock(Box::new(store::MemoryStore::default()), clock)
+    }
+
+    /// Store that only counts how often it is written
+    struct CountingStore {
+        saves: Arc<AtomicU64>,
+    }
+
+    impl StatsStore for CountingStore {
+        fn load(&self) -> store::StoreResult<Stats> {
+            Ok(Stats::new())
+        }
+
+        fn save(&self, _stats: &Stats) -> store::StoreResult<()> {
+            self.saves.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn append_events(&self, _events: &[StoredEvent]) -> store::StoreResult<()> {
+            Ok(())
+        }
+
+        fn path(&self) -> &Path {
+            Path::new("counting")
+        }
+    }
+
+    #[test]
+    fn save_skips_unchanged_stats() {
+        let saves = Arc::new(AtomicU64::new(0));
+        let manager = StatsManager::with_store(Box::new(CountingStore { saves: saves.clone() }));
+
+        manager.record_key("A".to_string());
+        manager.save().unwrap();
+        manager.save().unwrap();
+        assert_eq!(saves.load(Ordering::SeqCst), 1);
+
+        manager.record_scroll(1);
+        manager.save().unwrap();
+        assert_eq!(saves.load(Ordering::SeqCst), 2);
+
+        manager.force_save().unwrap();
+        assert_eq!(saves.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn idle_time_counts_from_the_last_input() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        assert_eq!(manager.idle_time(), None);
+
+        manager.record_key("A".to_string());
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(manager.idle_time(), Some(Duration::from_secs(3)));
+        manager.record_click("Left".to_string());
+        assert_eq!(manager.idle_time(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn dedup_window_collapses_identical_events() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        let press = |key: &str| {
+            manager.record_key(key.to_string());
+            manager.record_key_release(key);
+        };
+
+        press("L");
+        clock.advance(Duration::from_millis(30));
+        press("L");
+        assert_eq!(manager.snapshot().total_keys(), 1);
+        clock.advance(DEFAULT_DEDUP_WINDOW);
+        press("L");
+        assert_eq!(manager.snapshot().total_keys(), 2);
+
+        manager.set_dedup_window(Duration::ZERO);
+        press("L");
+        press("L");
+        assert_eq!(manager.snapshot().total_keys(), 4);
+
+        manager.set_dedup_window(Duration::from_millis(200));
+        clock.advance(Duration::from_millis(150));
+        manager.record_click("Left".to_string());
+        manager.record_click("Left".to_string());
+        assert_eq!(manager.snapshot().mouse_clicks["Left"], 1);
+    }
+
+    #[test]
+    fn in_memory_manager_keeps_saved_stats() {
+        let manager = StatsManager::new_in_memory();
+        assert_eq!(manager.data_path(), Path::new(":memory:"));
+        manager.record_key("A".to_string());
+        manager.save().unwrap();
+        assert_eq!(manager.snapshot().total_keys(), 1);
+    }
+
+    #[test]
+    fn manager_counts_today_keys() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        for key in ["A", "B", "A", "Space"] {
+            clock.advance(Duration::from_millis(100));
+            manager.record_key(key.to_string());
+            manager.record_key_release(key);
+        }
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.today_keys(), 4);
+        assert_eq!(stats.daily_stats["2024-05-01"].hourly_keys[&10], 4);
+        assert_eq!(stats.key_counts["A"], 2);
+    }
+
+    #[test]
+    fn manager_attributes_input_to_devices() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("A".to_string());
+        manager.set_current_device(Some("Laptop Keyboard"));
+        clock.advance(Duration::from_millis(100));
+        manager.record_key("B".to_string());
+        clock.advance(Duration::from_millis(100));
+        manager.record_key("C".to_string());
+        manager.set_current_device(Some("USB Mouse"));
+        manager.record_click("Left".to_string());
+
+        let devices = manager.snapshot().devices();
+        let totals: Vec<_> = devices.iter().map(|d| (d.name.as_str(), d.keys, d.clicks)).collect();
+        assert_eq!(totals, vec![("Laptop Keyboard", 2, 0), ("USB Mouse", 0, 1), (DEFAULT_DEVICE, 1, 0)]);
+        assert_eq!(devices[0].last_seen, Some(clock.now_local()));
+    }
+
+    #[test]
+    fn saves_checkpoint_the_running_session() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.force_save().unwrap();
+        assert!(manager.snapshot().sessions.is_empty());
+
+        manager.record_key("A".to_string());
+        clock.advance(Duration::from_secs(60));
+        manager.force_save().unwrap();
+        manager.record_click("Left".to_string());
+        clock.advance(Duration::from_secs(60));
+        manager.force_save().unwrap();
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.sessions.len(), 1);
+        let session = &stats.sessions[0];
+        assert_eq!((session.keys, session.clicks), (1, 1));
+        assert_eq!(session.duration(), Duration::from_secs(120));
+
+        // The running session is listed once, ending now
+        clock.advance(Duration::from_secs(60));
+        let listed = manager.snapshot().sessions();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].duration(), Duration::from_secs(180));
+    }
+
+    /// Manager whose store already holds `keys` presses of "A", reading the time from `clock`
+    fn manager_with_keys(keys: u64, clock: Arc<MockClock>) -> StatsManager {
+        let store = store::MemoryStore::default();
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), keys);
+        store.save(&stats).unwrap();
+        StatsManager::with_store_and_clock(Box::new(store), clock)
+    }
+
+    #[test]
+    fn crossing_a_milestone_queues_it_once() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = manager_with_keys(49_999, clock.clone());
+        assert!(!manager.snapshot().achieved.contains_key("keys-50k"));
+        manager.record_key("B".to_string());
+        clock.advance(Duration::from_millis(60));
+        manager.record_key("C".to_string());
+
+        assert_eq!(manager.take_announcements(), ["⌨️ Milestone unlocked: 50,000 keys"]);
+        assert!(manager.take_announcements().is_empty());
+        assert!(manager.snapshot().achieved.contains_key("keys-50k"));
+    }
+
+    #[test]
+    fn streak_milestones_use_the_configured_minimum() {
+        let store = store::MemoryStore::default();
+        let mut stats = Stats::new();
+        for day in date("2024-05-01").iter_days().rev().take(100) {
+            stats.daily_stats.insert(day.format("%Y-%m-%d").to_string(), DailyStats { total_keys: 10, ..Default::default() });
+        }
+        store.save(&stats).unwrap();
+        let manager = StatsManager::with_store_and_clock(Box::new(store), MockClock::at(2024, 5, 1, 10, 0));
+        assert!(!manager.snapshot().achieved.contains_key("streak-100"));
+
+        manager.set_streak_min_keys(50);
+        assert!(!manager.snapshot().achieved.contains_key("streak-100"));
+        manager.set_streak_min_keys(10);
+        assert!(manager.snapshot().achieved.contains_key("streak-100"));
+        assert!(manager.take_announcements().is_empty());
+    }
+
+    #[test]
+    fn next_milestone_fills_from_the_previous_one() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.next_milestone(), Some((1_000, 0, 0.0)));
+        stats.key_counts.insert("A".to_string(), 30_000);
+        assert_eq!(stats.next_milestone(), Some((50_000, 30_000, 0.5)));
+        stats.anonymous_keys = 20_000;
+        assert_eq!(stats.next_milestone(), Some((100_000, 50_000, 0.0)));
+        stats.anonymous_keys = 10_000_000;
+        assert_eq!(stats.next_milestone(), None);
+    }
+
+    #[test]
+    fn milestones_reached_before_tracking_unlock_silently() {
+        let manager = manager_with_keys(150_000, MockClock::at(2024, 5, 1, 10, 0));
+        manager.record_key("B".to_string());
+
+        let achieved = manager.snapshot().achieved;
+        assert!(achieved.contains_key("keys-10k") && achieved.contains_key("keys-100k"));
+        assert!(!achieved.contains_key("keys-1m"));
+        assert!(manager.take_announcements().is_empty());
+    }
+
+    #[test]
+    fn devices_stay_connected_while_a_handle_is_open() {
+        let manager = StatsManager::new_in_memory();
+        manager.set_device_connected("Keyboard", true);
+        manager.set_device_connected("Keyboard", true);
+        manager.set_device_connected("Keyboard", false);
+        assert!(manager.is_device_connected("Keyboard"));
+        manager.set_device_connected("Keyboard", false);
+        assert!(!manager.is_device_connected("Keyboard"));
+        manager.set_device_connected("Mouse", false);
+        assert!(!manager.is_device_connected("Mouse"));
+    }
+
+    #[test]
+    fn manager_samples_cursor_positions() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        // A burst of moves counts once per sample interval
+        for x in [5.0, 10.0, 15.0, 20.0] {
+            manager.record_mouse_position(x, 10.0, (1920, 1080));
+            clock.advance(Duration::from_millis(20));
+        }
+        clock.advance(mouse_grid::SAMPLE_INTERVAL);
+        // Off the right edge of the primary display
+        manager.record_mouse_position(5000.0, 10.0, (1920, 1080));
+
+        let grid = manager.snapshot().mouse_position_grid;
+        assert_eq!(grid.iter().sum::<u64>(), 3);
+        assert_eq!(grid[0], 2);
+        assert_eq!(grid[mouse_grid::GRID_COLUMNS - 1], 1);
+    }
+
+    #[test]
+    fn manager_places_clicks_on_grid() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_click_at("Left".to_string(), Some(7));
+        clock.advance(Duration::from_millis(100));
+        manager.record_click_at("Left".to_string(), None);
+        // Duplicates are dropped from the grid too
+        manager.record_click_at("Left".to_string(), Some(7));
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.today_clicks(), 2);
+        assert_eq!(stats.click_position_grid.iter().sum::<u64>(), 1);
+        assert_eq!(stats.click_position_grid[7], 1);
+        assert!(stats.mouse_position_grid.is_empty());
+    }
+
+    #[test]
+    fn unnamed_buttons_count_as_other() {
+        let mut stats = Stats::new();
+        for button in ["Left", "Back", "Forward", "Button(12)", "Button(12)", "Button(13)"] {
+            stats.record_click(button.to_string());
+        }
+        assert_eq!(stats.other_button_clicks(), 3);
+        assert_eq!(stats.mouse_clicks.get("Back"), Some(&1));
+    }
+
+    #[test]
+    fn manager_counts_double_and_triple_clicks() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        let click_after = |ms: u64, button: &str| {
+            clock.advance(Duration::from_millis(ms));
+            manager.record_click(button.to_string());
+        };
+        // Double click with switch bounce on the second press
+        click_after(0, "Left");
+        click_after(150, "Left");
+        click_after(20, "Left");
+        // Pause, then a triple click
+        click_after(1000, "Left");
+        click_after(200, "Left");
+        click_after(200, "Left");
+        // Too slow for a double click
+        click_after(1000, "Right");
+        click_after(500, "Right");
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.mouse_clicks.get("Left"), Some(&5));
+        // The triple click is not counted as a double click as well
+        assert_eq!(stats.double_clicks.get("Left"), Some(&1));
+        assert_eq!(stats.triple_clicks.get("Left"), Some(&1));
+        assert_eq!(stats.mouse_clicks.get("Right"), Some(&2));
+        assert!(!stats.double_clicks.contains_key("Right"));
+
+        // A longer window turns the slow pair into a double click
+        manager.set_multi_click_window(Duration::from_millis(600));
+        click_after(500, "Right");
+        assert_eq!(manager.snapshot().double_clicks.get("Right"), Some(&1));
+    }
+
+    #[test]
+    fn manager_wpm_over_synthetic_typing() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        // 30 alternating letters, one every 2 seconds
+        for i in 0..30 {
+            if i > 0 {
+                clock.advance(Duration::from_secs(2));
+            }
+            let key = if i % 2 == 0 { "A" } else { "S" };
+            manager.record_key(key.to_string());
+            manager.record_key_release(key);
+        }
+        assert_eq!(manager.snapshot().current_wpm(), 30.0 / DEFAULT_WORD_LENGTH);
+
+        // The first 10 presses fall out of the one-minute window
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(manager.snapshot().current_wpm(), 20.0 / DEFAULT_WORD_LENGTH);
+    }
+
+    #[test]
+    fn drags_are_part_of_the_distance() {
+        let manager = StatsManager::new_in_memory();
+        manager.record_movement(40.0, false);
+        manager.record_movement(25.0, true);
+        manager.record_drag();
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.today_distance(), 65.0);
+        assert_eq!(stats.today_drags(), (1, 25.0));
+        assert_eq!((stats.drag_count, stats.drag_distance), (1, 25.0));
+    }
+
+    #[test]
+    fn averages_over_active_time() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        // Under a minute of activity has no average yet
+        manager.record_key("A".to_string());
+        manager.record_key_release("A");
+        assert_eq!(manager.snapshot().avg_keys_per_minute(), 0.0);
+
+        // 60 more keys a second apart, then a click after a long break
+        for i in 0..60 {
+            clock.advance(Duration::from_secs(1));
+            let key = if i % 2 == 0 { "S" } else { "A" };
+            manager.record_key(key.to_string());
+            manager.record_key_release(key);
+        }
+        clock.advance(Duration::from_secs(600));
+        manager.record_click("Left".to_string());
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.total_active_seconds, 60.0);
+        assert_eq!(stats.avg_keys_per_minute(), 61.0);
+        assert_eq!(stats.avg_clicks_per_minute(), 1.0);
+    }
+
+    #[test]
+    fn listener_is_claimed_once() {
+        let manager = StatsManager::new_in_memory();
+        assert!(manager.claim_listener());
+        assert!(!manager.claim_listener());
+
+        // After a failure the listener can be started again
+        manager.set_listener_error("denied".to_string());
+        manager.set_listener_active(false);
+        assert!(manager.claim_listener());
+        assert_eq!(manager.get_listener_error(), None);
+    }
+
+    #[test]
+    fn bigrams_count_quick_successions() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        for (key, gap_ms) in [("T", 0), ("H", 300), ("E", 300), ("T", 1500), ("H", 200)] {
+            clock.advance(Duration::from_millis(gap_ms));
+            manager.record_key(key.to_string());
+            manager.record_key_release(key);
+        }
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.top_bigrams(5), vec![
+            ("T".to_string(), "H".to_string(), 2),
+            ("H".to_string(), "E".to_string(), 1),
+        ]);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"T→H\":2"));
+        let loaded: Stats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.bigram_counts, stats.bigram_counts);
+    }
+
+    #[test]
+    fn held_key_counts_single_press() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("Backspace".to_string());
+        for _ in 0..5 {
+            clock.advance(Duration::from_millis(60));
+            manager.record_key("Backspace".to_string());
+        }
+        manager.record_key_release("Backspace");
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("Backspace"), Some(&1));
+        assert_eq!(stats.key_repeats.get("Backspace"), Some(&5));
+        assert_eq!(stats.today_keys(), 1);
+    }
+
+    #[test]
+    fn release_between_presses_counts_each_press() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        for _ in 0..3 {
+            manager.record_key("A".to_string());
+            manager.record_key_release("A");
+            clock.advance(DEFAULT_DEDUP_WINDOW);
+        }
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("A"), Some(&3));
+        assert!(stats.key_repeats.is_empty());
+    }
+
+    #[test]
+    fn count_repeats_restores_legacy_counting() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.set_count_repeats(true);
+        manager.record_key("↓".to_string());
+        clock.advance(Duration::from_millis(60));
+        manager.record_key("↓".to_string());
+        manager.record_key_release("↓");
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("↓"), Some(&2));
+        assert_eq!(stats.key_repeats.get("↓"), Some(&1));
+    }
+
+    #[test]
+    fn fast_auto_repeat_is_counted_despite_dedup() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("Backspace".to_string());
+        for _ in 0..4 {
+            clock.advance(Duration::from_millis(30));
+            manager.record_key("Backspace".to_string());
+        }
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("Backspace"), Some(&1));
+        assert_eq!(stats.key_repeats.get("Backspace"), Some(&4));
+    }
+
+    #[test]
+    fn lost_release_does_not_hold_key_forever() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("A".to_string());
+        clock.advance(HOLD_TIMEOUT + Duration::from_millis(1));
+        manager.record_key("A".to_string());
+        manager.record_key_release("A");
+
+        // Releases lost with a dropped event or an unplugged device
+        clock.advance(Duration::from_millis(100));
+        manager.record_key("A".to_string());
+        manager.record_dropped_event();
+        clock.advance(Duration::from_millis(100));
+        manager.record_key("A".to_string());
+        manager.set_device_connected("Keyboard", true);
+        manager.set_device_connected("Keyboard", false);
+        clock.advance(Duration::from_millis(100));
+        manager.record_key("A".to_string());
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("A"), Some(&5));
+        assert!(stats.key_repeats.is_empty());
+    }
+
+    #[test]
+    fn copies_of_a_press_are_not_repeats() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        // The global listener and the focused dashboard both see the press
+        manager.record_key("A".to_string());
+        clock.advance(Duration::from_millis(3));
+        manager.record_key("A".to_string());
+        manager.record_key_release("A");
+        manager.record_key_release("A");
+
+        manager.set_count_repeats(true);
+        clock.advance(Duration::from_millis(200));
+        manager.record_key("B".to_string());
+        clock.advance(Duration::from_millis(3));
+        manager.record_key("B".to_string());
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("A"), Some(&1));
+        assert_eq!(stats.key_counts.get("B"), Some(&1));
+        assert!(stats.key_repeats.is_empty());
+    }
+
+    fn stats_with_active_days(days: &[&str]) -> Stats {
+        let mut stats = Stats::new();
+        for day in days {
+            stats.daily_stats.insert(day.to_string(), DailyStats { total_keys: 10, ..Default::default() });
+        }
+        stats
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn streak_broken_by_skipped_day() {
+        // 2024-03-03 is missing
+        let stats = stats_with_active_days(&[
+            "2024-02-28", "2024-02-29", "2024-03-01", "2024-03-02",
+            "2024-03-04", "2024-03-05",
+        ]);
+
+        assert_eq!(stats.longest_streak(1), 4);
+        assert_eq!(stats.current_streak_on(date("2024-03-05"), 1), 2);
+    }
+
+    #[test]
+    fn current_streak_tolerates_quiet_today_only() {
+        let mut stats = stats_with_active_days(&["2024-12-30", "2024-12-31"]);
+        stats.daily_stats.insert("2025-01-01".to_string(), DailyStats::default());
+
+        // Today has no keys yet, but yesterday keeps the streak alive
+        assert_eq!(stats.current_streak_on(date("2025-01-01"), 1), 2);
+        // Both today and yesterday missing resets it
+        assert_eq!(stats.current_streak_on(date("2025-01-02"), 1), 0);
+        assert_eq!(stats_with_active_days(&[]).longest_streak(1), 0);
+    }
+
+    #[test]
+    fn single_day_is_a_streak_of_one() {
+        let stats = stats_with_active_days(&["2024-06-10"]);
+        assert_eq!(stats.longest_streak(1), 1);
+        assert_eq!(stats.current_streak_on(date("2024-06-10"), 1), 1);
+        assert_eq!(stats.current_streak_on(date("2024-06-11"), 1), 1);
+    }
+
+    #[test]
+    fn streak_skips_unparsable_dates_and_quiet_days() {
+        let mut stats = stats_with_active_days(&["2024-06-08", "2024-06-09", "2024-06-10"]);
+        stats.daily_stats.insert("not-a-date".to_string(), DailyStats { total_keys: 500, ..Default::default() });
+        stats.daily_stats.get_mut("2024-06-09").unwrap().total_keys = 3;
+
+        assert_eq!(stats.longest_streak(1), 3);
+        // With a higher bar the quieter day breaks the run
+        assert_eq!(stats.longest_streak(5), 1);
+        assert_eq!(stats.current_streak_on(date("2024-06-10"), 5), 1);
+        assert_eq!(stats.current_streak_on(date("2024-06-10"), 0), 3);
+    }
+
+    #[test]
+    fn peak_hour_and_day() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.peak_hour(), None);
+        assert_eq!(stats.peak_day(), None);
+
+        stats.hourly_key_counts.extend([(9, 120), (15, 300), (21, 300), (3, 0)]);
+        for (date, keys) in [("2024-06-11", 500), ("2024-06-12", 900), ("2024-06-13", 900)] {
+            stats.daily_stats.insert(date.to_string(), DailyStats { total_keys: keys, ..Default::default() });
+        }
+
+        // Ties go to the earlier hour and day
+        assert_eq!(stats.peak_hour(), Some((15, 300)));
+        assert_eq!(stats.peak_day(), Some(("2024-06-12".to_string(), 900)));
+    }
+
+    #[test]
+    fn splits_weekdays_from_weekends() {
+        let mut stats = Stats::new();
+        let (weekdays, weekends) = stats.weekday_weekend_split();
+        assert_eq!((weekdays.total_keys, weekends.total_keys), (0, 0));
+
+        // Friday, Saturday, Sunday, Monday
+        for (date, keys) in [("2024-06-14", 100), ("2024-06-15", 20), ("2024-06-16", 30), ("2024-06-17", 400)] {
+            let day = DailyStats { total_keys: keys, total_clicks: keys / 10, total_distance: 1.5, ..Default::default() };
+            stats.daily_stats.insert(date.to_string(), day);
+        }
+
+        let (weekdays, weekends) = stats.weekday_weekend_split();
+        assert_eq!((weekdays.total_keys, weekdays.total_clicks, weekdays.total_distance), (500, 50, 3.0));
+        assert_eq!((weekends.total_keys, weekends.total_clicks, weekends.total_distance), (50, 5, 3.0));
+    }
+
+    #[test]
+    fn hand_balance_skips_unmapped_keys() {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 3);
+        stats.key_counts.insert("J".to_string(), 5);
+        stats.key_counts.insert("Space".to_string(), 2);
+        stats.key_counts.insert("Key(172)".to_string(), 7);
+
+        assert_eq!(stats.hand_balance(), (3, 5, 2));
+    }
+
+    #[test]
+    fn finger_usage_sums_by_finger() {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("F".to_string(), 4);
+        stats.key_counts.insert("G".to_string(), 1);
+        stats.key_counts.insert("Shift".to_string(), 9);
+
+        let usage = stats.finger_load_with(&FingerMap::from_table(&[("F", Finger::LeftIndex), ("G", Finger::LeftIndex)]));
+        assert_eq!(usage.get(&Finger::LeftIndex), Some(&5));
+        assert_eq!(usage.get(&Finger::LeftPinky), Some(&5));
+        assert_eq!(usage.values().sum::<u64>(), 14);
+    }
+
+    #[test]
+    fn finger_load_attributes_unmapped_keys() {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("F".to_string(), 4);
+        stats.key_counts.insert("F4".to_string(), 2);
+        stats.key_counts.insert("Shift".to_string(), 9);
+        stats.key_counts.insert("Key(172)".to_string(), 7);
+
+        let load = stats.finger_load();
+        assert_eq!(load.get(&Finger::LeftIndex), Some(&6));
+        assert_eq!(load.get(&Finger::LeftPinky), Some(&5));
+        assert_eq!(load.get(&Finger::RightPinky), Some(&4));
+        assert_eq!(load.values().sum::<u64>(), 15);
+    }
+
+    #[test]
+    fn top_keys_filtered_by_category() {
+        let mut stats = Stats::new();
+        for (key, count) in [("A", 5), ("Shift", 9), ("1", 3), ("B", 2), (";", 1), ("Space", 20)] {
+            stats.key_counts.insert(key.to_string(), count);
+        }
+
+        let letters = stats.top_keys_filtered(10, KeyCategory::Letters);
+        assert_eq!(letters, vec![("A".to_string(), 5), ("B".to_string(), 2)]);
+        assert_eq!(stats.top_keys_filtered(1, KeyCategory::Modifiers), vec![("Shift".to_string(), 9)]);
+        assert_eq!(stats.top_keys_filtered(1, KeyCategory::All), vec![("Space".to_string(), 20)]);
+        assert_eq!(stats.top_keys_filtered(10, KeyCategory::All).len(), 6);
+    }
+
+    #[test]
+    fn key_aliases_rename_recorded_keys() {
+        let mut stats = Stats::new();
+        for (key, count) in [("Pause", 2), ("PauseBreak", 3), ("Key(191)", 4), ("A", 1)] {
+            stats.key_counts.insert(key.to_string(), count);
+        }
+        stats.bigram_counts.insert(("Kp1".to_string(), "KP1".to_string()), 5);
+        stats.bigram_counts.insert(("KP1".to_string(), "KP1".to_string()), 1);
+
+        let custom = [("Key(191)".to_string(), "Macro1".to_string())].into_iter().collect();
+        stats.apply_key_aliases(&KeyAliases::new(custom).unwrap());
+        assert_eq!(stats.key_counts.get("PauseBreak"), Some(&5));
+        assert_eq!(stats.key_counts.get("Macro1"), Some(&4));
+        assert_eq!(stats.key_counts.get("A"), Some(&1));
+        assert_eq!(stats.key_counts.len(), 3);
+        assert_eq!(stats.bigram_counts.get(&("KP1".to_string(), "KP1".to_string())), Some(&6));
+    }
+
+    #[test]
+    fn session_key_counts_start_empty_and_skip_privacy_mode() {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 40);
+        stats.record_key("A".to_string());
+        stats.set_privacy_mode(true);
+        stats.record_key("B".to_string());
+
+        assert_eq!(stats.session_key_counts, HashMap::from([("A".to_string(), 1)]));
+        let reloaded: Stats = serde_json::from_str(&serde_json::to_string(&stats).unwrap()).unwrap();
+        assert!(reloaded.session_key_counts.is_empty());
+        assert_eq!(reloaded.key_counts["A"], 41);
+    }
+
+    fn no_label(_key: &str) -> Option<&'static str> {
+        None
+    }
+
+    #[test]
+    fn searching_top_keys_keeps_ranks() {
+        let mut stats = Stats::new();
+        for (key, count) in [("Space", 50), ("E", 40), ("Backspace", 30), ("S", 30), ("P", 2)] {
+            stats.key_counts.insert(key.to_string(), count);
+        }
+
+        let found = stats.search_top_keys("SPACE", 2, KeyCategory::All, no_label);
+        assert_eq!(found, vec![(1, "Space".to_string(), 50), (3, "Backspace".to_string(), 30)]);
+        // Beyond the top n, too
+        assert_eq!(stats.search_top_keys(" p ", 2, KeyCategory::All, no_label).last(), Some(&(5, "P".to_string(), 2)));
+        assert_eq!(stats.search_top_keys("", 2, KeyCategory::All, no_label).len(), 2);
+        assert!(stats.search_top_keys("tab", 2, KeyCategory::All, no_label).is_empty());
+        // Ranks within the category
+        assert_eq!(stats.search_top_keys("s", 10, KeyCategory::Letters, no_label), vec![(2, "S".to_string(), 30)]);
+        // Labels are searched as well
+        let label = |key: &str| (key == "Space").then_some("Leertaste");
+        assert_eq!(stats.search_top_keys("leer", 2, KeyCategory::All, label), vec![(1, "Space".to_string(), 50)]);
+    }
+
+    #[test]
+    fn top_keys_sorted_and_truncated() {
+        let mut stats = Stats::new();
+        for (key, count) in [("E", 40), ("T", 30), ("A", 25), ("Q", 1), ("Z", 2)] {
+            stats.key_counts.insert(key.to_string(), count);
+        }
+
+        let top = stats.top_keys_filtered(3, KeyCategory::All);
+        assert_eq!(top, vec![("E".to_string(), 40), ("T".to_string(), 30), ("A".to_string(), 25)]);
+        assert_eq!(stats.top_keys_filtered(0, KeyCategory::All), vec![]);
+        assert_eq!(stats.top_keys_filtered(10, KeyCategory::All).last(), Some(&("Q".to_string(), 1)));
+    }
+
+    #[test]
+    fn top_apps_ranks_by_keys_and_clicks() {
+        let mut stats = Stats::new();
+        for _ in 0..3 {
+            stats.record_app_key("editor".to_string());
+        }
+        stats.record_app_key("browser".to_string());
+        for _ in 0..4 {
+            stats.record_app_click("browser".to_string());
+        }
+        stats.record_app_click("terminal".to_string());
+
+        let top = stats.top_apps(2);
+        assert_eq!(top, vec![
+            ("browser".to_string(), 1, 4),
+            ("editor".to_string(), 3, 0),
+        ]);
+    }
+
+    #[test]
+    fn summary_text_reports_day_totals() {
+        let mut stats = Stats::new();
+        stats.daily_stats.insert("2024-05-01".to_string(), DailyStats {
+            total_keys: 1200,
+            total_clicks: 340,
+            total_distance: 56_780.0,
+            peak_wpm: 72.4,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            stats.summary_text_for("2024-05-01"),
+            "Daily summary for 2024-05-01: 1200 keys, 340 clicks, 56.78 m mouse distance, peak 72 WPM"
+        );
+        assert!(stats.summary_text_for("2024-05-02").contains(": 0 keys"));
+    }
+
+    #[test]
+    fn clipboard_summary_formats_today() {
+        let mut stats = Stats::with_clock(MockClock::at(2024, 5, 1, 18, 0));
+        stats.daily_stats.insert("2024-05-01".to_string(), DailyStats {
+            total_keys: 14_230,
+            total_clicks: 1_802,
+            total_distance: 34_400.0,
+            peak_wpm: 86.6,
+            active_seconds: 2.0 * 3600.0 + 5.0 * 60.0,
+            ..Default::default()
+        });
+        stats.key_counts.insert("A".to_string(), 1_234_567);
+        stats.mouse_clicks.insert("Left".to_string(), 98_765);
+        stats.mouse_distance = 12_345_000.0;
+
+        assert_eq!(stats.summary_text(false), "Today: 14,230 keys, 1,802 clicks, 34 m mouse travel, peak 87 WPM");
+        let lines: Vec<String> = stats.summary_text(true).lines().map(str::to_string).collect();
+        assert_eq!(lines, [
+            "Finger Monitor · 2024-05-01",
+            "Keys: 14,230",
+            "Clicks: 1,802",
+            "Mouse travel: 34 m",
+            "Peak WPM: 87",
+            "Active time: 2h 05m",
+            "All time: 1,234,567 keys, 98,765 clicks, 12.35 km mouse travel",
+        ]);
+    }
+
+    #[test]
+    fn peak_wpm_tracks_daily_maximum() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        for _ in 0..10 {
+            stats.record_key("A".to_string());
+        }
+        // A slower minute later in the day keeps the earlier peak
+        clock.advance(Duration::from_secs(120));
+        stats.record_key("A".to_string());
+        assert_eq!(stats.daily_stats["2024-05-01"].peak_wpm, 2.0);
+    }
+
+    #[test]
+    fn wpm_counts_only_character_keys() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        // "hello world" typed with a typo, plus a save shortcut and some cursor movement
+        let sequence = [
+            "Shift", "H", "E", "L", "L", "P", "Backspace", "O", "Space",
+            "W", "O", "R", "L", "D", "Enter", "Ctrl", "S", "↑", "↑", "←",
+        ];
+        for key in sequence {
+            stats.record_key(key.to_string());
+        }
+
+        // The old formula counted every press: 20 / 5
+        let all_keys = sequence.len() as f64 / DEFAULT_WORD_LENGTH;
+        assert_eq!(all_keys, 4.0);
+        // 14 character keys (including the typo and the S of Ctrl+S)
+        assert_eq!(stats.current_wpm(), 14.0 / DEFAULT_WORD_LENGTH);
+        // One Backspace takes back one character
+        assert_eq!(stats.corrected_wpm(), 13.0 / DEFAULT_WORD_LENGTH);
+
+        stats.word_length = Some(7.0);
+        assert_eq!(stats.current_wpm(), 2.0);
+    }
+
+    #[test]
+    fn correction_ratio_counts_backspace_and_delete() {
+        let mut stats = Stats::new();
+        assert_eq!(stats.correction_ratio(), 0.0);
+        assert_eq!(stats.today_correction_ratio(), 0.0);
+
+        for key in ["H", "I", "Backspace", "O", "Delete", "Space", "Y", "O"] {
+            stats.record_key(key.to_string());
+        }
+        assert_eq!(stats.correction_ratio(), 0.25);
+        assert_eq!(stats.today_correction_ratio(), 0.25);
+        assert_eq!(stats.daily_stats[&stats.today_key()].backspaces, 2);
+
+        // Older files without the field load with no corrections
+        let day: DailyStats = serde_json::from_str(r#"{"total_keys": 10}"#).unwrap();
+        assert_eq!(day.backspaces, 0);
+    }
+
+    #[test]
+    fn apm_counts_keys_and_clicks_and_decays() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        for key in ["Q", "W", "Ctrl"] {
+            stats.record_key(key.to_string());
+        }
+        stats.record_click("Left".to_string());
+        stats.record_click("Right".to_string());
+        assert_eq!(stats.current_apm(), 5.0);
+        assert_eq!(stats.peak_apm_today(), 5.0);
+
+        clock.advance(Duration::from_secs(30));
+        stats.record_click("Left".to_string());
+        assert_eq!(stats.current_apm(), 6.0);
+
+        // Without new input the value falls off without any write
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(stats.current_apm(), 1.0);
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(stats.current_apm(), 0.0);
+        assert_eq!(stats.peak_apm_today(), 6.0);
+    }
+
+    #[test]
+    fn typing_intensity_follows_recent_keys() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        assert_eq!(stats.typing_intensity(), 0.0);
+
+        // Four keys a second are about half the full rate
+        for _ in 0..12 {
+            clock.advance(Duration::from_millis(250));
+            stats.record_key("A".to_string());
+        }
+        let half = stats.typing_intensity();
+        assert!((0.45..0.6).contains(&half), "{}", half);
+        // Modifiers don't produce text and don't count
+        stats.record_key("Ctrl".to_string());
+        assert_eq!(stats.typing_intensity(), half);
+
+        // Faster than the full rate the meter stays full
+        for _ in 0..40 {
+            clock.advance(Duration::from_millis(100));
+            stats.record_key("A".to_string());
+        }
+        assert_eq!(stats.typing_intensity(), 1.0);
+
+        // It eases off once typing stops
+        clock.advance(Duration::from_millis(1500));
+        let easing = stats.typing_intensity();
+        assert!(easing > 0.0 && easing < 1.0, "{}", easing);
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(stats.typing_intensity(), 0.0);
+    }
+
+    #[test]
+    fn wpm_window_expires_after_a_minute() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        for _ in 0..10 {
+            stats.record_key("A".to_string());
+        }
+        clock.advance(Duration::from_secs(59));
+        assert_eq!(stats.current_wpm(), 2.0);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(stats.current_wpm(), 0.0);
+        assert_eq!(stats.session_duration(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn wpm_history_is_sampled_and_bounded() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let mut stats = Stats::with_clock(clock.clone());
+        for _ in 0..10 {
+            stats.record_key("A".to_string());
+        }
+        stats.sample_wpm();
+        clock.advance(Duration::from_secs(2));
+        stats.sample_wpm();
+        assert_eq!(stats.wpm_history(Duration::from_secs(600)), vec![2.0]);
+        assert_eq!(stats.peak_wpm_today(), 2.0);
+
+        // Two hours of samples keep only the last hour
+        for _ in 0..(2 * 60 * 12) {
+            clock.advance(WPM_SAMPLE_INTERVAL);
+            stats.sample_wpm();
+        }
+        assert_eq!(stats.wpm_history.len(), 60 * 12 + 1);
+        assert_eq!(stats.wpm_history(Duration::from_secs(600)).len(), 10 * 12 + 1);
+        assert!(stats.wpm_history(WPM_HISTORY_SPAN).iter().all(|wpm| *wpm == 0.0));
+    }
+
+    #[test]
+    fn hour_buckets_and_days_follow_the_clock() {
+        let clock = MockClock::at(2024, 5, 1, 23, 59);
+        let mut stats = Stats::with_clock(clock.clone());
+        stats.record_key("A".to_string());
+        stats.record_click("Left".to_string());
+
+        clock.advance(Duration::from_secs(60));
+        stats.record_key("B".to_string());
+
+        assert_eq!(stats.hourly_key_counts.get(&23), Some(&1));
+        assert_eq!(stats.hourly_key_counts.get(&0), Some(&1));
+        assert_eq!(stats.hourly_click_counts.get(&23), Some(&1));
+        assert_eq!(stats.daily_stats["2024-05-01"].total_keys, 1);
+        assert_eq!(stats.daily_stats["2024-05-01"].total_clicks, 1);
+        assert_eq!(stats.daily_stats["2024-05-02"].total_keys, 1);
+        assert_eq!(stats.today(), date("2024-05-02"));
+        assert_eq!(stats.yesterday_keys(), 1);
+    }
+
+    #[test]
+    fn manager_rolls_over_with_the_clock() {
+        let clock = MockClock::at(2024, 5, 1, 23, 59);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("A".to_string());
+        manager.record_key_release("A");
+
+        clock.advance(Duration::from_secs(60));
+        manager.record_key("A".to_string());
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.today(), date("2024-05-02"));
+        assert_eq!(stats.today_keys(), 1);
+        assert_eq!(stats.yesterday_keys(), 1);
+    }
+
+    #[test]
+    fn excluded_apps_match_case_insensitive_substrings() {
+        let patterns = vec!["keepassxc".to_string(), "1password".to_string()];
+        assert!(is_excluded("KeePassXC", &patterns));
+        assert!(is_excluded("1Password 8", &patterns));
+        assert!(!is_excluded("firefox", &patterns));
+        assert!(!is_excluded("KeePassXC", &[]));
+    }
+
+    #[test]
+    fn excluded_app_mutes_recording() {
+        let manager = StatsManager::new_in_memory();
+        manager.set_excluded_apps(vec![manager.current_app()]);
+        manager.record_key("A".to_string());
+        manager.record_click("Left".to_string());
+        assert_eq!(manager.pause_reason(), Some(PauseReason::ExcludedApp));
+        assert_eq!(manager.snapshot().total_keys(), 0);
+        assert!(manager.snapshot().mouse_clicks.is_empty());
+
+        manager.set_excluded_apps(vec!["  ".to_string()]);
+        manager.record_key("A".to_string());
+        assert_eq!(manager.pause_reason(), None);
+        assert_eq!(manager.snapshot().total_keys(), 1);
+    }
+
+    #[test]
+    fn decayed_counts_halve_every_half_life() {
+        let mut stats = Stats::new();
+        stats.current_date = Some(date("2024-05-01"));
+        for _ in 0..8 {
+            stats.record_key("Q".to_string());
+        }
+        stats.roll_over(date("2024-05-15"));
+        for _ in 0..4 {
+            stats.record_key("W".to_string());
+        }
+
+        assert_eq!(stats.daily_key_counts["2024-05-01"]["Q"], 8);
+        let decayed = stats.decayed_key_counts(14.0);
+        assert!((decayed["Q"] - 4.0).abs() < 1e-9);
+        assert!((decayed["W"] - 4.0).abs() < 1e-9);
+        // Totals are unaffected
+        assert_eq!(stats.key_counts["Q"], 8);
+
+        // Privacy mode keeps key names out of the daily counts too
+        stats.privacy_mode = true;
+        stats.record_key("E".to_string());
+        assert!(!stats.decayed_key_counts(14.0).contains_key("E"));
+    }
+
+    #[test]
+    fn old_daily_key_counts_are_pruned_on_rollover() {
+        let mut stats = Stats::new();
+        stats.current_date = Some(date("2023-01-01"));
+        stats.record_key("A".to_string());
+        stats.roll_over(date("2023-06-01"));
+        stats.record_key("B".to_string());
+        stats.roll_over(date("2024-01-02"));
+
+        assert!(!stats.daily_key_counts.contains_key("2023-01-01"));
+        assert!(stats.daily_key_counts.contains_key("2023-06-01"));
+        // The all-time counts keep everything
+        assert_eq!(stats.key_counts["A"], 1);
+    }
+
+    #[test]
+    fn pruning_keeps_the_streaks() {
+        let mut stats = Stats::new();
+        stats.current_date = Some(date("2024-05-10"));
+        // 40 active days up to yesterday, then 5 more after a gap
+        for offset in 1..=40 {
+            stats.daily_stats.insert((date("2024-05-09") - Days::new(offset - 1)).to_string(), day(offset, 0, 0.0));
+        }
+        for offset in 0..5 {
+            stats.daily_stats.insert((date("2024-02-01") + Days::new(offset)).to_string(), day(9, 0, 0.0));
+        }
+        assert_eq!((stats.current_streak(1), stats.longest_streak(1), stats.longest_streak(10)), (40, 40, 31));
+
+        // Tracked for 1 key a day from the start, for 10 keys only from 2024-04-20
+        assert_eq!(stats.prune_daily_stats(20, 1), 25);
+        assert_eq!((stats.current_streak(1), stats.longest_streak(1)), (40, 40));
+        assert_eq!(stats.prune_daily_stats(0, 10), 20);
+        assert!(stats.daily_stats.is_empty());
+        assert_eq!((stats.current_streak(1), stats.longest_streak(1), stats.longest_streak(10)), (40, 40, 11));
+        assert_eq!(stats.pruned_streaks[&1], PrunedStreak { last_day: date("2024-05-09"), run: 40, longest: 40 });
+        assert_eq!(stats.pruned_streaks.len(), 2);
+
+        // Merged histories keep the longest streak of both
+        let mut merged = Stats::new();
+        merged.current_date = stats.current_date;
+        merged.merge(&stats);
+        assert_eq!(merged.longest_streak(1), 40);
+    }
+
+    #[test]
+    fn retention_keeps_at_least_a_month() {
+        let clock = MockClock::at(2024, 5, 10, 10, 0);
+        let manager = clocked_manager(clock);
+        if let Ok(mut stats) = manager.stats.write() {
+            stats.daily_stats.insert("2024-05-09".to_string(), day(5, 0, 0.0));
+            stats.daily_stats.insert("2024-03-01".to_string(), day(5, 0, 0.0));
+        }
+        manager.set_retention_days(Some(0));
+        manager.force_save().unwrap();
+
+        let stats = manager.snapshot();
+        assert!(stats.daily_stats.contains_key("2024-05-09"));
+        assert!(!stats.daily_stats.contains_key("2024-03-01"));
+        assert_eq!(stats.current_streak(1), 1);
+    }
+
+    #[test]
+    fn pruning_drops_old_days_only() {
+        let mut stats = Stats::new();
+        stats.current_date = Some(date("2024-05-10"));
+        for (date, keys) in [("2024-04-01", 5), ("2024-04-30", 7), ("2024-05-01", 3), ("2024-05-10", 2)] {
+            stats.daily_stats.insert(date.to_string(), day(keys, keys * 2, keys as f64));
+            stats.daily_key_counts.insert(date.to_string(), [("A".to_string(), keys)].into_iter().collect());
+        }
+        stats.daily_stats.insert("not a date".to_string(), day(1, 0, 0.0));
+        stats.key_counts.insert("A".to_string(), 17);
+
+        assert_eq!(stats.prune_daily_stats(9, 1), 2);
+        // The all-time counts are not derived from the days
+        assert_eq!(stats.total_keys(), 17);
+        let mut kept: Vec<_> = stats.daily_stats.keys().cloned().collect();
+        kept.sort();
+        assert_eq!(kept, ["2024-05-01", "2024-05-10", "not a date"]);
+        assert!(!stats.daily_key_counts.contains_key("2024-04-30"));
+        // Nothing left to prune
+        assert_eq!(stats.prune_daily_stats(9, 1), 0);
+    }
+
+    fn day(keys: u64, clicks: u64, distance: f64) -> DailyStats {
+        DailyStats { total_keys: keys, total_clicks: clicks, total_distance: distance, ..Default::default() }
+    }
+
+    #[test]
+    fn rollover_restarts_today_and_keeps_history() {
+        let mut stats = Stats::new();
+        stats.current_date = Some(date("2024-05-01"));
+        stats.record_key("A".to_string());
+        stats.record_click("Left".to_string());
+        stats.record_movement(100.0, false);
+        assert_eq!(stats.today_keys(), 1);
+
+        assert_eq!(stats.roll_over(date("2024-05-02")), Some(date("2024-05-01")));
+        assert_eq!(stats.roll_over(date("2024-05-02")), None);
+        assert_eq!(stats.today_keys(), 0);
+        assert_eq!(stats.today_clicks(), 0);
+        assert_eq!(stats.today_distance(), 0.0);
+        assert!(stats.today_hourly_keys().is_empty());
+        assert_eq!(stats.current_wpm(), 0.0);
+        assert_eq!(stats.yesterday_keys(), 1);
+        assert_eq!(stats.daily_stats["2024-05-01"].hourly_keys.values().sum::<u64>(), 1);
+
+        stats.record_key("B".to_string());
+        assert_eq!(stats.today_keys(), 1);
+        assert_eq!(stats.daily_stats["2024-05-02"].total_keys, 1);
+        assert_eq!(stats.total_keys(), 2);
+    }
+
+    #[test]
+    fn manager_rollover_keeps_history() {
+        let manager = StatsManager::new_in_memory();
+        manager.record_key("A".to_string());
+        let today = manager.snapshot().today();
+
+        manager.roll_over_to(today.succ_opt().unwrap());
+        let stats = manager.snapshot();
+        assert_eq!(stats.today_keys(), 0);
+        assert_eq!(stats.yesterday_keys(), 1);
+        assert_eq!(stats.total_keys(), 1);
+    }
+
+    #[test]
+    fn rollover_reports_the_finished_day_once() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-reports-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let clock = MockClock::at(2024, 5, 1, 23, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.set_report_dir(Some(dir.clone()));
+        manager.record_key("A".to_string());
+
+        clock.advance(Duration::from_secs(2 * 3600));
+        manager.check_rollover();
+        assert_eq!(manager.take_announcements(), ["2024-05-01 is over: 1 keys, 0 clicks, 0 m of cursor travel"]);
+        let report = fs::read_to_string(dir.join("2024-05-01.md")).unwrap();
+        assert!(report.contains("| Most used key | `A` (1 presses) |"));
+
+        // Going back and forth across midnight does not report the day again
+        manager.roll_over_to(date("2024-05-01"));
+        manager.roll_over_to(date("2024-05-02"));
+        assert!(manager.take_announcements().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_disjoint_days() {
+        let mut ours = Stats::new();
+        ours.key_counts.insert("A".to_string(), 2);
+        ours.daily_stats.insert("2024-05-01".to_string(), day(2, 0, 10.0));
+
+        let mut theirs = Stats::new();
+        theirs.key_counts.insert("A".to_string(), 3);
+        theirs.key_counts.insert("B".to_string(), 1);
+        theirs.mouse_clicks.insert("Left".to_string(), 4);
+        theirs.mouse_distance = 25.0;
+        theirs.hourly_key_counts.insert(9, 4);
+        theirs.daily_stats.insert("2024-05-02".to_string(), day(4, 4, 25.0));
+
+        let report = ours.merge(&theirs);
+        assert_eq!(report.keys_added, 4);
+        assert_eq!(report.clicks_added, 4);
+        assert_eq!(report.days_merged, 1);
+        assert!(report.overlapping_days.is_empty());
+        assert_eq!(ours.key_counts["A"], 5);
+        assert_eq!(ours.key_counts["B"], 1);
+        assert_eq!(ours.mouse_distance, 25.0);
+        assert_eq!(ours.hourly_key_counts[&9], 4);
+        assert_eq!(ours.daily_stats.len(), 2);
+        assert_eq!(ours.daily_stats["2024-05-01"].total_keys, 2);
+    }
+
+    #[test]
+    fn merge_overlapping_days_adds_totals() {
+        let mut ours = Stats::new();
+        ours.daily_stats.insert("2024-05-01".to_string(), day(10, 2, 100.0));
+        ours.daily_stats.insert("2024-05-02".to_string(), day(5, 1, 50.0));
+
+        let mut theirs = Stats::new();
+        theirs.daily_stats.insert("2024-05-02".to_string(), day(7, 3, 20.0));
+        theirs.daily_stats.insert("2024-05-03".to_string(), day(1, 0, 0.0));
+
+        let report = ours.merge(&theirs);
+        assert_eq!(report.days_merged, 2);
+        assert_eq!(report.overlapping_days, vec!["2024-05-02".to_string()]);
+        let merged = &ours.daily_stats["2024-05-02"];
+        assert_eq!((merged.total_keys, merged.total_clicks, merged.total_distance), (12, 4, 70.0));
+        assert_eq!(ours.daily_stats.len(), 3);
+    }
+
+    #[test]
+    fn merge_adds_cursor_grids() {
+        let mut ours = Stats::new();
+        let mut theirs = Stats::new();
+        theirs.record_mouse_position(5);
+        theirs.record_mouse_position(5);
+        theirs.record_click_position(9);
+        ours.merge(&theirs);
+        ours.record_mouse_position(5);
+        assert_eq!(ours.mouse_position_grid[5], 3);
+        assert_eq!(ours.mouse_position_grid.len(), mouse_grid::GRID_COLUMNS * mouse_grid::GRID_ROWS);
+        assert_eq!(ours.click_position_grid[9], 1);
+    }
+
+    #[test]
+    fn merge_keeps_latest_device_use() {
+        let earlier = MockClock::at(2024, 5, 1, 10, 0);
+        let later = MockClock::at(2024, 5, 2, 10, 0);
+        let mut ours = Stats::new();
+        ours.clock = SharedClock(later.clone());
+        ours.record_device_key("Keyboard".to_string());
+        let mut theirs = Stats::new();
+        theirs.clock = SharedClock(earlier.clone());
+        theirs.record_device_key("Keyboard".to_string());
+        theirs.record_device_click("Mouse".to_string());
+
+        ours.merge(&theirs);
+        assert_eq!(ours.device_key_counts["Keyboard"], 2);
+        assert_eq!(ours.device_last_seen["Keyboard"], later.now_local());
+        assert_eq!(ours.device_last_seen["Mouse"], earlier.now_local());
+    }
+
+    #[test]
+    fn garbage_stats_file_is_set_aside() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-garbage_file-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stats.json");
+        fs::write(&path, [0x00, 0x9f, 0x92, 0x96, b'{', 0xff]).unwrap();
+
+        let manager = StatsManager::with_data_path(path.clone());
+        assert_eq!(manager.snapshot().total_keys(), 0);
+        assert!(manager.storage_error().is_none());
+        assert!(manager.load_warning().unwrap().contains("starting with empty stats"));
+        assert!(!path.exists());
+        let moved: Vec<_> = fs::read_dir(&dir).unwrap().flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert!(moved[0].starts_with("stats.corrupt.") && moved[0].ends_with(".json"));
+
+        manager.record_key("A".to_string());
+        manager.save().unwrap();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn future_schema_version_is_never_overwritten() {
+        let dir = TempDir::new("future_file");
+        let path = dir.join("stats.json");
+        let future = format!(r#"{{"schema_version": {}, "key_counts": {{"A": 1}}}}"#, SCHEMA_VERSION + 1);
+        fs::write(&path, &future).unwrap();
+
+        let manager = StatsManager::with_data_path(path.clone());
+        assert!(manager.storage_error().is_some());
+        manager.record_key("B".to_string());
+        assert!(manager.save().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), future);
+    }
+
+    #[test]
+    fn merge_rejects_future_schema_version() {
+        let manager = StatsManager::new_in_memory();
+        let dir = TempDir::new("future_merge");
+        let path = dir.join("future.json");
+        let future = format!(r#"{{"schema_version": {}, "key_counts": {{"A": 1}}}}"#, SCHEMA_VERSION + 1);
+        fs::write(&path, future).unwrap();
+
+        let error = manager.merge_from_file(&path).unwrap_err();
+        assert!(error.to_string().contains("schema version"));
+        assert_eq!(manager.snapshot().total_keys(), 0);
+    }
+
+    #[test]
+    fn import_saves_and_keeps_session() {
+        let dir = TempDir::new("import_and_merge");
+        let manager = StatsManager::with_data_path(dir.join("stats.json"));
+        let session_start = manager.snapshot().session_start;
+        let path = dir.join("import.json");
+        fs::write(&path, r#"{"key_counts": {"A": 3}, "mouse_distance": 10.0}"#).unwrap();
+
+        manager.import_and_merge(&path).unwrap();
+        // No deduplication: a second import adds the same counts again
+        manager.import_and_merge(&path).unwrap();
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts["A"], 6);
+        assert_eq!(stats.mouse_distance, 20.0);
+        assert_eq!(stats.session_start, session_start);
+        let saved = fs::read_to_string(manager.data_path()).unwrap();
+        assert!(saved.contains("\"A\": 6") || saved.contains("\"A\":6"));
+    }
+
+    #[test]
+    fn manager_records_keys_under_their_aliases() {
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        let custom = [("KP+".to_string(), "+".to_string())].into_iter().collect();
+        manager.set_key_aliases(KeyAliases::new(custom).unwrap());
+        manager.record_key("KP+".to_string());
+        manager.record_key_release("KP+");
+        clock.advance(Duration::from_millis(200));
+        manager.record_key("+".to_string());
+
+        let stats = manager.snapshot();
+        assert_eq!(stats.key_counts.get("+"), Some(&2));
+        assert!(!stats.key_counts.contains_key("KP+"));
+        assert!(stats.key_repeats.is_empty());
+    }
+
+    #[test]
+    fn normalize_renames_saved_keys_after_a_backup() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-normalize-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let data_path = dir.join("stats.json");
+        fs::write(&data_path, r#"{"key_counts": {"KP+": 3, "+": 4, "Kp1": 2, "A": 1}}"#).unwrap();
+        let manager = StatsManager::with_data_path(data_path.clone());
+        let custom = [("KP+".to_string(), "+".to_string())].into_iter().collect();
+        manager.set_key_aliases(KeyAliases::new(custom).unwrap());
+
+        let report = manager.normalize_keys().unwrap();
+        assert_eq!(report.renamed, vec![
+            ("KP+".to_string(), "+".to_string(), 3),
+            ("Kp1".to_string(), "KP1".to_string(), 2),
+        ]);
+        let backup = fs::read_to_string(report.backup.unwrap()).unwrap();
+        assert!(backup.contains("KP+"));
+        let saved = StatsManager::with_data_path(data_path).snapshot();
+        assert_eq!(saved.key_counts.get("+"), Some(&7));
+        assert_eq!(saved.key_counts.get("KP1"), Some(&2));
+        assert_eq!(saved.key_counts.len(), 3);
+
+        // Running it again has nothing left to do
+        let again = manager.normalize_keys().unwrap();
+        assert!(again.renamed.is_empty() && again.backup.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exports_date_range() {
+        let manager = StatsManager::new_in_memory();
+        for (day, keys) in [("2024-05-01", 10), ("2024-05-02", 20), ("2024-05-03", 40), ("2024-05-04", 80)] {
+            let stats = DailyStats { total_keys: keys, peak_wpm: keys as f64, ..Default::default() };
+            manager.stats.write().unwrap().daily_stats.insert(day.to_string(), stats);
+        }
+        let path = std::env::temp_dir().join(format!("rust-finger-export-{}.json", std::process::id()));
+
+        assert_eq!(manager.export_range(date("2024-05-02"), date("2024-05-03"), &path).unwrap(), 2);
+        let written: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["from"], "2024-05-02");
+        assert_eq!(written["totals"]["total_keys"], 60);
+        assert_eq!(written["totals"]["peak_wpm"], 40.0);
+        assert_eq!(written["daily_stats"].as_object().unwrap().len(), 2);
+
+        // An inverted range is an error, not an empty file
+        let _ = fs::remove_file(&path);
+        assert!(manager.export_range(date("2024-05-03"), date("2024-05-02"), &path).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn scroll_display_converts_units() {
+        let mut stats = Stats::new();
+        stats.record_scroll(50);
+        stats.record_scroll(-30);
+        assert_eq!(stats.scroll_distance(), 80);
+        assert_eq!(stats.scroll_display(ScrollUnit::Notches), "80 notches");
+        assert_eq!(stats.scroll_display(ScrollUnit::Lines), "240 lines");
+        assert_eq!(stats.scroll_display(ScrollUnit::Screens), "6.0 screens");
+    }
+
+    #[test]
+    fn yesterday_totals_come_from_previous_day() {
+        let mut stats = Stats::with_clock(MockClock::at(2024, 5, 10, 9, 0));
+        stats.daily_stats.insert("2024-05-09".to_string(), day(18_000, 900, 2500.0));
+        stats.record_key("A".to_string());
+
+        assert_eq!(stats.yesterday_keys(), 18_000);
+        assert_eq!(stats.yesterday_clicks(), 900);
+        assert_eq!(stats.yesterday_distance(), 2500.0);
+        assert_eq!(stats.today_keys(), 1);
+    }
+
+    #[test]
+    fn yesterday_crosses_month_boundaries() {
+        let clock = MockClock::at(2024, 3, 1, 9, 0);
+        let mut stats = Stats::with_clock(clock);
+        stats.daily_stats.insert("2024-02-29".to_string(), day(700, 30, 0.0));
+        stats.daily_stats.insert("2024-02-28".to_string(), day(5, 5, 0.0));
+
+        assert_eq!(stats.yesterday_keys(), 700);
+        assert_eq!(stats.yesterday_clicks(), 30);
+    }
+
+    #[test]
+    fn percent_change_handles_empty_yesterday() {
+        assert_eq!(percent_change(12_000.0, 18_000.0).map(f64::round), Some(-33.0));
+        assert_eq!(percent_change(150.0, 100.0), Some(50.0));
+        assert_eq!(percent_change(10.0, 0.0), None);
+        assert_eq!(percent_change(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn smoothed_wpm_approaches_current_wpm() {
+        let mut stats = Stats::new();
+        for _ in 0..50 {
+            stats.record_key("A".to_string());
+        }
+        assert_eq!(stats.current_wpm(), 10.0);
+        assert_eq!(stats.display_wpm(), 0.0);
+
+        stats.update_smoothed_wpm(0.5);
+        assert_eq!(stats.display_wpm(), 5.0);
+        stats.update_smoothed_wpm(0.5);
+        assert_eq!(stats.display_wpm(), 7.5);
+        stats.update_smoothed_wpm(1.0);
+        assert_eq!(stats.display_wpm(), 10.0);
+    }
+
+    #[test]
+    fn privacy_mode_keeps_only_aggregates() {
+        let mut stats = Stats::new();
+        stats.record_key("A".to_string());
+        stats.set_privacy_mode(true);
+        stats.record_key("B".to_string());
+        stats.record_repeat("B".to_string());
+
+        assert_eq!(stats.key_counts.len(), 1);
+        assert!(!stats.key_counts.contains_key("B"));
+        assert!(stats.key_repeats.is_empty());
+        assert_eq!(stats.total_keys(), 2);
+        assert_eq!(stats.today_keys(), 2);
+        assert_eq!(stats.hourly_key_counts.values().sum::<u64>(), 2);
+    }
+
+    #[test]
+    fn privacy_mode_counts_categories() {
+        let mut stats = Stats::new();
+        stats.set_privacy_mode(true);
+        for key in ["P", "A", "5", "!", "Space", "↑", "Shift"] {
+            stats.record_key(key.to_string());
+        }
+
+        assert!(stats.key_counts.is_empty());
+        assert_eq!(stats.anonymous_keys, 7);
+        assert_eq!(stats.category_counts[&PrivacyBucket::Alpha], 2);
+        assert_eq!(stats.category_counts[&PrivacyBucket::Digit], 1);
+        assert_eq!(stats.category_counts[&PrivacyBucket::Whitespace], 1);
+        assert_eq!(stats.category_breakdown()[0], (PrivacyBucket::Alpha, 2));
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"alpha\":2"));
+        let loaded: Stats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.category_counts, stats.category_counts);
+    }
+
+    #[test]
+    fn privacy_mode_persists_with_period() {
+        let mut stats = Stats::new();
+        stats.set_privacy_mode(true);
+        let json = serde_json::to_string(&stats).unwrap();
+        let mut loaded: Stats = serde_json::from_str(&json).unwrap();
+
+        assert!(loaded.privacy_mode);
+        assert_eq!(loaded.privacy_periods.len(), 1);
+        assert!(loaded.privacy_periods[0].end.is_none());
+
+        loaded.set_privacy_mode(false);
+        assert!(loaded.privacy_periods[0].end.is_some());
+    }
+
+    /// Store whose event log fails until `available` is set
+    struct FlakyEventStore {
+        available: Arc<AtomicBool>,
+        appended: Arc<AtomicU64>,
+    }
+
+    impl StatsStore for FlakyEventStore {
+        fn load(&self) -> store::StoreResult<Stats> {
+            Ok(Stats::new())
+        }
+
+        fn save(&self, _stats: &Stats) -> store::StoreResult<()> {
+            Ok(())
+        }
+
+        fn append_events(&self, events: &[StoredEvent]) -> store::StoreResult<()> {
+            if !self.available.load(Ordering::SeqCst) {
+                return Err("database is locked".into());
+            }
+            self.appended.fetch_add(events.len() as u64, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn path(&self) -> &Path {
+            Path::new("flaky")
+        }
+    }
+
+    #[test]
+    fn events_are_kept_when_appending_fails() {
+        let available = Arc::new(AtomicBool::new(false));
+        let appended = Arc::new(AtomicU64::new(0));
+        let manager = StatsManager::with_store(Box::new(FlakyEventStore { available: available.clone(), appended: appended.clone() }));
+        manager.record_events.store(true, Ordering::Relaxed);
+        manager.record_click("Left".to_string());
+        assert!(manager.force_save().is_err());
+        manager.record_click("Right".to_string());
+
+        available.store(true, Ordering::SeqCst);
+        manager.force_save().unwrap();
+        assert_eq!(appended.load(Ordering::SeqCst), 2);
+        assert!(manager.pending_events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn events_are_queued_only_when_recorded() {
+        let manager = StatsManager::new_in_memory();
+        manager.record_key("A".to_string());
+        manager.record_click("Left".to_string());
+        assert!(manager.pending_events.lock().unwrap().is_empty());
+
+        manager.record_events.store(true, Ordering::Relaxed);
+        manager.record_key("B".to_string());
+        assert_eq!(manager.pending_events.lock().unwrap().len(), 1);
+
+        // Saving while disabled still drops the queued events
+        *manager.storage_error.write().unwrap() = Some("newer version".to_string());
+        assert!(manager.force_save().is_err());
+        assert!(manager.pending_events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn manager_writes_the_event_log() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-test-event-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("events.jsonl");
+        let clock = MockClock::at(2024, 5, 1, 10, 0);
+        let manager = clocked_manager(clock.clone());
+        manager.record_key("A".to_string());
+        manager.set_event_log(Some(path.clone()));
+        manager.record_key("B".to_string());
+        manager.record_click("Left".to_string());
+        manager.set_privacy_mode(true);
+        manager.record_key("C".to_string());
+        manager.force_save().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""type":"key","name":"B""#));
+        assert!(lines[1].contains(r#""type":"click","name":"Left""#));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+/// What a milestone is measured in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    /// All-time key presses
+    Keys,
+    /// All-time mouse clicks
+    Clicks,
+    /// All-time cursor travel in kilometers, as shown on the dashboard
+    Distance,
+    /// Current streak of days with key presses
+    Streak,
+}
+
+impl Measure {
+    pub const ALL: [Measure; 4] = [Measure::Keys, Measure::Clicks, Measure::Distance, Measure::Streak];
+}
+
+/// A total worth celebrating once it is reached
+#[derive(Debug, PartialEq)]
+pub struct Milestone {
+    /// Name in the stats file; never change it, or the milestone unlocks again
+    pub id: &'static str,
+    pub title: &'static str,
+    pub icon: &'static str,
+    pub measure: Measure,
+    pub threshold: f64,
+}
+
+const fn milestone(id: &'static str, title: &'static str, icon: &'static str, measure: Measure, threshold: f64) -> Milestone {
+    Milestone { id, title, icon, measure, threshold }
+}
+
+/// Every milestone, in the order the dashboard shows them
+pub const MILESTONES: &[Milestone] = &[
+    milestone("keys-1k", "1,000 keys", "⌨️", Measure::Keys, 1_000.0),
+    milestone("keys-10k", "10,000 keys", "⌨️", Measure::Keys, 10_000.0),
+    milestone("keys-50k", "50,000 keys", "⌨️", Measure::Keys, 50_000.0),
+    milestone("keys-100k", "100,000 keys", "⌨️", Measure::Keys, 100_000.0),
+    milestone("keys-500k", "500,000 keys", "⌨️", Measure::Keys, 500_000.0),
+    milestone("keys-1m", "1 million keys", "⌨️", Measure::Keys, 1_000_000.0),
+    milestone("keys-5m", "5 million keys", "⌨️", Measure::Keys, 5_000_000.0),
+    milestone("keys-10m", "10 million keys", "⌨️", Measure::Keys, 10_000_000.0),
+    milestone("clicks-1k", "1,000 clicks", "🖱️", Measure::Clicks, 1_000.0),
+    milestone("clicks-10k", "10,000 clicks", "🖱️", Measure::Clicks, 10_000.0),
+    milestone("clicks-100k", "100,000 clicks", "🖱️", Measure::Clicks, 100_000.0),
+    milestone("clicks-1m", "1 million clicks", "🖱️", Measure::Clicks, 1_000_000.0),
+    milestone("distance-1km", "1 km of cursor travel", "📏", Measure::Distance, 1.0),
+    milestone("distance-10km", "10 km of cursor travel", "📏", Measure::Distance, 10.0),
+    milestone("distance-marathon", "Cursor marathon (42.2 km)", "🏃", Measure::Distance, 42.195),
+    milestone("streak-100", "100-day streak", "🔥", Measure::Streak, 100.0),
+];
+
+/// Milestones of `measure` that `value` has reached
+pub fn reached(measure: Measure, value: f64) -> impl Iterator<Item = &'static Milestone> {
+    MILESTONES
+        .iter()
+        .filter(move |milestone| milestone.measure == measure && value >= milestone.threshold)
+}
+
+/// Key totals the dashboard counts towards: the thresholds of the key
+/// milestones, in increasing order
+pub fn key_ladder() -> impl Iterator<Item = u64> {
+    MILESTONES
+        .iter()
+        .filter(|milestone| milestone.measure == Measure::Keys)
+        .map(|milestone| milestone.threshold as u64)
+}
+
+/// Rungs of the key ladder around `total`: the last one reached (0 before
+/// the first) and the next one ahead, or `None` once all are reached
+pub fn key_ladder_span(total: u64) -> Option<(u64, u64)> {
+    let mut previous = 0;
+    for rung in key_ladder() {
+        if rung > total {
+            return Some((previous, rung));
+        }
+        previous = rung;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn ids(measure: Measure, value: f64) -> Vec<&'static str> {
+        reached(measure, value).map(|milestone| milestone.id).collect()
+    }
+
+    #[test]
+    fn thresholds_are_inclusive() {
+        assert!(ids(Measure::Keys, 999.0).is_empty());
+        assert_eq!(ids(Measure::Keys, 10_000.0), ["keys-1k", "keys-10k"]);
+        assert_eq!(ids(Measure::Keys, 2_500_000.0), ["keys-1k", "keys-10k", "keys-50k", "keys-100k", "keys-500k", "keys-1m"]);
+        assert_eq!(ids(Measure::Distance, 42.2), ["distance-1km", "distance-10km", "distance-marathon"]);
+        assert!(ids(Measure::Streak, 99.0).is_empty());
+    }
+
+    #[test]
+    fn key_ladder_follows_the_key_milestones() {
+        let ladder: Vec<_> = key_ladder().collect();
+        assert_eq!(ladder, [1_000, 10_000, 50_000, 100_000, 500_000, 1_000_000, 5_000_000, 10_000_000]);
+        assert_eq!(key_ladder_span(0), Some((0, 1_000)));
+        assert_eq!(key_ladder_span(9_999), Some((1_000, 10_000)));
+        // Reaching a rung moves on to the next one
+        assert_eq!(key_ladder_span(10_000), Some((10_000, 50_000)));
+        assert_eq!(key_ladder_span(10_000_000), None);
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let ids: HashSet<_> = MILESTONES.iter().map(|milestone| milestone.id).collect();
+        assert_eq!(ids.len(), MILESTONES.len());
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+/// Time between periodic saves, unless configured otherwise
+pub const DEFAULT_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the autosave thread checks whether to save
+pub const TICK: Duration = Duration::from_secs(1);
+
+/// Without input for this long, activity is considered over
+pub const IDLE_AFTER: Duration = Duration::from_secs(30);
+
+/// Minimum time between two idle-triggered saves
+pub const MIN_IDLE_SAVE_GAP: Duration = Duration::from_secs(120);
+
+/// Why the schedule asked for a save
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveReason {
+    /// The regular save interval elapsed
+    Interval,
+    /// A burst of activity just ended
+    Idle,
+}
+
+/// Decides when the autosave thread writes the stats.
+///
+/// Saves happen every `interval`, and additionally once activity stops for
+/// `IDLE_AFTER`, so data is on disk before the machine may go to sleep. Idle
+/// saves are at least `MIN_IDLE_SAVE_GAP` apart so that on-and-off activity
+/// does not write every few seconds; the regular interval still applies.
+/// Time is passed in, which keeps the schedule testable.
+#[derive(Debug)]
+pub struct AutosaveSchedule {
+    interval: Duration,
+    last_save: Instant,
+    last_idle_save: Option<Instant>,
+    was_active: bool,
+}
+
+impl AutosaveSchedule {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last_save: now,
+            last_idle_save: None,
+            was_active: false,
+        }
+    }
+    
+    /// Change the time between periodic saves, counted from the last save
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+    
+    /// Check the schedule at `now`, given the time of the latest input
+    pub fn poll(&mut self, now: Instant, last_activity: Option<Instant>) -> Option<SaveReason> {
+        let active = last_activity.is_some_and(|t| now.duration_since(t) < IDLE_AFTER);
+        let went_idle = self.was_active && !active;
+        self.was_active = active;
+        
+        let reason = if went_idle && self.last_idle_save.is_none_or(|t| now.duration_since(t) >= MIN_IDLE_SAVE_GAP) {
+            self.last_idle_save = Some(now);
+            SaveReason::Idle
+        } else if now.duration_since(self.last_save) >= self.interval {
+            SaveReason::Interval
+        } else {
+            return None;
+        };
+        
+        self.last_save = now;
+        Some(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn shorter_interval_applies_from_the_last_save() {
+        let start = Instant::now();
+        let mut schedule = AutosaveSchedule::new(secs(300), start);
+        assert_eq!(schedule.poll(start + secs(100), None), None);
+        schedule.set_interval(secs(60));
+        assert_eq!(schedule.poll(start + secs(101), None), Some(SaveReason::Interval));
+    }
+
+    #[test]
+    fn saves_every_interval() {
+        let start = Instant::now();
+        let mut schedule = AutosaveSchedule::new(secs(300), start);
+        assert_eq!(schedule.poll(start + secs(299), None), None);
+        assert_eq!(schedule.poll(start + secs(300), None), Some(SaveReason::Interval));
+        assert_eq!(schedule.poll(start + secs(301), None), None);
+        assert_eq!(schedule.poll(start + secs(600), None), Some(SaveReason::Interval));
+    }
+
+    #[test]
+    fn saves_when_activity_ends() {
+        let start = Instant::now();
+        let mut schedule = AutosaveSchedule::new(secs(300), start);
+        assert_eq!(schedule.poll(start + secs(10), Some(start + secs(10))), None);
+        assert_eq!(schedule.poll(start + secs(39), Some(start + secs(10))), None);
+        assert_eq!(schedule.poll(start + secs(40), Some(start + secs(10))), Some(SaveReason::Idle));
+        // Staying idle does not save again before the interval
+        assert_eq!(schedule.poll(start + secs(100), Some(start + secs(10))), None);
+        assert_eq!(schedule.poll(start + secs(340), Some(start + secs(10))), Some(SaveReason::Interval));
+    }
+
+    #[test]
+    fn idle_saves_are_rate_limited() {
+        let start = Instant::now();
+        let mut schedule = AutosaveSchedule::new(secs(3600), start);
+        schedule.poll(start + secs(1), Some(start + secs(1)));
+        assert_eq!(schedule.poll(start + secs(31), Some(start + secs(1))), Some(SaveReason::Idle));
+
+        // Another short burst right away is not saved on idle
+        schedule.poll(start + secs(40), Some(start + secs(40)));
+        assert_eq!(schedule.poll(start + secs(70), Some(start + secs(40))), None);
+
+        // Once the gap has passed, the next burst is
+        schedule.poll(start + secs(200), Some(start + secs(200)));
+        assert_eq!(schedule.poll(start + secs(230), Some(start + secs(200))), Some(SaveReason::Idle));
+    }
+}
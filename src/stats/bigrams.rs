@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::categories::KeyCategory;
+use super::fingers::FingerMap;
+
+/// Longest gap between two key presses that still counts as a sequence
+pub const BIGRAM_WINDOW: Duration = Duration::from_secs(1);
+
+/// Separator between the two keys in stored bigram names, e.g. `T→H`
+const SEPARATOR: &str = "→";
+
+/// Pairs consecutive key presses into bigrams.
+///
+/// Modifiers are skipped without ending the sequence, so Shift between two
+/// letters still counts the letters as a pair.
+#[derive(Debug, Default)]
+pub struct BigramTracker {
+    previous: Option<(String, Instant)>,
+}
+
+impl BigramTracker {
+    /// Note a key press, returning the bigram it completes, if any
+    pub fn observe(&mut self, key: &str, now: Instant) -> Option<(String, String)> {
+        if KeyCategory::of(key) == Some(KeyCategory::Modifiers) {
+            return None;
+        }
+        let previous = self.previous.replace((key.to_string(), now))?;
+        (now.duration_since(previous.1) <= BIGRAM_WINDOW).then(|| (previous.0, key.to_string()))
+    }
+}
+
+/// Whether both keys of a bigram are typed by the same finger (a slow,
+/// strain-prone motion). Repeats of one key are not counted.
+pub fn same_finger(map: &FingerMap, first: &str, second: &str) -> bool {
+    first != second && map.finger_of(first).is_some() && map.finger_of(first) == map.finger_of(second)
+}
+
+/// Stored name of a bigram
+fn join(first: &str, second: &str) -> String {
+    format!("{}{}{}", first, SEPARATOR, second)
+}
+
+/// Split a stored name into its keys. Key names may themselves be `→` (the
+/// right arrow), so the first split leaving two non-empty keys wins.
+fn split(name: &str) -> Option<(String, String)> {
+    name.match_indices(SEPARATOR)
+        .map(|(i, _)| (&name[..i], &name[i + SEPARATOR.len()..]))
+        .find(|(first, second)| !first.is_empty() && !second.is_empty())
+        .map(|(first, second)| (first.to_string(), second.to_string()))
+}
+
+/// Serializes bigram counts as a map from `A→B` strings, keeping the JSON flat
+pub mod serde_keys {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        counts: &HashMap<(String, String), u64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        counts
+            .iter()
+            .map(|((first, second), count)| (join(first, second), *count))
+            .collect::<HashMap<_, _>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<(String, String), u64>, D::Error> {
+        let named = HashMap::<String, u64>::deserialize(deserializer)?;
+        Ok(named
+            .into_iter()
+            .filter_map(|(name, count)| split(&name).map(|pair| (pair, count)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_keys_within_window() {
+        let start = Instant::now();
+        let mut tracker = BigramTracker::default();
+        assert_eq!(tracker.observe("T", start), None);
+        assert_eq!(tracker.observe("H", start + Duration::from_millis(200)), Some(("T".into(), "H".into())));
+        assert_eq!(tracker.observe("E", start + Duration::from_millis(1200)), Some(("H".into(), "E".into())));
+        // Just past the window the sequence starts over
+        assert_eq!(tracker.observe("N", start + Duration::from_millis(2201)), None);
+        assert_eq!(tracker.observe("O", start + Duration::from_millis(2300)), Some(("N".into(), "O".into())));
+    }
+
+    #[test]
+    fn modifiers_do_not_break_sequences() {
+        let start = Instant::now();
+        let mut tracker = BigramTracker::default();
+        tracker.observe("A", start);
+        assert_eq!(tracker.observe("Shift", start + Duration::from_millis(100)), None);
+        assert_eq!(tracker.observe("B", start + Duration::from_millis(200)), Some(("A".into(), "B".into())));
+    }
+
+    #[test]
+    fn names_round_trip_with_arrow_keys() {
+        for (first, second) in [("A", "B"), ("→", "A"), ("A", "→"), ("→", "→"), ("←", "→")] {
+            assert_eq!(split(&join(first, second)), Some((first.to_string(), second.to_string())));
+        }
+        assert_eq!(split("AB"), None);
+    }
+
+    #[test]
+    fn flags_same_finger_pairs() {
+        let map = FingerMap::default();
+        assert!(same_finger(&map, "E", "D"));
+        assert!(!same_finger(&map, "E", "E"));
+        assert!(!same_finger(&map, "T", "H"));
+    }
+}
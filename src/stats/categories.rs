@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+
+/// Broad category of a key, used to filter and color key lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyCategory {
+    /// Every key, including those outside the other categories
+    #[default]
+    All,
+    Letters,
+    Numbers,
+    Symbols,
+    Modifiers,
+    /// Keys outside the specific categories
+    Other,
+}
+
+/// Modifier keys as named by the listener
+const MODIFIER_KEYS: &[&str] = &["Shift", "Ctrl", "Alt", "AltGr", "Meta", "CapsLock", "Fn"];
+
+/// Numpad keys that produce symbols
+const NUMPAD_SYMBOLS: &[&str] = &["KP+", "KP-", "KP*", "KP/", "KP."];
+
+impl KeyCategory {
+    /// All filters in display order
+    pub const ALL: [KeyCategory; 6] = [
+        KeyCategory::All,
+        KeyCategory::Letters,
+        KeyCategory::Numbers,
+        KeyCategory::Symbols,
+        KeyCategory::Modifiers,
+        KeyCategory::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyCategory::All => "All",
+            KeyCategory::Letters => "Letters",
+            KeyCategory::Numbers => "Numbers",
+            KeyCategory::Symbols => "Symbols",
+            KeyCategory::Modifiers => "Modifiers",
+            KeyCategory::Other => "Other",
+        }
+    }
+
+    /// Classify a key name. Returns `None` for keys outside the specific
+    /// categories (Space, Enter, function and navigation keys, unknown codes).
+    pub fn of(key: &str) -> Option<KeyCategory> {
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return if c.is_ascii_alphabetic() {
+                Some(KeyCategory::Letters)
+            } else if c.is_ascii_digit() {
+                Some(KeyCategory::Numbers)
+            } else if c.is_ascii_punctuation() {
+                Some(KeyCategory::Symbols)
+            } else {
+                None
+            };
+        }
+
+        if MODIFIER_KEYS.contains(&key) {
+            Some(KeyCategory::Modifiers)
+        } else if NUMPAD_SYMBOLS.contains(&key) || key == "<>" {
+            Some(KeyCategory::Symbols)
+        } else if key.strip_prefix("KP").is_some_and(|rest| rest.len() == 1 && rest.chars().all(|c| c.is_ascii_digit())) {
+            Some(KeyCategory::Numbers)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a key passes this filter
+    pub fn matches(&self, key: &str) -> bool {
+        match self {
+            KeyCategory::All => true,
+            KeyCategory::Other => KeyCategory::of(key).is_none(),
+            category => KeyCategory::of(key) == Some(*category),
+        }
+    }
+}
+
+/// Whether a key produces a character when typing text (letters, digits,
+/// punctuation, Space, Enter); only these count towards the WPM
+pub fn produces_character(key: &str) -> bool {
+    matches!(
+        KeyCategory::of(key),
+        Some(KeyCategory::Letters | KeyCategory::Numbers | KeyCategory::Symbols)
+    ) || matches!(key, "Space" | "Enter" | "KPEnter")
+}
+
+/// Keys counted as whitespace in privacy mode
+const WHITESPACE_KEYS: &[&str] = &["Space", "Enter", "Tab", "KPEnter"];
+
+/// Keys counted as navigation in privacy mode
+const NAVIGATION_KEYS: &[&str] = &["↑", "↓", "←", "→", "Home", "End", "PageUp", "PageDown"];
+
+/// Coarse bucket a key press is counted under in privacy mode, so that
+/// the stored counts cannot reveal which characters were typed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyBucket {
+    Alpha,
+    Digit,
+    Symbol,
+    Whitespace,
+    Navigation,
+}
+
+impl PrivacyBucket {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PrivacyBucket::Alpha => "Letters",
+            PrivacyBucket::Digit => "Digits",
+            PrivacyBucket::Symbol => "Symbols",
+            PrivacyBucket::Whitespace => "Whitespace",
+            PrivacyBucket::Navigation => "Navigation",
+        }
+    }
+
+    /// Bucket of a key name. Returns `None` for modifiers, function keys and
+    /// other keys that are only counted in the total.
+    pub fn of(key: &str) -> Option<PrivacyBucket> {
+        match KeyCategory::of(key) {
+            Some(KeyCategory::Letters) => Some(PrivacyBucket::Alpha),
+            Some(KeyCategory::Numbers) => Some(PrivacyBucket::Digit),
+            Some(KeyCategory::Symbols) => Some(PrivacyBucket::Symbol),
+            _ if WHITESPACE_KEYS.contains(&key) => Some(PrivacyBucket::Whitespace),
+            _ if NAVIGATION_KEYS.contains(&key) => Some(PrivacyBucket::Navigation),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_main_block() {
+        assert_eq!(KeyCategory::of("Q"), Some(KeyCategory::Letters));
+        assert_eq!(KeyCategory::of("7"), Some(KeyCategory::Numbers));
+        assert_eq!(KeyCategory::of(";"), Some(KeyCategory::Symbols));
+        assert_eq!(KeyCategory::of("\\"), Some(KeyCategory::Symbols));
+        assert_eq!(KeyCategory::of("Shift"), Some(KeyCategory::Modifiers));
+    }
+
+    #[test]
+    fn classifies_numpad() {
+        assert_eq!(KeyCategory::of("KP4"), Some(KeyCategory::Numbers));
+        assert_eq!(KeyCategory::of("KP+"), Some(KeyCategory::Symbols));
+        assert_eq!(KeyCategory::of("KPEnter"), None);
+    }
+
+    #[test]
+    fn other_keys_only_match_all_and_other() {
+        for key in ["Space", "Enter", "F5", "Key(172)"] {
+            assert_eq!(KeyCategory::of(key), None, "{}", key);
+            assert!(KeyCategory::All.matches(key));
+            assert!(KeyCategory::Other.matches(key));
+            assert!(!KeyCategory::Letters.matches(key));
+        }
+        assert!(!KeyCategory::Other.matches("A"));
+    }
+
+    #[test]
+    fn listener_names_fall_in_one_category() {
+        let cases: &[(&[&str], KeyCategory)] = &[
+            (&["A", "M", "Z"], KeyCategory::Letters),
+            (&["0", "9", "KP0", "KP9"], KeyCategory::Numbers),
+            (&["`", "-", "=", "[", "]", "\\", ";", "'", ",", ".", "/", "<>", "KP+", "KP-", "KP*", "KP/", "KP."], KeyCategory::Symbols),
+            (&["Shift", "Ctrl", "Alt", "AltGr", "Meta", "CapsLock", "Fn"], KeyCategory::Modifiers),
+            (
+                &[
+                    "Space", "Enter", "KPEnter", "Tab", "Backspace", "Esc", "Delete", "Insert", "Home", "End", "PageUp",
+                    "PageDown", "←", "↑", "→", "↓", "F1", "F12", "NumLock", "ScrollLock", "PrintScreen", "PauseBreak",
+                    "VolUp", "Mute", "Key(191)",
+                ],
+                KeyCategory::Other,
+            ),
+        ];
+        for (keys, expected) in cases {
+            for key in *keys {
+                let matching: Vec<_> = KeyCategory::ALL[1..].iter().filter(|category| category.matches(key)).collect();
+                assert_eq!(matching, [expected], "{}", key);
+            }
+        }
+    }
+
+    #[test]
+    fn character_keys() {
+        for key in ["A", "7", ";", "KP5", "Space", "Enter"] {
+            assert!(produces_character(key), "{}", key);
+        }
+        for key in ["Shift", "Ctrl", "←", "Backspace", "Tab", "F5"] {
+            assert!(!produces_character(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn privacy_buckets() {
+        assert_eq!(PrivacyBucket::of("Q"), Some(PrivacyBucket::Alpha));
+        assert_eq!(PrivacyBucket::of("KP4"), Some(PrivacyBucket::Digit));
+        assert_eq!(PrivacyBucket::of("/"), Some(PrivacyBucket::Symbol));
+        assert_eq!(PrivacyBucket::of("Space"), Some(PrivacyBucket::Whitespace));
+        assert_eq!(PrivacyBucket::of("PageDown"), Some(PrivacyBucket::Navigation));
+        assert_eq!(PrivacyBucket::of("Shift"), None);
+        assert_eq!(PrivacyBucket::of("F5"), None);
+    }
+}
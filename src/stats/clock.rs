@@ -0,0 +1,93 @@
+use chrono::{DateTime, Local};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Source of the current time for everything that depends on it
+/// (hourly buckets, daily keys, the WPM window, deduplication)
+pub trait Clock: Send + Sync {
+    /// Wall-clock time, used for dates and hours
+    fn now_local(&self) -> DateTime<Local>;
+    /// Monotonic time, used for durations
+    fn now_instant(&self) -> Instant;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_local(&self) -> DateTime<Local> {
+        Local::now()
+    }
+    
+    fn now_instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock handle stored in `Stats`, defaulting to the system clock
+#[derive(Clone)]
+pub struct SharedClock(pub Arc<dyn Clock>);
+
+impl SharedClock {
+    pub fn now_local(&self) -> DateTime<Local> {
+        self.0.now_local()
+    }
+    
+    pub fn now_instant(&self) -> Instant {
+        self.0.now_instant()
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self(Arc::new(SystemClock))
+    }
+}
+
+impl fmt::Debug for SharedClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SharedClock")
+    }
+}
+
+#[cfg(test)]
+pub use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    
+    /// Manually advanced clock for tests
+    pub struct MockClock {
+        now: Mutex<(DateTime<Local>, Instant)>,
+    }
+    
+    impl MockClock {
+        /// Clock standing at the given local date and time
+        pub fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> Arc<Self> {
+            let local = Local.with_ymd_and_hms(year, month, day, hour, minute, 0).unwrap();
+            Arc::new(Self { now: Mutex::new((local, Instant::now())) })
+        }
+        
+        pub fn advance(&self, by: Duration) {
+            let mut now = self.now.lock().unwrap();
+            now.0 += by;
+            now.1 += by;
+        }
+    }
+    
+    impl Clock for MockClock {
+        fn now_local(&self) -> DateTime<Local> {
+            self.now.lock().unwrap().0
+        }
+        
+        fn now_instant(&self) -> Instant {
+            self.now.lock().unwrap().1
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt::Write;
+
+use super::DailyStats;
+
+/// Hour with the most key presses and clicks combined, with that total
+pub fn peak_hour(day: &DailyStats) -> Option<(u8, u64)> {
+    (0..24u8)
+        .map(|hour| {
+            let keys = day.hourly_keys.get(&hour).copied().unwrap_or(0);
+            let clicks = day.hourly_clicks.get(&hour).copied().unwrap_or(0);
+            (hour, keys + clicks)
+        })
+        .filter(|(_, actions)| *actions > 0)
+        .max_by_key(|(hour, actions)| (*actions, std::cmp::Reverse(*hour)))
+}
+
+/// Most pressed key of a day from its per-key counts, if key names were recorded
+pub fn top_key<'a>(counts: impl IntoIterator<Item = (&'a String, &'a u64)>) -> Option<(&'a str, u64)> {
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(key, count)| (key.as_str(), *count))
+}
+
+/// One-line summary of a finished day, for the end-of-day notification
+pub fn headline(date: &str, day: &DailyStats) -> String {
+    format!(
+        "{} is over: {} keys, {} clicks, {:.0} m of cursor travel",
+        date,
+        day.total_keys,
+        day.total_clicks,
+        day.total_distance / 1000.0,
+    )
+}
+
+/// Markdown report of a finished day
+pub fn markdown(date: &str, day: &DailyStats, top_key: Option<(&str, u64)>) -> String {
+    let minutes = (day.active_seconds / 60.0).round() as u64;
+    let mut report = format!("# Finger Monitor report for {}\n\n", date);
+    report.push_str("| | |\n|---|---|\n");
+    let _ = writeln!(report, "| Keys | {} |", day.total_keys);
+    let _ = writeln!(report, "| Clicks | {} |", day.total_clicks);
+    let _ = writeln!(report, "| Cursor travel | {:.2} m |", day.total_distance / 1000.0);
+    let _ = writeln!(report, "| Active time | {}h {:02}m |", minutes / 60, minutes % 60);
+    let _ = writeln!(report, "| Peak WPM | {:.0} |", day.peak_wpm);
+    let _ = match top_key {
+        Some((key, count)) => writeln!(report, "| Most used key | `{}` ({} presses) |", key, count),
+        None => writeln!(report, "| Most used key | — |"),
+    };
+    let _ = match peak_hour(day) {
+        Some((hour, actions)) => writeln!(report, "| Busiest hour | {:02}:00–{:02}:59 ({} keys and clicks) |", hour, hour, actions),
+        None => writeln!(report, "| Busiest hour | — |"),
+    };
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn seeded_day() -> DailyStats {
+        DailyStats {
+            total_keys: 4200,
+            total_clicks: 310,
+            total_distance: 85_400.0,
+            peak_wpm: 71.6,
+            active_seconds: 2.5 * 3600.0 + 59.0,
+            hourly_keys: HashMap::from([(9, 1800), (14, 2400)]),
+            hourly_clicks: HashMap::from([(9, 10), (14, 300)]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn report_lists_the_headline_numbers() {
+        let report = markdown("2024-05-01", &seeded_day(), Some(("E", 512)));
+        assert!(report.starts_with("# Finger Monitor report for 2024-05-01\n"));
+        assert!(report.contains("| Keys | 4200 |"));
+        assert!(report.contains("| Clicks | 310 |"));
+        assert!(report.contains("| Cursor travel | 85.40 m |"));
+        assert!(report.contains("| Active time | 2h 31m |"));
+        assert!(report.contains("| Peak WPM | 72 |"));
+        assert!(report.contains("| Most used key | `E` (512 presses) |"));
+        assert!(report.contains("| Busiest hour | 14:00–14:59 (2700 keys and clicks) |"));
+    }
+
+    #[test]
+    fn empty_day_has_placeholders() {
+        let report = markdown("2024-05-01", &DailyStats::default(), None);
+        assert!(report.contains("| Most used key | — |"));
+        assert!(report.contains("| Busiest hour | — |"));
+        assert_eq!(headline("2024-05-01", &seeded_day()), "2024-05-01 is over: 4200 keys, 310 clicks, 85 m of cursor travel");
+    }
+
+    #[test]
+    fn top_key_breaks_ties_by_name() {
+        let counts: HashMap<String, u64> = [("B", 5), ("A", 5), ("C", 1)].map(|(k, c)| (k.to_string(), c)).into();
+        assert_eq!(top_key(&counts), Some(("A", 5)));
+        assert_eq!(top_key(&HashMap::new()), None);
+    }
+}
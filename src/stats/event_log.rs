@@ -0,0 +1,93 @@
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::store::StoredEvent;
+
+/// Bytes of events kept in memory before they are written to the file
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// One line of the event log
+#[derive(Serialize)]
+struct LogLine<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    name: &'a str,
+    /// Milliseconds since the Unix epoch
+    timestamp_ms: i64,
+}
+
+/// Opt-in, append-only JSON Lines log of every recorded key press and click,
+/// for analysis outside the app. Lines are buffered and reach the file when
+/// the buffer fills up or on `flush`, never synced per event.
+pub struct EventLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl EventLog {
+    /// Open `path` for appending, creating it and its directory if needed
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self { path, writer: BufWriter::with_capacity(BUFFER_SIZE, file) })
+    }
+
+    pub fn append(&mut self, event: &StoredEvent) -> io::Result<()> {
+        let line = LogLine {
+            kind: event.kind.as_str(),
+            name: &event.name,
+            timestamp_ms: event.time.timestamp_millis(),
+        };
+        serde_json::to_writer(&mut self.writer, &line)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Write the buffered lines to the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::store::EventKind;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn appends_json_lines() {
+        let path = std::env::temp_dir()
+            .join(format!("rust-finger-event-log-{}", std::process::id()))
+            .join("events.jsonl");
+        let _ = fs::remove_file(&path);
+        let time = Local.timestamp_millis_opt(1_714_550_400_125).unwrap();
+
+        let mut log = EventLog::open(path.clone()).unwrap();
+        log.append(&StoredEvent { time, kind: EventKind::Key, name: "\"".to_string() }).unwrap();
+        // Buffered until flushed
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        log.flush().unwrap();
+
+        // Reopening appends
+        let mut log = EventLog::open(path.clone()).unwrap();
+        log.append(&StoredEvent { time, kind: EventKind::Click, name: "Left".to_string() }).unwrap();
+        log.flush().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "key");
+        assert_eq!(lines[0]["name"], "\"");
+        assert_eq!(lines[0]["timestamp_ms"], 1_714_550_400_125i64);
+        assert_eq!(lines[1]["type"], "click");
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+/// Touch-typing finger responsible for a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    LeftThumb,
+    RightThumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+impl Finger {
+    /// All fingers from left pinky to right pinky
+    pub const ALL: [Finger; 10] = [
+        Finger::LeftPinky,
+        Finger::LeftRing,
+        Finger::LeftMiddle,
+        Finger::LeftIndex,
+        Finger::LeftThumb,
+        Finger::RightThumb,
+        Finger::RightIndex,
+        Finger::RightMiddle,
+        Finger::RightRing,
+        Finger::RightPinky,
+    ];
+
+    /// Short label for compact widgets
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            Finger::LeftPinky => "LP",
+            Finger::LeftRing => "LR",
+            Finger::LeftMiddle => "LM",
+            Finger::LeftIndex => "LI",
+            Finger::LeftThumb => "LT",
+            Finger::RightThumb => "RT",
+            Finger::RightIndex => "RI",
+            Finger::RightMiddle => "RM",
+            Finger::RightRing => "RR",
+            Finger::RightPinky => "RP",
+        }
+    }
+}
+
+/// Standard QWERTY touch-typing assignments.
+/// Shift/Ctrl/Meta are left out because both sides share one key name.
+pub const QWERTY_FINGERS: &[(&str, Finger)] = &[
+    ("Esc", Finger::LeftPinky), ("`", Finger::LeftPinky), ("1", Finger::LeftPinky),
+    ("Tab", Finger::LeftPinky), ("Q", Finger::LeftPinky), ("CapsLock", Finger::LeftPinky),
+    ("A", Finger::LeftPinky), ("<>", Finger::LeftPinky), ("Z", Finger::LeftPinky),
+    ("2", Finger::LeftRing), ("W", Finger::LeftRing), ("S", Finger::LeftRing), ("X", Finger::LeftRing),
+    ("3", Finger::LeftMiddle), ("E", Finger::LeftMiddle), ("D", Finger::LeftMiddle), ("C", Finger::LeftMiddle),
+    ("4", Finger::LeftIndex), ("5", Finger::LeftIndex), ("R", Finger::LeftIndex), ("T", Finger::LeftIndex),
+    ("F", Finger::LeftIndex), ("G", Finger::LeftIndex), ("V", Finger::LeftIndex), ("B", Finger::LeftIndex),
+    ("Alt", Finger::LeftThumb),
+    ("Space", Finger::RightThumb), ("AltGr", Finger::RightThumb),
+    ("6", Finger::RightIndex), ("7", Finger::RightIndex), ("Y", Finger::RightIndex), ("U", Finger::RightIndex),
+    ("H", Finger::RightIndex), ("J", Finger::RightIndex), ("N", Finger::RightIndex), ("M", Finger::RightIndex),
+    ("8", Finger::RightMiddle), ("I", Finger::RightMiddle), ("K", Finger::RightMiddle), (",", Finger::RightMiddle),
+    ("9", Finger::RightRing), ("O", Finger::RightRing), ("L", Finger::RightRing), (".", Finger::RightRing),
+    ("0", Finger::RightPinky), ("-", Finger::RightPinky), ("=", Finger::RightPinky), ("Backspace", Finger::RightPinky),
+    ("P", Finger::RightPinky), ("[", Finger::RightPinky), ("]", Finger::RightPinky), ("\\", Finger::RightPinky),
+    (";", Finger::RightPinky), ("'", Finger::RightPinky), ("Enter", Finger::RightPinky), ("/", Finger::RightPinky),
+];
+
+/// Nearest finger for keys outside the touch-typing rows
+pub const NEAREST_FINGERS: &[(&str, Finger)] = &[
+    ("F1", Finger::LeftRing), ("F2", Finger::LeftMiddle), ("F3", Finger::LeftMiddle),
+    ("F4", Finger::LeftIndex), ("F5", Finger::LeftIndex), ("F6", Finger::RightIndex),
+    ("F7", Finger::RightIndex), ("F8", Finger::RightMiddle), ("F9", Finger::RightRing),
+    ("F10", Finger::RightPinky), ("F11", Finger::RightPinky), ("F12", Finger::RightPinky),
+    ("Insert", Finger::RightIndex), ("Delete", Finger::RightIndex),
+    ("Home", Finger::RightMiddle), ("End", Finger::RightMiddle),
+    ("PageUp", Finger::RightRing), ("PageDown", Finger::RightRing),
+    ("←", Finger::RightIndex), ("↑", Finger::RightMiddle), ("↓", Finger::RightMiddle), ("→", Finger::RightRing),
+    ("NumLock", Finger::RightIndex), ("KP7", Finger::RightIndex), ("KP4", Finger::RightIndex), ("KP1", Finger::RightIndex),
+    ("KP/", Finger::RightMiddle), ("KP8", Finger::RightMiddle), ("KP5", Finger::RightMiddle), ("KP2", Finger::RightMiddle),
+    ("KP*", Finger::RightRing), ("KP9", Finger::RightRing), ("KP6", Finger::RightRing), ("KP3", Finger::RightRing),
+    ("KP.", Finger::RightRing), ("KP-", Finger::RightPinky), ("KP+", Finger::RightPinky), ("KPEnter", Finger::RightPinky),
+    ("KP0", Finger::RightThumb),
+];
+
+/// Keys present on both sides under one name, pressed by either finger
+const SHARED_KEYS: &[(&str, Finger, Finger)] = &[
+    ("Shift", Finger::LeftPinky, Finger::RightPinky),
+    ("Ctrl", Finger::LeftPinky, Finger::RightPinky),
+    ("Meta", Finger::LeftThumb, Finger::RightThumb),
+];
+
+/// Estimated finger(s) pressing a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attribution {
+    One(Finger),
+    /// Either of two fingers; presses are split evenly between them
+    Split(Finger, Finger),
+}
+
+/// Lookup table from key name to finger
+#[derive(Debug, Clone)]
+pub struct FingerMap {
+    assignments: HashMap<String, Finger>,
+}
+
+impl FingerMap {
+    /// Build a map from `(key, finger)` pairs
+    pub fn from_table(table: &[(&str, Finger)]) -> Self {
+        Self {
+            assignments: table.iter().map(|(key, finger)| (key.to_string(), *finger)).collect(),
+        }
+    }
+
+    pub fn finger_of(&self, key: &str) -> Option<Finger> {
+        self.assignments.get(key).copied()
+    }
+
+    /// Best guess for any key: the assigned finger, else the nearest finger,
+    /// else both sides of a shared modifier. `None` for unknown keys.
+    pub fn estimate(&self, key: &str) -> Option<Attribution> {
+        if let Some(finger) = self.finger_of(key) {
+            return Some(Attribution::One(finger));
+        }
+        if let Some((_, finger)) = NEAREST_FINGERS.iter().find(|(k, _)| *k == key) {
+            return Some(Attribution::One(*finger));
+        }
+        SHARED_KEYS.iter()
+            .find(|(k, _, _)| *k == key)
+            .map(|(_, left, right)| Attribution::Split(*left, *right))
+    }
+}
+
+impl Default for FingerMap {
+    fn default() -> Self {
+        Self::from_table(QWERTY_FINGERS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn home_row_uses_expected_fingers() {
+        let map = FingerMap::default();
+        let home = ["A", "S", "D", "F", "J", "K", "L", ";"];
+        let fingers = [
+            Finger::LeftPinky, Finger::LeftRing, Finger::LeftMiddle, Finger::LeftIndex,
+            Finger::RightIndex, Finger::RightMiddle, Finger::RightRing, Finger::RightPinky,
+        ];
+        for (key, finger) in home.iter().zip(fingers) {
+            assert_eq!(map.finger_of(key), Some(finger), "{}", key);
+        }
+    }
+
+    #[test]
+    fn ambiguous_and_unknown_keys_are_unassigned() {
+        let map = FingerMap::default();
+        assert_eq!(map.finger_of("Shift"), None);
+        assert_eq!(map.finger_of("F5"), None);
+        assert_eq!(map.finger_of("Key(172)"), None);
+    }
+
+    #[test]
+    fn estimate_falls_back_to_nearest_and_shared() {
+        let map = FingerMap::default();
+        assert_eq!(map.estimate("J"), Some(Attribution::One(Finger::RightIndex)));
+        assert_eq!(map.estimate("F5"), Some(Attribution::One(Finger::LeftIndex)));
+        assert_eq!(map.estimate("KP0"), Some(Attribution::One(Finger::RightThumb)));
+        assert_eq!(map.estimate("Shift"), Some(Attribution::Split(Finger::LeftPinky, Finger::RightPinky)));
+        assert_eq!(map.estimate("Key(172)"), None);
+    }
+
+    #[test]
+    fn nearest_table_does_not_shadow_assignments() {
+        let map = FingerMap::default();
+        for (key, _) in NEAREST_FINGERS {
+            assert_eq!(map.finger_of(key), None, "{} is already assigned", key);
+        }
+    }
+
+    #[test]
+    fn custom_tables_replace_defaults() {
+        let map = FingerMap::from_table(&[("Space", Finger::LeftThumb)]);
+        assert_eq!(map.finger_of("Space"), Some(Finger::LeftThumb));
+        assert_eq!(map.finger_of("A"), None);
+    }
+}
@@ -0,0 +1,98 @@
+/// Which hand is used to press a key on a standard QWERTY keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+    /// Thumb keys and modifiers whose side is not recorded
+    Both,
+}
+
+/// Keys typed with the left hand
+const LEFT_KEYS: &[&str] = &[
+    "Esc", "F1", "F2", "F3", "F4", "F5",
+    "`", "1", "2", "3", "4", "5",
+    "Tab", "Q", "W", "E", "R", "T",
+    "CapsLock", "A", "S", "D", "F", "G",
+    "<>", "Z", "X", "C", "V", "B",
+    "Alt",
+];
+
+/// Keys typed with the right hand
+const RIGHT_KEYS: &[&str] = &[
+    "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+    "6", "7", "8", "9", "0", "-", "=", "Backspace",
+    "Y", "U", "I", "O", "P", "[", "]", "\\",
+    "H", "J", "K", "L", ";", "'", "Enter",
+    "N", "M", ",", ".", "/",
+    "AltGr",
+    "Insert", "Delete", "Home", "End", "PageUp", "PageDown",
+    "↑", "↓", "←", "→",
+    "NumLock", "KP/", "KP*", "KP-", "KP+", "KPEnter", "KP.",
+    "KP0", "KP1", "KP2", "KP3", "KP4", "KP5", "KP6", "KP7", "KP8", "KP9",
+];
+
+/// Keys pressed with either hand. Left and right Shift/Ctrl/Meta share a name,
+/// so their side is unknown.
+const BOTH_KEYS: &[&str] = &["Space", "Shift", "Ctrl", "Meta"];
+
+/// Classify a key name into the hand that presses it.
+/// Returns `None` for keys outside the mapping (unknown codes, media keys).
+pub fn classify(key: &str) -> Option<Hand> {
+    if LEFT_KEYS.contains(&key) {
+        Some(Hand::Left)
+    } else if RIGHT_KEYS.contains(&key) {
+        Some(Hand::Right)
+    } else if BOTH_KEYS.contains(&key) {
+        Some(Hand::Both)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Same rows as the dashboard's QWERTY heatmap layout
+    const QWERTY_ROWS: &[&[&str]] = &[
+        &["`", "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "=", "Backspace"],
+        &["Tab", "Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P", "[", "]", "\\"],
+        &["CapsLock", "A", "S", "D", "F", "G", "H", "J", "K", "L", ";", "'", "Enter"],
+        &["Shift", "Z", "X", "C", "V", "B", "N", "M", ",", ".", "/", "Shift"],
+        &["Ctrl", "Meta", "Alt", "Space", "Alt", "Meta", "Ctrl"],
+    ];
+
+    #[test]
+    fn every_layout_key_is_classified() {
+        for key in QWERTY_ROWS.iter().flat_map(|row| row.iter()) {
+            assert!(classify(key).is_some(), "{} is not classified", key);
+        }
+    }
+
+    #[test]
+    fn rows_split_between_hands() {
+        assert_eq!(classify("T"), Some(Hand::Left));
+        assert_eq!(classify("Y"), Some(Hand::Right));
+        assert_eq!(classify("G"), Some(Hand::Left));
+        assert_eq!(classify("H"), Some(Hand::Right));
+        assert_eq!(classify("B"), Some(Hand::Left));
+        assert_eq!(classify("N"), Some(Hand::Right));
+        assert_eq!(classify("Space"), Some(Hand::Both));
+    }
+
+    #[test]
+    fn unknown_keys_are_unmapped() {
+        assert_eq!(classify("Key(172)"), None);
+        assert_eq!(classify("VolumeUp"), None);
+    }
+
+    #[test]
+    fn tables_do_not_overlap() {
+        for key in LEFT_KEYS {
+            assert!(!RIGHT_KEYS.contains(key) && !BOTH_KEYS.contains(key), "{} mapped twice", key);
+        }
+        for key in RIGHT_KEYS {
+            assert!(!BOTH_KEYS.contains(key), "{} mapped twice", key);
+        }
+    }
+}
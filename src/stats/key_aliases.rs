@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Key names recorded by older versions, and what the listener calls them now
+const RENAMED_KEYS: &[(&str, &str)] = &[
+    ("Pause", "PauseBreak"),
+    ("Function", "Fn"),
+    ("Kp0", "KP0"),
+    ("Kp1", "KP1"),
+    ("Kp2", "KP2"),
+    ("Kp3", "KP3"),
+    ("Kp4", "KP4"),
+    ("Kp5", "KP5"),
+    ("Kp6", "KP6"),
+    ("Kp7", "KP7"),
+    ("Kp8", "KP8"),
+    ("Kp9", "KP9"),
+    ("KpPlus", "KP+"),
+    ("KpMinus", "KP-"),
+    ("KpMultiply", "KP*"),
+    ("KpDivide", "KP/"),
+    ("KpDelete", "KP."),
+    ("KpReturn", "KPEnter"),
+];
+
+/// Custom key names from the config (e.g. `"Key(191)" = "Macro1"`) on top of
+/// the renames of names recorded by older versions.
+///
+/// Custom names are followed until a name without one, so `"KP+" = "Plus"`
+/// and `"Plus" = "+"` both end at "+"; cycles are rejected when loading.
+/// A custom name for the exact recorded name wins. Otherwise an old name is
+/// first renamed to the current one, so a custom name for e.g. "PauseBreak"
+/// also covers history recorded as "Pause".
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "HashMap<String, String>", into = "HashMap<String, String>")]
+pub struct KeyAliases {
+    custom: HashMap<String, String>,
+}
+
+impl KeyAliases {
+    /// Aliases from a custom name table, or an error naming a cycle in it
+    pub fn new(custom: HashMap<String, String>) -> Result<Self, String> {
+        for start in custom.keys() {
+            let mut seen = HashSet::new();
+            let mut name = start;
+            while let Some(next) = custom.get(name) {
+                if !seen.insert(name) {
+                    return Err(format!("key alias cycle through \"{}\"", start));
+                }
+                name = next;
+            }
+        }
+        Ok(Self { custom })
+    }
+
+    /// Name `name` is shown and recorded as, or `None` if it has no alias
+    pub fn resolve<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if self.custom.contains_key(name) {
+            return Some(self.follow(name));
+        }
+        let renamed = RENAMED_KEYS.iter().find(|(old, _)| *old == name).map(|(_, new)| *new)?;
+        Some(self.follow(renamed))
+    }
+
+    /// `name` with its alias applied
+    pub fn apply(&self, name: &str) -> String {
+        self.resolve(name).unwrap_or(name).to_string()
+    }
+
+    /// End of the chain of custom names starting at `name`
+    fn follow<'a>(&'a self, mut name: &'a str) -> &'a str {
+        while let Some(next) = self.custom.get(name) {
+            name = next;
+        }
+        name
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for KeyAliases {
+    type Error = String;
+
+    fn try_from(custom: HashMap<String, String>) -> Result<Self, Self::Error> {
+        Self::new(custom)
+    }
+}
+
+impl From<KeyAliases> for HashMap<String, String> {
+    fn from(aliases: KeyAliases) -> Self {
+        aliases.custom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(pairs: &[(&str, &str)]) -> KeyAliases {
+        KeyAliases::new(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()).unwrap()
+    }
+
+    #[test]
+    fn renames_old_names() {
+        let aliases = KeyAliases::default();
+        assert_eq!(aliases.apply("Kp7"), "KP7");
+        assert_eq!(aliases.apply("Pause"), "PauseBreak");
+        assert_eq!(aliases.resolve("A"), None);
+        assert_eq!(aliases.apply("Key(191)"), "Key(191)");
+    }
+
+    #[test]
+    fn custom_names_take_precedence() {
+        let aliases = aliases(&[("Key(191)", "Macro1"), ("PauseBreak", "Break"), ("Kp0", "Zero")]);
+        assert_eq!(aliases.apply("Key(191)"), "Macro1");
+        // A custom name for the current name covers the old one too
+        assert_eq!(aliases.apply("PauseBreak"), "Break");
+        assert_eq!(aliases.apply("Pause"), "Break");
+        // ...unless the old name has a custom name of its own
+        assert_eq!(aliases.apply("Kp0"), "Zero");
+        assert_eq!(aliases.apply("KP0"), "KP0");
+    }
+
+    #[test]
+    fn chains_are_followed() {
+        let aliases = aliases(&[("KP+", "Plus"), ("Plus", "+"), ("Pause", "Brk"), ("PauseBreak", "Break")]);
+        assert_eq!(aliases.apply("KP+"), "+");
+        assert_eq!(aliases.apply("Plus"), "+");
+        assert_eq!(aliases.apply("KpPlus"), "+");
+        assert_eq!(aliases.apply("Pause"), "Brk");
+        assert_eq!(aliases.apply("PauseBreak"), "Break");
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let table = |pairs: &[(&str, &str)]| pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        assert!(KeyAliases::new(table(&[("A", "B"), ("B", "C"), ("C", "A")])).is_err());
+        assert!(KeyAliases::new(table(&[("A", "A")])).is_err());
+        assert!(KeyAliases::new(table(&[("A", "B"), ("C", "B")])).is_ok());
+    }
+}
@@ -0,0 +1,133 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::{Stats, SCHEMA_VERSION};
+
+/// Error loading persisted stats
+#[derive(Debug)]
+pub enum LoadError {
+    /// Written by a newer build; must not be read or overwritten
+    FutureVersion { found: u32 },
+    /// Not valid stats JSON
+    Invalid(serde_json::Error),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::FutureVersion { found } => write!(
+                f,
+                "stats use schema version {}, but this build only understands up to version {}",
+                found, SCHEMA_VERSION,
+            ),
+            LoadError::Invalid(e) => write!(f, "invalid stats data: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(e: serde_json::Error) -> Self {
+        LoadError::Invalid(e)
+    }
+}
+
+/// Whether a boxed error is a `LoadError::FutureVersion`
+pub fn is_future_version(error: &(dyn std::error::Error + 'static)) -> bool {
+    matches!(error.downcast_ref::<LoadError>(), Some(LoadError::FutureVersion { .. }))
+}
+
+/// Parse persisted stats of any known version, upgrading them to the current layout
+pub fn stats_from_json(json: &str) -> Result<Stats, LoadError> {
+    let value: Value = serde_json::from_str(json)?;
+    
+    // Files without a version marker predate versioning
+    let mut version = value.get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v.max(1) as u32);
+    if version > SCHEMA_VERSION {
+        return Err(LoadError::FutureVersion { found: version });
+    }
+    
+    let mut fields = match value {
+        Value::Object(fields) => fields,
+        _ => return Err(LoadError::Invalid(serde::de::Error::custom("expected a JSON object"))),
+    };
+    
+    // Upgrade one version at a time
+    while version < SCHEMA_VERSION {
+        fields = match version {
+            1 => serde_json::from_value::<StatsV1>(Value::Object(fields))?.upgrade(),
+            _ => unreachable!("no migration from schema version {}", version),
+        };
+        version += 1;
+    }
+    
+    let mut stats: Stats = serde_json::from_value(Value::Object(fields))?;
+    stats.schema_version = SCHEMA_VERSION;
+    Ok(stats)
+}
+
+/// Version 1: scrolling was a single undirected total
+#[derive(Deserialize)]
+struct StatsV1 {
+    #[serde(default)]
+    scroll_distance: i64,
+    #[serde(flatten)]
+    rest: Map<String, Value>,
+}
+
+impl StatsV1 {
+    /// Version 2 splits scrolling into up and down. The direction of older
+    /// scrolling is unknown, so it is counted as down, the common direction
+    /// when reading.
+    fn upgrade(self) -> Map<String, Value> {
+        let mut fields = self.rest;
+        fields.insert("scroll_up".to_string(), Value::from(0));
+        fields.insert("scroll_down".to_string(), Value::from(self.scroll_distance));
+        fields.insert("schema_version".to_string(), Value::from(2));
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_file_migrates_scroll_distance() {
+        let json = r#"{"key_counts": {"A": 3}, "scroll_distance": 42}"#;
+        let stats = stats_from_json(json).unwrap();
+        assert_eq!(stats.schema_version, SCHEMA_VERSION);
+        assert_eq!(stats.key_counts["A"], 3);
+        assert_eq!((stats.scroll_up, stats.scroll_down), (0, 42));
+        assert_eq!(stats.scroll_distance(), 42);
+    }
+
+    #[test]
+    fn current_version_loads_unchanged() {
+        let mut stats = Stats::new();
+        stats.scroll_up = 5;
+        stats.scroll_down = 7;
+        let json = serde_json::to_string(&stats).unwrap();
+
+        let loaded = stats_from_json(&json).unwrap();
+        assert_eq!((loaded.scroll_up, loaded.scroll_down), (5, 7));
+    }
+
+    #[test]
+    fn future_version_is_rejected() {
+        let json = format!(r#"{{"schema_version": {}}}"#, SCHEMA_VERSION + 1);
+        let error = stats_from_json(&json).unwrap_err();
+        assert!(matches!(error, LoadError::FutureVersion { .. }));
+
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(is_future_version(boxed.as_ref()));
+    }
+
+    #[test]
+    fn non_object_is_invalid() {
+        assert!(matches!(stats_from_json("[1, 2]"), Err(LoadError::Invalid(_))));
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+/// Columns and rows of the screen grid cursor positions and clicks are counted in
+pub const GRID_COLUMNS: usize = 64;
+pub const GRID_ROWS: usize = 36;
+
+/// Shortest time between two recorded cursor positions
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Index into the flattened grid of the cell containing (`x`, `y`) on a
+/// display of `width` × `height` pixels. Positions outside the display, e.g.
+/// on a second monitor, fall into the nearest edge cell.
+pub fn cell(x: f64, y: f64, (width, height): (u64, u64)) -> usize {
+    let column = scale(x, width, GRID_COLUMNS);
+    let row = scale(y, height, GRID_ROWS);
+    row * GRID_COLUMNS + column
+}
+
+/// Count one sample in `cell`, creating the grid on first use
+pub fn increment(grid: &mut Vec<u64>, cell: usize) {
+    if grid.is_empty() {
+        *grid = vec![0; GRID_COLUMNS * GRID_ROWS];
+    }
+    if let Some(count) = grid.get_mut(cell) {
+        *count += 1;
+    }
+}
+
+/// Add the counts of another grid, e.g. from a second machine
+pub fn add(grid: &mut Vec<u64>, other: &[u64]) {
+    if grid.len() < other.len() {
+        grid.resize(other.len(), 0);
+    }
+    for (ours, theirs) in grid.iter_mut().zip(other) {
+        *ours += theirs;
+    }
+}
+
+/// Bucket of `position` along an axis of `length` pixels split into `buckets`
+fn scale(position: f64, length: u64, buckets: usize) -> usize {
+    if length == 0 || !position.is_finite() {
+        return 0;
+    }
+    let bucket = (position / length as f64 * buckets as f64).floor();
+    bucket.clamp(0.0, (buckets - 1) as f64) as usize
+}
+
+/// Lets at most one cursor position through per `SAMPLE_INTERVAL`
+#[derive(Debug, Default)]
+pub struct PositionSampler {
+    last: Option<Instant>,
+}
+
+impl PositionSampler {
+    /// Whether a position seen at `now` should be recorded
+    pub fn sample(&mut self, now: Instant) -> bool {
+        if self.last.is_some_and(|last| now.duration_since(last) < SAMPLE_INTERVAL) {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+/// Serializes the grid as `[cell, count]` pairs of the visited cells only,
+/// since most of the screen is rarely visited
+pub mod serde_sparse {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(grid: &[u64], serializer: S) -> Result<S::Ok, S::Error> {
+        grid.iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .collect::<Vec<_>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u64>, D::Error> {
+        let cells = Vec::<(usize, u64)>::deserialize(deserializer)?;
+        if cells.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut grid = vec![0; GRID_COLUMNS * GRID_ROWS];
+        for (cell, count) in cells {
+            if let Some(slot) = grid.get_mut(cell) {
+                *slot += count;
+            }
+        }
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISPLAY: (u64, u64) = (1920, 1080);
+
+    #[test]
+    fn maps_positions_to_cells() {
+        assert_eq!(cell(0.0, 0.0, DISPLAY), 0);
+        assert_eq!(cell(1919.0, 1079.0, DISPLAY), GRID_COLUMNS * GRID_ROWS - 1);
+        // 30 pixels per cell in both directions
+        assert_eq!(cell(45.0, 45.0, DISPLAY), GRID_COLUMNS + 1);
+    }
+
+    #[test]
+    fn clamps_positions_off_the_display() {
+        // A monitor left of and one below the primary display
+        assert_eq!(cell(-800.0, 10.0, DISPLAY), 0);
+        assert_eq!(cell(2500.0, 2000.0, DISPLAY), GRID_COLUMNS * GRID_ROWS - 1);
+        assert_eq!(cell(f64::NAN, 10.0, DISPLAY), 0);
+        assert_eq!(cell(10.0, 10.0, (0, 0)), 0);
+    }
+
+    #[test]
+    fn samples_at_most_every_interval() {
+        let start = Instant::now();
+        let mut sampler = PositionSampler::default();
+        assert!(sampler.sample(start));
+        assert!(!sampler.sample(start + Duration::from_millis(30)));
+        assert!(sampler.sample(start + Duration::from_millis(50)));
+        assert!(!sampler.sample(start + Duration::from_millis(99)));
+    }
+
+    #[test]
+    fn stores_visited_cells_only() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Wrapper(#[serde(with = "serde_sparse")] Vec<u64>);
+
+        let mut grid = vec![0; GRID_COLUMNS * GRID_ROWS];
+        grid[3] = 7;
+        grid[GRID_COLUMNS * GRID_ROWS - 1] = 2;
+        let json = serde_json::to_string(&Wrapper(grid.clone())).unwrap();
+        assert_eq!(json, "[[3,7],[2303,2]]");
+        assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap().0, grid);
+        assert!(serde_json::from_str::<Wrapper>("[]").unwrap().0.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+/// Default longest gap between the presses of a double or triple click
+pub const DEFAULT_MULTI_CLICK_WINDOW: Duration = Duration::from_millis(400);
+
+/// Counts consecutive presses of the same mouse button into double and
+/// triple clicks.
+///
+/// A run ends after the third press, so a fourth quick press starts a new
+/// run instead of counting as another triple click.
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    previous: Option<(String, Instant, u32)>,
+}
+
+impl ClickTracker {
+    /// Note a press, returning its position in the current run: 1 for a
+    /// single click, 2 when it completes a double click, 3 for a triple click
+    pub fn observe(&mut self, button: &str, now: Instant, window: Duration) -> u32 {
+        let position = match &self.previous {
+            Some((previous, time, position))
+                if previous == button && *position < 3 && now.duration_since(*time) <= window =>
+            {
+                position + 1
+            }
+            _ => 1,
+        };
+        self.previous = Some((button.to_string(), now, position));
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(presses: &[(&str, u64)]) -> Vec<u32> {
+        let start = Instant::now();
+        let mut tracker = ClickTracker::default();
+        presses
+            .iter()
+            .map(|(button, ms)| tracker.observe(button, start + Duration::from_millis(*ms), DEFAULT_MULTI_CLICK_WINDOW))
+            .collect()
+    }
+
+    #[test]
+    fn counts_runs_within_window() {
+        // Double click, a pause, then a triple click
+        assert_eq!(positions(&[("Left", 0), ("Left", 180), ("Left", 1000), ("Left", 1300), ("Left", 1600)]), [1, 2, 1, 2, 3]);
+        // Just past the window the run starts over
+        assert_eq!(positions(&[("Left", 0), ("Left", 401)]), [1, 1]);
+    }
+
+    #[test]
+    fn runs_end_after_a_triple_click() {
+        assert_eq!(positions(&[("Left", 0), ("Left", 100), ("Left", 200), ("Left", 300), ("Left", 400)]), [1, 2, 3, 1, 2]);
+    }
+
+    #[test]
+    fn other_buttons_break_runs() {
+        assert_eq!(positions(&[("Left", 0), ("Right", 100), ("Left", 200), ("Left", 300)]), [1, 1, 1, 2]);
+    }
+}
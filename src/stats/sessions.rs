@@ -0,0 +1,109 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Number of past sessions kept in the stats; older ones are dropped
+pub const SESSION_HISTORY_LIMIT: usize = 200;
+
+/// Summary of one run of the app
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub started_at: DateTime<Local>,
+    /// Shutdown time, or the last checkpoint if the app did not shut down cleanly
+    pub ended_at: DateTime<Local>,
+    pub keys: u64,
+    pub clicks: u64,
+    /// Cursor travel in pixels
+    pub distance: f64,
+    pub peak_wpm: f64,
+}
+
+impl SessionRecord {
+    /// Session without any activity yet
+    pub fn new(started_at: DateTime<Local>) -> Self {
+        Self {
+            started_at,
+            ended_at: started_at,
+            keys: 0,
+            clicks: 0,
+            distance: 0.0,
+            peak_wpm: 0.0,
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        (self.ended_at - self.started_at).to_std().unwrap_or_default()
+    }
+
+    /// Whether nothing was recorded; such sessions are not stored
+    pub fn is_empty(&self) -> bool {
+        self.keys == 0 && self.clicks == 0 && self.distance == 0.0
+    }
+}
+
+/// Store `session` in `history`, replacing its earlier checkpoint, and drop
+/// the oldest sessions beyond `SESSION_HISTORY_LIMIT`
+pub fn checkpoint(history: &mut Vec<SessionRecord>, session: &SessionRecord) {
+    match history.iter_mut().rev().find(|stored| stored.started_at == session.started_at) {
+        Some(stored) => *stored = session.clone(),
+        None => history.push(session.clone()),
+    }
+    trim(history);
+}
+
+/// Add the sessions of another history, keeping `history` ordered by start time
+pub fn merge(history: &mut Vec<SessionRecord>, other: &[SessionRecord]) {
+    history.extend(other.iter().cloned());
+    history.sort_by_key(|session| session.started_at);
+    trim(history);
+}
+
+fn trim(history: &mut Vec<SessionRecord>) {
+    if history.len() > SESSION_HISTORY_LIMIT {
+        history.drain(..history.len() - SESSION_HISTORY_LIMIT);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn checkpoints_replace_the_same_session() {
+        let mut history = vec![SessionRecord::new(at(8, 0))];
+        let mut session = SessionRecord::new(at(10, 0));
+        session.keys = 5;
+        session.ended_at = at(10, 1);
+        checkpoint(&mut history, &session);
+        session.keys = 9;
+        session.ended_at = at(10, 30);
+        checkpoint(&mut history, &session);
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].keys, 9);
+        assert_eq!(history[1].duration(), Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn history_keeps_the_newest_sessions() {
+        let mut history = Vec::new();
+        for minute in 0..SESSION_HISTORY_LIMIT as i64 + 5 {
+            checkpoint(&mut history, &SessionRecord::new(at(0, 0) + chrono::Duration::minutes(minute)));
+        }
+        assert_eq!(history.len(), SESSION_HISTORY_LIMIT);
+        assert_eq!(history[0].started_at, at(0, 5));
+    }
+
+    #[test]
+    fn merge_orders_by_start() {
+        let mut history = vec![SessionRecord::new(at(8, 0)), SessionRecord::new(at(12, 0))];
+        merge(&mut history, &[SessionRecord::new(at(10, 0))]);
+        let starts: Vec<_> = history.iter().map(|session| session.started_at).collect();
+        assert_eq!(starts, vec![at(8, 0), at(10, 0), at(12, 0)]);
+    }
+}
@@ -0,0 +1,263 @@
+mod json;
+#[cfg(test)]
+mod memory;
+mod sqlite;
+
+pub use json::JsonStore;
+#[cfg(test)]
+pub use memory::MemoryStore;
+pub use sqlite::SqliteStore;
+
+use chrono::{DateTime, Local};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::Stats;
+
+pub type StoreResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Persistence backend for the statistics
+pub trait StatsStore: Send + Sync {
+    /// Load the persisted statistics
+    fn load(&self) -> StoreResult<Stats>;
+    
+    /// Persist the full aggregate statistics
+    fn save(&self, stats: &Stats) -> StoreResult<()>;
+    
+    /// Append raw input events recorded since the last save.
+    /// Backends without an event log ignore them.
+    fn append_events(&self, events: &[StoredEvent]) -> StoreResult<()>;
+    
+    /// Whether `append_events` keeps the events
+    fn keeps_events(&self) -> bool {
+        false
+    }
+    
+    /// Drop the events recorded before `cutoff`, returning how many were dropped
+    fn prune_events(&self, _cutoff: DateTime<Local>) -> StoreResult<usize> {
+        Ok(0)
+    }
+    
+    /// File or database the statistics are stored in
+    fn path(&self) -> &Path;
+    
+    /// What went wrong during the last `load` that the user should know
+    /// about even though loading went on, e.g. a corrupt file set aside
+    fn take_load_warning(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Kind of a raw input event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Key,
+    Click,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Key => "key",
+            EventKind::Click => "click",
+        }
+    }
+}
+
+/// A single key press or click, as appended to the event log
+#[derive(Debug, Clone)]
+pub struct StoredEvent {
+    pub time: DateTime<Local>,
+    pub kind: EventKind,
+    /// Key or button name
+    pub name: String,
+}
+
+/// Selectable storage backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageBackend {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(StorageBackend::Json),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            other => Err(format!("unknown storage backend: {}", other)),
+        }
+    }
+}
+
+/// Environment variable overriding the data directory, e.g. for a portable install
+pub const DATA_DIR_ENV: &str = "FINGER_DATA_DIR";
+
+/// `data_dir` from the config file, set once at startup
+static CONFIGURED_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `dir` as the data directory unless `FINGER_DATA_DIR` is set. Call it
+/// before opening any file in the data directory.
+pub fn set_data_dir(dir: PathBuf) {
+    if CONFIGURED_DATA_DIR.set(dir).is_err() {
+        log::warn!("Data directory already set, ignoring the new one");
+    }
+}
+
+/// Directory holding the statistics files: `FINGER_DATA_DIR` if set, then
+/// `data_dir` from the config file, otherwise `rust-finger` in the local
+/// data directory
+pub fn data_dir() -> PathBuf {
+    data_dir_from(std::env::var_os(DATA_DIR_ENV), CONFIGURED_DATA_DIR.get())
+}
+
+fn data_dir_from(override_dir: Option<OsString>, configured: Option<&PathBuf>) -> PathBuf {
+    match override_dir.filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => configured.cloned().unwrap_or_else(|| {
+            dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("rust-finger")
+        }),
+    }
+}
+
+/// `path` with `extension` appended to its file name, e.g. `stats.json.bak`
+pub fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+/// Startup backups kept when the config sets no number
+pub const DEFAULT_STARTUP_BACKUPS: usize = 3;
+
+/// Slot `n` of the backups of `path` in `dir`, e.g. `stats.backup.2.json`
+fn backup_path(dir: &Path, path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => dir.join(format!("{}.backup.{}.{}", stem, n, extension.to_string_lossy())),
+        None => dir.join(format!("{}.backup.{}", stem, n)),
+    }
+}
+
+/// Copy `path` into slot 1 of its backups in `dir`, shifting the older ones
+/// up a slot and discarding those beyond `slots`. Returns the new backup,
+/// or `None` if there is nothing to back up. With `slots` 0 backups are
+/// off, and those taken before are left alone.
+fn rotate_backups(path: &Path, dir: &Path, slots: usize) -> io::Result<Option<PathBuf>> {
+    if slots == 0 {
+        return Ok(None);
+    }
+    // Slots left over from a larger setting go first
+    let mut n = slots + 1;
+    while backup_path(dir, path, n).exists() {
+        fs::remove_file(backup_path(dir, path, n))?;
+        n += 1;
+    }
+    if !path.exists() {
+        return Ok(None);
+    }
+    
+    fs::create_dir_all(dir)?;
+    for n in (1..slots).rev() {
+        let older = backup_path(dir, path, n);
+        if older.exists() {
+            fs::rename(&older, backup_path(dir, path, n + 1))?;
+        }
+    }
+    let newest = backup_path(dir, path, 1);
+    fs::copy(path, &newest)?;
+    Ok(Some(newest))
+}
+
+/// Copy the stats file of `backend` into `backups/` in the data directory
+/// before it is loaded, keeping the copies of the last `slots` starts. Only
+/// called by the instance holding the lock, so the file is not being written.
+pub fn back_up_on_startup(backend: StorageBackend, slots: usize) {
+    let dir = data_dir();
+    let path = match backend {
+        StorageBackend::Json => dir.join("stats.json"),
+        StorageBackend::Sqlite => dir.join("stats.db"),
+    };
+    match rotate_backups(&path, &dir.join("backups"), slots) {
+        Ok(Some(backup)) => log::info!("Backed up {} to {}", path.display(), backup.display()),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to back up {}: {}", path.display(), e),
+    }
+}
+
+/// Open the store for the selected backend in the default data directory.
+/// A fresh SQLite database imports the existing `stats.json`.
+pub fn open(backend: StorageBackend) -> StoreResult<Box<dyn StatsStore>> {
+    let dir = data_dir();
+    let json_path = dir.join("stats.json");
+    
+    match backend {
+        StorageBackend::Json => Ok(Box::new(JsonStore::new(json_path))),
+        StorageBackend::Sqlite => {
+            let store = SqliteStore::open(dir.join("stats.db"))?;
+            store.import_if_empty(&JsonStore::new(json_path))?;
+            Ok(Box::new(store))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_dir_override() {
+        let configured = PathBuf::from("/srv/finger");
+        assert_eq!(data_dir_from(Some("/media/usb/finger".into()), Some(&configured)), PathBuf::from("/media/usb/finger"));
+        assert_eq!(data_dir_from(Some(OsString::new()), Some(&configured)), configured);
+        assert!(data_dir_from(Some(OsString::new()), None).ends_with("rust-finger"));
+        assert!(data_dir_from(None, None).ends_with("rust-finger"));
+    }
+
+    fn backups(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn startup_backups_rotate() {
+        let dir = std::env::temp_dir().join(format!("rust-finger-backups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stats.json");
+        let backup_dir = dir.join("backups");
+        // Nothing to back up before the first save
+        assert_eq!(rotate_backups(&path, &backup_dir, 3).unwrap(), None);
+
+        for start in 1..=5 {
+            fs::write(&path, format!("start {}", start)).unwrap();
+            rotate_backups(&path, &backup_dir, 3).unwrap();
+        }
+        assert_eq!(backups(&backup_dir), ["stats.backup.1.json", "stats.backup.2.json", "stats.backup.3.json"]);
+        // Newest first; starts 1 and 2 were discarded
+        assert_eq!(fs::read_to_string(backup_dir.join("stats.backup.1.json")).unwrap(), "start 5");
+        assert_eq!(fs::read_to_string(backup_dir.join("stats.backup.3.json")).unwrap(), "start 3");
+
+        // Lowering the number drops the surplus slots
+        rotate_backups(&path, &backup_dir, 2).unwrap();
+        assert_eq!(backups(&backup_dir), ["stats.backup.1.json", "stats.backup.2.json"]);
+        // Turning them off keeps those taken so far
+        assert_eq!(rotate_backups(&path, &backup_dir, 0).unwrap(), None);
+        assert_eq!(backups(&backup_dir), ["stats.backup.1.json", "stats.backup.2.json"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,220 @@
+use chrono::Local;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{sibling_path, StatsStore, StoreResult, StoredEvent};
+use crate::stats::migrate::{self, LoadError};
+use crate::stats::Stats;
+
+/// Stores the statistics as a single pretty-printed JSON file
+pub struct JsonStore {
+    path: PathBuf,
+    /// Set when `load` found the file corrupt and moved it aside
+    load_warning: Mutex<Option<String>>,
+}
+
+impl JsonStore {
+    pub fn new(path: PathBuf) -> Self {
+        // Ensure directory exists
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        Self { path, load_warning: Mutex::new(None) }
+    }
+    
+    fn read(path: &Path) -> StoreResult<Stats> {
+        let content = fs::read_to_string(path)?;
+        Ok(migrate::stats_from_json(&content)?)
+    }
+    
+    /// Rename the unreadable stats file to `stats.corrupt.<timestamp>.json`,
+    /// so saving fresh stats cannot overwrite what may still be recovered
+    fn set_aside_corrupt(&self) -> io::Result<PathBuf> {
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+        let target = self.path.with_file_name(format!("{}.corrupt.{}.json", stem, timestamp));
+        fs::rename(&self.path, &target)?;
+        Ok(target)
+    }
+}
+
+/// Whether a load error means the file content is damaged, rather than
+/// missing, unreadable or from a newer build
+fn is_corrupt(error: &(dyn std::error::Error + 'static)) -> bool {
+    match error.downcast_ref::<io::Error>() {
+        Some(e) => e.kind() == io::ErrorKind::InvalidData,
+        None => matches!(error.downcast_ref::<LoadError>(), Some(LoadError::Invalid(_))),
+    }
+}
+
+impl StatsStore for JsonStore {
+    /// Load the stats file, falling back to the backup of the last good file.
+    /// A file from a newer build is reported as is, never replaced by the backup.
+    /// A corrupt file is moved aside first.
+    fn load(&self) -> StoreResult<Stats> {
+        Self::read(&self.path).or_else(|e| {
+            if migrate::is_future_version(e.as_ref()) {
+                return Err(e);
+            }
+            let backup = sibling_path(&self.path, "bak");
+            if !self.path.exists() {
+                return Self::read(&backup);
+            }
+            log::warn!("Failed to load {}: {}, trying {}", self.path.display(), e, backup.display());
+            if !is_corrupt(e.as_ref()) {
+                return Self::read(&backup);
+            }
+            
+            let moved = self.set_aside_corrupt()?;
+            let restored = Self::read(&backup);
+            let warning = format!(
+                "{} was unreadable ({}) and was moved to {}; {}",
+                self.path.display(),
+                e,
+                moved.display(),
+                if restored.is_ok() { "restored the previous save" } else { "starting with empty stats" },
+            );
+            log::error!("{}", warning);
+            if let Ok(mut lock) = self.load_warning.lock() {
+                *lock = Some(warning);
+            }
+            restored
+        })
+    }
+    
+    /// The data is written to a temp file that is then renamed over the target,
+    /// so a crash mid-write never leaves a truncated `stats.json`. The previous
+    /// file is kept as `stats.json.bak`.
+    fn save(&self, stats: &Stats) -> StoreResult<()> {
+        let json = serde_json::to_string_pretty(stats)?;
+        
+        let tmp_path = sibling_path(&self.path, "tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(json.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        
+        if self.path.exists() {
+            fs::copy(&self.path, sibling_path(&self.path, "bak"))?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+    
+    fn append_events(&self, _events: &[StoredEvent]) -> StoreResult<()> {
+        Ok(())
+    }
+    
+    fn path(&self) -> &Path {
+        &self.path
+    }
+    
+    fn take_load_warning(&self) -> Option<String> {
+        self.load_warning.lock().ok()?.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::DailyStats;
+
+    fn test_store(name: &str) -> JsonStore {
+        let path = std::env::temp_dir()
+            .join(format!("rust-finger-json-{}-{}", name, std::process::id()))
+            .join("stats.json");
+        JsonStore::new(path)
+    }
+
+    #[test]
+    fn round_trip() {
+        let store = test_store("round_trip");
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 3);
+        stats.hourly_click_counts.insert(9, 4);
+        stats.daily_stats.insert("2024-05-01".to_string(), DailyStats {
+            total_keys: 3,
+            total_clicks: 4,
+            total_distance: 12.5,
+            peak_wpm: 40.0,
+            ..Default::default()
+        });
+        store.save(&stats).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.key_counts, stats.key_counts);
+        assert_eq!(loaded.hourly_click_counts, stats.hourly_click_counts);
+        assert_eq!(loaded.daily_stats["2024-05-01"].total_distance, 12.5);
+    }
+
+    #[test]
+    fn truncated_file_recovers_from_backup() {
+        let store = test_store("truncated_recovery");
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 1);
+        store.save(&stats).unwrap();
+        stats.key_counts.insert("B".to_string(), 1);
+        store.save(&stats).unwrap();
+
+        // Simulate a write cut off halfway through
+        let content = fs::read_to_string(&store.path).unwrap();
+        fs::write(&store.path, &content[..content.len() / 2]).unwrap();
+
+        let restored = store.load().unwrap();
+        assert_eq!(restored.key_counts.get("A"), Some(&1));
+        assert_eq!(restored.key_counts.get("B"), None);
+    }
+
+    /// Files in the store's directory whose name contains `.corrupt.`
+    fn corrupt_files(store: &JsonStore) -> Vec<PathBuf> {
+        fs::read_dir(store.path.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.to_string_lossy().contains(".corrupt."))
+            .collect()
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside_before_restoring_the_backup() {
+        let store = test_store("corrupt_with_backup");
+        for path in corrupt_files(&store) {
+            fs::remove_file(path).unwrap();
+        }
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 1);
+        store.save(&stats).unwrap();
+        store.save(&stats).unwrap();
+        fs::write(&store.path, b"\xff\xfe garbage").unwrap();
+
+        let restored = store.load().unwrap();
+        assert_eq!(restored.key_counts.get("A"), Some(&1));
+        let moved = corrupt_files(&store);
+        assert_eq!(moved.len(), 1);
+        assert_eq!(fs::read(&moved[0]).unwrap(), b"\xff\xfe garbage");
+        assert!(store.take_load_warning().unwrap().contains("restored the previous save"));
+        assert!(store.take_load_warning().is_none());
+    }
+
+    #[test]
+    fn save_leaves_no_temp_file() {
+        let store = test_store("no_temp_file");
+        store.save(&Stats::new()).unwrap();
+        assert!(store.path.exists());
+        assert!(!sibling_path(&store.path, "tmp").exists());
+    }
+
+    #[test]
+    fn future_version_does_not_fall_back_to_backup() {
+        let store = test_store("future_version");
+        store.save(&Stats::new()).unwrap();
+        store.save(&Stats::new()).unwrap();
+        let future = format!(r#"{{"schema_version": {}}}"#, crate::stats::SCHEMA_VERSION + 1);
+        fs::write(&store.path, future).unwrap();
+
+        let error = store.load().unwrap_err();
+        assert!(matches!(error.downcast_ref::<LoadError>(), Some(LoadError::FutureVersion { .. })));
+    }
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::{StatsStore, StoreResult, StoredEvent};
+use crate::stats::Stats;
+
+/// Keeps the last saved statistics in memory, never touching the disk
+#[derive(Default)]
+pub struct MemoryStore {
+    saved: Mutex<Option<Stats>>,
+}
+
+impl StatsStore for MemoryStore {
+    fn load(&self) -> StoreResult<Stats> {
+        let saved = self.saved.lock().map_err(|e| e.to_string())?;
+        Ok(saved.clone().unwrap_or_default())
+    }
+    
+    fn save(&self, stats: &Stats) -> StoreResult<()> {
+        *self.saved.lock().map_err(|e| e.to_string())? = Some(stats.clone());
+        Ok(())
+    }
+    
+    fn append_events(&self, _events: &[StoredEvent]) -> StoreResult<()> {
+        Ok(())
+    }
+    
+    fn path(&self) -> &Path {
+        Path::new(":memory:")
+    }
+}
@@ -0,0 +1,421 @@
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{JsonStore, StatsStore, StoreResult, StoredEvent};
+use crate::stats::migrate;
+use crate::stats::{DailyStats, Stats};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS state (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        json TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS daily_totals (
+        date TEXT PRIMARY KEY,
+        total_keys INTEGER NOT NULL,
+        total_clicks INTEGER NOT NULL,
+        total_distance REAL NOT NULL,
+        peak_wpm REAL NOT NULL,
+        backspaces INTEGER NOT NULL DEFAULT 0,
+        peak_apm REAL NOT NULL DEFAULT 0,
+        drag_count INTEGER NOT NULL DEFAULT 0,
+        drag_distance REAL NOT NULL DEFAULT 0,
+        active_seconds REAL NOT NULL DEFAULT 0
+    );
+    CREATE TABLE IF NOT EXISTS daily_hourly (
+        date TEXT NOT NULL,
+        hour INTEGER NOT NULL,
+        keys INTEGER NOT NULL,
+        clicks INTEGER NOT NULL,
+        PRIMARY KEY (date, hour)
+    );
+    CREATE TABLE IF NOT EXISTS key_counts (
+        key TEXT PRIMARY KEY,
+        count INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS hourly_buckets (
+        hour INTEGER PRIMARY KEY,
+        keys INTEGER NOT NULL,
+        clicks INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        time TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        name TEXT NOT NULL
+    );
+";
+
+/// `Stats` fields kept in their own tables rather than in the `state` blob
+const TABLE_FIELDS: &[&str] = &["daily_stats", "key_counts", "hourly_key_counts", "hourly_click_counts"];
+
+/// Stores the statistics in a SQLite database.
+///
+/// Daily totals (with their hourly split), per-key counts and hourly buckets live in queryable tables,
+/// the remaining fields are kept as JSON in the single-row `state` table.
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: PathBuf) -> StoreResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&path)?;
+        conn.execute_batch(SCHEMA)?;
+        add_column_if_missing(&conn, "daily_totals", "backspaces", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "daily_totals", "peak_apm", "REAL NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "daily_totals", "drag_count", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "daily_totals", "drag_distance", "REAL NOT NULL DEFAULT 0")?;
+        add_column_if_missing(&conn, "daily_totals", "active_seconds", "REAL NOT NULL DEFAULT 0")?;
+        Ok(Self { path, conn: Mutex::new(conn) })
+    }
+    
+    /// Import an existing JSON store into a database that holds no stats yet
+    pub fn import_if_empty(&self, json: &JsonStore) -> StoreResult<()> {
+        if self.has_state()? {
+            return Ok(());
+        }
+        match json.load() {
+            Ok(stats) => {
+                self.save(&stats)?;
+                log::info!("Imported existing JSON stats into {}", self.path.display());
+            }
+            Err(e) => log::debug!("No JSON stats to import: {}", e),
+        }
+        Ok(())
+    }
+    
+    fn has_state(&self) -> StoreResult<bool> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM state", [], |row| row.get(0))?;
+        Ok(count > 0)
+    }
+}
+
+impl StatsStore for SqliteStore {
+    fn load(&self) -> StoreResult<Stats> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        
+        let json: Option<String> = conn
+            .query_row("SELECT json FROM state WHERE id = 1", [], |row| row.get(0))
+            .optional()?;
+        let mut stats: Stats = match json {
+            Some(json) => migrate::stats_from_json(&json)?,
+            None => Stats::new(),
+        };
+        
+        let mut stmt = conn.prepare(
+            "SELECT date, total_keys, total_clicks, total_distance, peak_wpm, backspaces, peak_apm, drag_count, drag_distance,
+                    active_seconds
+             FROM daily_totals",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, DailyStats {
+                total_keys: row.get::<_, i64>(1)? as u64,
+                total_clicks: row.get::<_, i64>(2)? as u64,
+                total_distance: row.get(3)?,
+                peak_wpm: row.get(4)?,
+                backspaces: row.get::<_, i64>(5)? as u64,
+                peak_apm: row.get(6)?,
+                drag_count: row.get::<_, i64>(7)? as u64,
+                drag_distance: row.get(8)?,
+                active_seconds: row.get(9)?,
+                ..Default::default()
+            }))
+        })?;
+        for row in rows {
+            let (date, day) = row?;
+            stats.daily_stats.insert(date, day);
+        }
+        
+        let mut stmt = conn.prepare("SELECT date, hour, keys, clicks FROM daily_hourly")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (date, hour, keys, clicks) = row?;
+            let day = stats.daily_stats.entry(date).or_default();
+            if keys > 0 {
+                day.hourly_keys.insert(hour as u8, keys as u64);
+            }
+            if clicks > 0 {
+                day.hourly_clicks.insert(hour as u8, clicks as u64);
+            }
+        }
+        
+        let mut stmt = conn.prepare("SELECT key, count FROM key_counts")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (key, count) = row?;
+            stats.key_counts.insert(key, count as u64);
+        }
+        
+        let mut stmt = conn.prepare("SELECT hour, keys, clicks FROM hourly_buckets")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        for row in rows {
+            let (hour, keys, clicks) = row?;
+            if keys > 0 {
+                stats.hourly_key_counts.insert(hour as u8, keys as u64);
+            }
+            if clicks > 0 {
+                stats.hourly_click_counts.insert(hour as u8, clicks as u64);
+            }
+        }
+        
+        Ok(stats)
+    }
+    
+    fn save(&self, stats: &Stats) -> StoreResult<()> {
+        let mut state = serde_json::to_value(stats)?;
+        if let Some(fields) = state.as_object_mut() {
+            for field in TABLE_FIELDS {
+                fields.remove(*field);
+            }
+        }
+        
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction()?;
+        
+        tx.execute(
+            "INSERT OR REPLACE INTO state (id, json) VALUES (1, ?1)",
+            params![state.to_string()],
+        )?;
+        tx.execute_batch(
+            "DELETE FROM daily_totals; DELETE FROM daily_hourly; DELETE FROM key_counts; DELETE FROM hourly_buckets;",
+        )?;
+        
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO daily_totals (date, total_keys, total_clicks, total_distance, peak_wpm, backspaces, peak_apm,
+                                           drag_count, drag_distance, active_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            )?;
+            for (date, day) in &stats.daily_stats {
+                stmt.execute(params![
+                    date,
+                    day.total_keys as i64,
+                    day.total_clicks as i64,
+                    day.total_distance,
+                    day.peak_wpm,
+                    day.backspaces as i64,
+                    day.peak_apm,
+                    day.drag_count as i64,
+                    day.drag_distance,
+                    day.active_seconds,
+                ])?;
+            }
+            
+            let mut stmt = tx.prepare(
+                "INSERT INTO daily_hourly (date, hour, keys, clicks) VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for (date, day) in &stats.daily_stats {
+                for hour in 0..24u8 {
+                    let keys = day.hourly_keys.get(&hour).copied().unwrap_or(0);
+                    let clicks = day.hourly_clicks.get(&hour).copied().unwrap_or(0);
+                    if keys > 0 || clicks > 0 {
+                        stmt.execute(params![date, hour, keys as i64, clicks as i64])?;
+                    }
+                }
+            }
+            
+            let mut stmt = tx.prepare("INSERT INTO key_counts (key, count) VALUES (?1, ?2)")?;
+            for (key, count) in &stats.key_counts {
+                stmt.execute(params![key, *count as i64])?;
+            }
+            
+            let mut stmt = tx.prepare(
+                "INSERT INTO hourly_buckets (hour, keys, clicks) VALUES (?1, ?2, ?3)",
+            )?;
+            for hour in 0..24u8 {
+                let keys = stats.hourly_key_counts.get(&hour).copied().unwrap_or(0);
+                let clicks = stats.hourly_click_counts.get(&hour).copied().unwrap_or(0);
+                stmt.execute(params![hour, keys as i64, clicks as i64])?;
+            }
+        }
+        
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn append_events(&self, events: &[StoredEvent]) -> StoreResult<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        
+        let mut conn = self.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO events (time, kind, name) VALUES (?1, ?2, ?3)")?;
+            for event in events {
+                stmt.execute(params![event.time.to_rfc3339(), event.kind.as_str(), event.name])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn keeps_events(&self) -> bool {
+        true
+    }
+    
+    fn prune_events(&self, cutoff: DateTime<Local>) -> StoreResult<usize> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        // Events are appended in time order, so everything before the first
+        // one to keep goes; finding it only reads the rows that are dropped
+        let pruned = conn.execute(
+            "DELETE FROM events WHERE id < COALESCE(
+                (SELECT id FROM events WHERE julianday(time) >= julianday(?1) ORDER BY id LIMIT 1),
+                (SELECT MAX(id) + 1 FROM events)
+            )",
+            params![cutoff.to_rfc3339()],
+        )?;
+        Ok(pruned)
+    }
+    
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Add a column introduced after the table was first created
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(Result::ok)
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::store::EventKind;
+    use std::collections::HashMap;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-finger-sqlite-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn sample_stats() -> Stats {
+        let mut stats = Stats::new();
+        stats.key_counts.insert("A".to_string(), 3);
+        stats.key_counts.insert("Space".to_string(), 7);
+        stats.mouse_clicks.insert("Left".to_string(), 4);
+        stats.hourly_key_counts.insert(9, 10);
+        stats.hourly_click_counts.insert(14, 4);
+        stats.mouse_distance = 1234.5;
+        stats.daily_stats.insert("2024-05-01".to_string(), DailyStats {
+            total_keys: 10,
+            total_clicks: 4,
+            total_distance: 1234.5,
+            peak_wpm: 55.0,
+            backspaces: 2,
+            peak_apm: 140.0,
+            drag_count: 3,
+            drag_distance: 210.0,
+            active_seconds: 5400.0,
+            hourly_keys: HashMap::from([(9, 10)]),
+            hourly_clicks: HashMap::from([(14, 4)]),
+        });
+        stats
+    }
+
+    #[test]
+    fn round_trip() {
+        let store = SqliteStore::open(test_dir("round_trip").join("stats.db")).unwrap();
+        let stats = sample_stats();
+        store.save(&stats).unwrap();
+        // Saving again overwrites rather than duplicating rows
+        store.save(&stats).unwrap();
+
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.key_counts, stats.key_counts);
+        assert_eq!(loaded.mouse_clicks, stats.mouse_clicks);
+        assert_eq!(loaded.hourly_key_counts, stats.hourly_key_counts);
+        assert_eq!(loaded.hourly_click_counts, stats.hourly_click_counts);
+        assert_eq!(loaded.mouse_distance, 1234.5);
+        assert_eq!(loaded.daily_stats["2024-05-01"].peak_wpm, 55.0);
+        assert_eq!(loaded.daily_stats["2024-05-01"].backspaces, 2);
+        assert_eq!(loaded.daily_stats["2024-05-01"].peak_apm, 140.0);
+        assert_eq!(loaded.daily_stats["2024-05-01"].drag_count, 3);
+        assert_eq!(loaded.daily_stats["2024-05-01"].drag_distance, 210.0);
+        assert_eq!(loaded.daily_stats["2024-05-01"].active_seconds, 5400.0);
+        assert_eq!(loaded.daily_stats["2024-05-01"].hourly_keys, stats.daily_stats["2024-05-01"].hourly_keys);
+        assert_eq!(loaded.daily_stats["2024-05-01"].hourly_clicks, stats.daily_stats["2024-05-01"].hourly_clicks);
+    }
+
+    #[test]
+    fn imports_json_on_first_open() {
+        let dir = test_dir("import");
+        let json = JsonStore::new(dir.join("stats.json"));
+        json.save(&sample_stats()).unwrap();
+
+        let store = SqliteStore::open(dir.join("stats.db")).unwrap();
+        store.import_if_empty(&json).unwrap();
+        assert_eq!(store.load().unwrap().key_counts.get("Space"), Some(&7));
+
+        // Later JSON changes are not re-imported over the database
+        json.save(&Stats::new()).unwrap();
+        store.import_if_empty(&json).unwrap();
+        assert_eq!(store.load().unwrap().key_counts.get("Space"), Some(&7));
+    }
+
+    #[test]
+    fn adds_columns_to_older_databases() {
+        let path = test_dir("upgrade").join("stats.db");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        Connection::open(&path).unwrap().execute_batch(
+            "CREATE TABLE daily_totals (date TEXT PRIMARY KEY, total_keys INTEGER NOT NULL,
+             total_clicks INTEGER NOT NULL, total_distance REAL NOT NULL, peak_wpm REAL NOT NULL);
+             INSERT INTO daily_totals VALUES ('2024-05-01', 10, 4, 1.5, 30.0);",
+        ).unwrap();
+
+        let store = SqliteStore::open(path).unwrap();
+        let loaded = store.load().unwrap();
+        assert_eq!(loaded.daily_stats["2024-05-01"].total_keys, 10);
+        assert_eq!(loaded.daily_stats["2024-05-01"].backspaces, 0);
+        assert_eq!(loaded.daily_stats["2024-05-01"].drag_count, 0);
+    }
+
+    #[test]
+    fn appends_events() {
+        let store = SqliteStore::open(test_dir("events").join("stats.db")).unwrap();
+        let event = StoredEvent { time: Local::now(), kind: EventKind::Key, name: "A".to_string() };
+        store.append_events(&[event.clone(), event]).unwrap();
+
+        let conn = store.conn.lock().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn prunes_events_before_the_cutoff() {
+        let store = SqliteStore::open(test_dir("prune-events").join("stats.db")).unwrap();
+        let now = Local::now();
+        let event = |days_ago: i64| StoredEvent { time: now - chrono::Duration::days(days_ago), kind: EventKind::Key, name: "A".to_string() };
+        store.append_events(&[event(10), event(5), event(1), event(0)]).unwrap();
+
+        assert_eq!(store.prune_events(now - chrono::Duration::days(3)).unwrap(), 2);
+        assert_eq!(store.prune_events(now - chrono::Duration::days(3)).unwrap(), 0);
+        // With nothing recent left, every event goes
+        assert_eq!(store.prune_events(now + chrono::Duration::days(1)).unwrap(), 2);
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::{Local, NaiveDate, Timelike};
+use std::thread;
+use std::time::Duration;
+
+use crate::notifications;
+use crate::stats::StatsManager;
+
+/// How often the scheduler checks the clock
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawn a background thread that produces the daily summary once per day
+/// during the given local hour
+pub fn spawn_daily_summary(stats: StatsManager, hour: u32) {
+    thread::spawn(move || {
+        // Remember the last date we fired for so a restart-free day only fires once
+        let mut last_fired: Option<NaiveDate> = None;
+        
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+            // Finish the day at midnight even if no input arrives
+            stats.check_rollover();
+            
+            let now = Local::now();
+            let today = now.date_naive();
+            if now.hour() != hour || last_fired == Some(today) {
+                continue;
+            }
+            last_fired = Some(today);
+            
+            let text = stats.snapshot().daily_summary_text();
+            log::info!("{}", text);
+            notifications::show(&text);
+        }
+    });
+}
@@ -1,9 +1,17 @@
 use gpui::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::Config;
 use crate::stats::StatsManager;
-use super::dashboard::Dashboard;
+use super::dashboard::{Dashboard, COMPACT_SIZE};
 
-/// Run the GPUI application
-pub fn run(stats_manager: StatsManager) {
+/// How often to check whether another instance asked for the window
+const SHOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run the GPUI application, raising the window whenever `show_requests` is
+/// set. `file_config` is the config file alone, which the settings view saves to.
+pub fn run(stats_manager: StatsManager, config: Config, file_config: Option<Config>, show_requests: Arc<AtomicBool>) {
     Application::new().run(move |cx: &mut App| {
         // Set up window options
         let window_options = WindowOptions {
@@ -23,13 +31,25 @@ pub fn run(stats_manager: StatsManager) {
             is_movable: true,
             app_id: Some("finger-monitor".to_string()),
             window_background: WindowBackgroundAppearance::Opaque,
-            window_min_size: Some(size(px(800.0), px(600.0))),
+            // Small enough for the compact view; the dashboard raises it to
+            // the full minimum outside the compact view where it can
+            window_min_size: Some(COMPACT_SIZE),
             ..Default::default()
         };
         
         // Open main window
-        cx.open_window(window_options, |_window, cx| {
-            cx.new(|cx| Dashboard::new(cx, stats_manager.clone()))
+        let window = cx.open_window(window_options, |window, cx| {
+            cx.new(|cx| Dashboard::new(window, cx, stats_manager.clone(), &config, file_config.clone()))
         }).expect("Failed to open window");
+        
+        // Raise it when another instance is started
+        cx.spawn(async move |cx| loop {
+            cx.background_executor().timer(SHOW_POLL_INTERVAL).await;
+            if show_requests.swap(false, Ordering::Relaxed)
+                && window.update(cx, |_, window, _| window.activate_window()).is_err()
+            {
+                break;
+            }
+        }).detach();
     });
 }
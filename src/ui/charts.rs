@@ -1,37 +1,197 @@
+use chrono::{Datelike, Duration as Days, NaiveDate};
 use gpui::*;
 use gpui::prelude::FluentBuilder;
 use std::collections::HashMap;
 
+use super::heat_scale::HeatColorScheme;
+use super::theme::Theme;
+
+/// Which series the activity chart shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartSeries {
+    #[default]
+    Keys,
+    Clicks,
+    /// Keys and clicks side by side, against a shared scale
+    Combined,
+}
+
+impl ChartSeries {
+    pub const ALL: [ChartSeries; 3] = [ChartSeries::Keys, ChartSeries::Clicks, ChartSeries::Combined];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChartSeries::Keys => "Keys",
+            ChartSeries::Clicks => "Clicks",
+            ChartSeries::Combined => "Both",
+        }
+    }
+}
+
 /// Hourly activity chart component
 pub struct HourlyChart {
     hourly_counts: HashMap<u8, u64>,
+    /// Bar color of the main series, the theme's blue unless set
+    color: Option<Rgba>,
+    /// Second series drawn next to the first, e.g. clicks next to keys
+    overlay: Option<(HashMap<u8, u64>, Rgba)>,
     max_count: u64,
+    /// Draw the cumulative count of the main series as a stepped line
+    running_total: bool,
+    /// Color the bars of a single series by their height instead
+    palette: Option<HeatColorScheme>,
+    theme: Theme,
 }
 
+/// Thickness of the running total line in pixels
+const RUNNING_TOTAL_LINE: f32 = 2.0;
+
 impl HourlyChart {
     pub fn new(hourly_counts: HashMap<u8, u64>) -> Self {
         let max_count = hourly_counts.values().copied().max().unwrap_or(1);
-        Self { hourly_counts, max_count }
+        Self { hourly_counts, color: None, overlay: None, max_count, running_total: false, palette: None, theme: Theme::default() }
     }
     
-    fn render_bar(&self, hour: u8) -> impl IntoElement {
-        let count = self.hourly_counts.get(&hour).copied().unwrap_or(0);
-        let height_percent = if self.max_count > 0 {
+    /// Set the bar color of the main series
+    pub fn color(mut self, color: Rgba) -> Self {
+        self.color = Some(color);
+        self
+    }
+    
+    /// Set the colors of empty bars, labels and the current hour
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+    
+    /// Color the bars by their height in `palette`, the busiest hour at its
+    /// top end; ignored when a second series is drawn
+    pub fn palette(mut self, palette: HeatColorScheme) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+    
+    /// Draw the running total of the main series over the bars, scaled to
+    /// the day's total rather than the busiest hour
+    pub fn running_total(mut self, enabled: bool) -> Self {
+        self.running_total = enabled;
+        self
+    }
+    
+    /// Draw a second series next to the first; both share one scale
+    pub fn overlay(mut self, hourly_counts: HashMap<u8, u64>, color: Rgba) -> Self {
+        let overlay_max = hourly_counts.values().copied().max().unwrap_or(0);
+        self.max_count = self.max_count.max(overlay_max);
+        self.overlay = Some((hourly_counts, color));
+        self
+    }
+    
+    fn height_fraction(&self, count: u64) -> f32 {
+        let percent = if self.max_count > 0 {
             (count as f32 / self.max_count as f32 * 100.0).max(2.0)
         } else {
             2.0
         };
+        percent / 100.0
+    }
+    
+    fn render_series_bar(&self, count: u64, color: Rgba, width: Pixels, highlight: bool) -> Div {
+        div()
+            .w(width)
+            .rounded_t_sm()
+            .bg(if count > 0 { color } else { rgb(self.theme.text_faint) })
+            .h(relative(self.height_fraction(count)))
+            .when(highlight, |this: Div| {
+                this.shadow_md()
+            })
+    }
+    
+    /// Cumulative count of the main series at the end of each hour
+    fn cumulative_counts(&self) -> [u64; 24] {
+        let mut total = 0;
+        std::array::from_fn(|hour| {
+            total += self.hourly_counts.get(&(hour as u8)).copied().unwrap_or(0);
+            total
+        })
+    }
+    
+    /// Step of the running total line over one hour: a level at the running
+    /// total, and a riser on the left from the previous hour's level. Only
+    /// drawn from the first to the last hour with activity, so quiet hours
+    /// before and after do not add flat lines along the axis.
+    fn render_running_total(&self, hour: u8, cumulative: &[u64; 24]) -> Option<Div> {
+        let hour = hour as usize;
+        let total = cumulative[23];
+        let last_active = (0..24u8).rfind(|h| self.hourly_counts.get(h).is_some_and(|&count| count > 0))?;
+        if cumulative[hour] == 0 || hour > last_active as usize {
+            return None;
+        }
+        let level = cumulative[hour] as f32 / total as f32;
+        let previous = if hour > 0 { cumulative[hour - 1] as f32 / total as f32 } else { 0.0 };
+        let color = rgb(self.theme.green);
+        // The columns are 4px apart; reach halfway into the gaps so the steps join up
+        Some(
+            div()
+                .absolute()
+                .left(px(-2.))
+                .right(px(-2.))
+                .top_0()
+                .bottom_0()
+                .child(
+                    div()
+                        .absolute()
+                        .left_0()
+                        .right_0()
+                        .bottom(relative(level))
+                        .h(px(RUNNING_TOTAL_LINE))
+                        .bg(color)
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .left_0()
+                        .w(px(RUNNING_TOTAL_LINE))
+                        .bottom(relative(previous))
+                        .h(relative(level - previous))
+                        .bg(color)
+                )
+        )
+    }
+    
+    fn render_bar(&self, hour: u8, cumulative: &[u64; 24]) -> impl IntoElement {
+        let count = self.hourly_counts.get(&hour).copied().unwrap_or(0);
         
         // Current hour highlight
         let current_hour = chrono::Local::now().hour() as u8;
         let is_current = hour == current_hour;
         
-        let bar_color = if is_current {
-            rgb(0xff9e64) // Orange for current hour
-        } else if count > 0 {
-            rgb(0x7aa2f7) // Blue for activity
-        } else {
-            rgb(0x414868) // Gray for no activity
+        let main_color = self.color.unwrap_or(rgb(self.theme.blue));
+        let bars = match &self.overlay {
+            // Keep the series colors apart; the label marks the current hour
+            Some((overlay_counts, overlay_color)) => {
+                let overlay_count = overlay_counts.get(&hour).copied().unwrap_or(0);
+                div()
+                    .h_full()
+                    .flex()
+                    .items_end()
+                    .gap_px()
+                    .child(self.render_series_bar(count, main_color, px(5.), false))
+                    .child(self.render_series_bar(overlay_count, *overlay_color, px(5.), false))
+            }
+            None => {
+                let color = if let Some(palette) = self.palette {
+                    palette.face_color(count as f32 / self.max_count.max(1) as f32)
+                } else if is_current {
+                    rgb(self.theme.orange) // Orange for current hour
+                } else {
+                    main_color
+                };
+                div()
+                    .h_full()
+                    .flex()
+                    .items_end()
+                    .child(self.render_series_bar(count, color, px(12.), is_current))
+            }
         };
         
         div()
@@ -43,21 +203,20 @@ impl HourlyChart {
             .justify_end()
             .gap_1()
             .child(
-                // Bar
                 div()
-                    .w_3()
-                    .rounded_t_sm()
-                    .bg(bar_color)
-                    .h(relative(height_percent / 100.0))
-                    .when(is_current, |this: Div| {
-                        this.shadow_md()
-                    })
+                    .relative()
+                    .flex_1()
+                    .w_full()
+                    .flex()
+                    .justify_center()
+                    .child(bars)
+                    .when(self.running_total, |this| this.children(self.render_running_total(hour, cumulative)))
             )
             .child(
                 // Hour label
                 div()
                     .text_xs()
-                    .text_color(if is_current { rgb(0xff9e64) } else { rgb(0x565f89) })
+                    .text_color(if is_current { rgb(self.theme.orange) } else { rgb(self.theme.text_muted) })
                     .child(format!("{}", hour))
             )
     }
@@ -67,12 +226,196 @@ impl IntoElement for HourlyChart {
     type Element = Div;
     
     fn into_element(self) -> Self::Element {
+        let cumulative = self.cumulative_counts();
         div()
             .flex_1()
             .flex()
             .gap_1()
             .pb_4()
-            .children((0..24).map(|hour| self.render_bar(hour)))
+            .children((0..24).map(|hour| self.render_bar(hour, &cumulative)))
+    }
+}
+
+/// Tiny bar chart of recent values, drawn without axes or labels
+pub struct Sparkline {
+    values: Vec<f64>,
+    color: Rgba,
+    theme: Theme,
+}
+
+impl Sparkline {
+    pub fn new(values: Vec<f64>, color: Rgba) -> Self {
+        Self { values, color, theme: Theme::default() }
+    }
+    
+    /// Set the color of empty bars
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+}
+
+impl IntoElement for Sparkline {
+    type Element = Div;
+    
+    fn into_element(self) -> Self::Element {
+        let max = self.values.iter().copied().fold(0.0, f64::max);
+        div()
+            .h(px(20.))
+            .w_full()
+            .flex()
+            .items_end()
+            .gap_px()
+            .children(self.values.iter().map(|value| {
+                let fraction = if max > 0.0 { (value / max) as f32 } else { 0.0 };
+                div()
+                    .flex_1()
+                    .h(relative(fraction.max(0.05)))
+                    .bg(if *value > 0.0 { self.color } else { rgb(self.theme.text_faint) })
+            }))
+    }
+}
+
+/// Weeks shown by the calendar heatmap, enough to cover a full year
+const CALENDAR_WEEKS: usize = 53;
+
+/// Side length of one day square in pixels
+const CALENDAR_CELL: f32 = 10.0;
+
+/// Year-at-a-glance grid of daily key counts, one column per week
+/// (Sunday at the top) ending with the current week
+pub struct CalendarHeatmap {
+    counts: HashMap<NaiveDate, u64>,
+    today: NaiveDate,
+    max_count: u64,
+    theme: Theme,
+}
+
+impl CalendarHeatmap {
+    pub fn new(counts: HashMap<NaiveDate, u64>, today: NaiveDate) -> Self {
+        let start = today - Days::days(365);
+        let max_count = counts
+            .iter()
+            .filter(|(date, _)| **date > start && **date <= today)
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0);
+        Self { counts, today, max_count, theme: Theme::default() }
+    }
+    
+    /// Set the square colors, labels and tooltips
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+    
+    /// Sunday of the first column
+    fn first_day(&self) -> NaiveDate {
+        let weekday = self.today.weekday().num_days_from_sunday() as i64;
+        self.today - Days::days((CALENDAR_WEEKS as i64 - 1) * 7 + weekday)
+    }
+    
+    /// Color step 0-4; 0 only for days without keys
+    fn level(&self, count: u64) -> usize {
+        if count == 0 || self.max_count == 0 {
+            return 0;
+        }
+        let fraction = count as f64 / self.max_count as f64;
+        ((fraction * 4.0).ceil() as usize).clamp(1, 4)
+    }
+    
+    fn render_month_labels(&self) -> Div {
+        let first = self.first_day();
+        div()
+            .flex()
+            .gap(px(2.))
+            .children((0..CALENDAR_WEEKS).map(|week| {
+                let sunday = first + Days::days(week as i64 * 7);
+                // Label the week in which a month starts
+                let label = (0..7)
+                    .map(|d| sunday + Days::days(d))
+                    .find(|day| day.day() == 1)
+                    .map(|day| day.format("%b").to_string());
+                div()
+                    .w(px(CALENDAR_CELL))
+                    .h(px(14.))
+                    .text_xs()
+                    .text_color(rgb(self.theme.text_muted))
+                    .when_some(label, |this, label| this.child(label))
+            }))
+    }
+    
+    fn render_day(&self, day: NaiveDate) -> AnyElement {
+        if day > self.today {
+            return div().size(px(CALENDAR_CELL)).into_any_element();
+        }
+        let count = self.counts.get(&day).copied().unwrap_or(0);
+        let text = format!("{}: {} keys", day.format("%a %Y-%m-%d"), count);
+        let theme = self.theme;
+        div()
+            .id(SharedString::from(format!("calendar-{}", day)))
+            .size(px(CALENDAR_CELL))
+            .rounded_sm()
+            .border_1()
+            .border_color(rgba(0x00000000))
+            .bg(rgb(self.theme.calendar_levels[self.level(count)]))
+            .hover(|s| s.border_color(rgb(self.theme.text_bright)))
+            .tooltip(move |_window, cx| cx.new(|_| TextTooltip(text.clone().into(), theme)).into())
+            .into_any_element()
+    }
+}
+
+impl IntoElement for CalendarHeatmap {
+    type Element = Div;
+    
+    fn into_element(self) -> Self::Element {
+        let first = self.first_day();
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(self.render_month_labels())
+            .child(
+                div()
+                    .flex()
+                    .gap(px(2.))
+                    .children((0..CALENDAR_WEEKS).map(|week| {
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap(px(2.))
+                            .children((0..7).map(|d| self.render_day(first + Days::days(week as i64 * 7 + d))))
+                    }))
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .text_xs()
+                    .text_color(rgb(self.theme.text_muted))
+                    .child("Less")
+                    .children(self.theme.calendar_levels.iter().map(|color| div().size(px(CALENDAR_CELL)).rounded_sm().bg(rgb(*color))))
+                    .child("More")
+            )
+    }
+}
+
+/// Small text popup shown while hovering an element
+struct TextTooltip(SharedString, Theme);
+
+impl Render for TextTooltip {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .bg(rgb(self.1.raised))
+            .border_1()
+            .border_color(rgb(self.1.text_faint))
+            .text_xs()
+            .text_color(rgb(self.1.text_bright))
+            .child(self.0.clone())
     }
 }
 
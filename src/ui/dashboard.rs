@@ -1,8 +1,168 @@
 use gpui::*;
-use crate::stats::{Stats, StatsManager};
-use super::keyboard_heatmap::KeyboardHeatmap;
-use super::charts::HourlyChart;
-use std::time::Duration;
+use gpui::prelude::FluentBuilder;
+use crate::config::{Config, ListenerBackend, UnmappedKeys};
+use crate::listener::{self, InputListener, PermissionStatus};
+use crate::platform;
+use crate::report;
+use crate::stats::{percent_change, DailyStats, DeviceUsage, PauseReason, Stats, StatsManager};
+use crate::stats::achievements::MILESTONES;
+use crate::stats::categories::KeyCategory;
+use crate::stats::sessions::SessionRecord;
+use crate::stats::bigrams;
+use crate::stats::fingers::{Finger, FingerMap};
+use crate::stats::key_aliases::KeyAliases;
+use crate::format::format_count;
+use super::heat_scale::{HeatColorScheme, HeatScale};
+use super::key_labels::KeyLabels;
+use super::keyboard_heatmap::{HeatmapDisplay, HeatmapPeriod, KeyboardHeatmap, PRESS_FLASH};
+use super::keyboard_layout::{self, KeyRow, KeyScale, KeyboardLayout, KeyboardSize, KeySlot};
+use super::mouse_heatmap::{CursorGrid, MouseHeatmap};
+use super::prefs::Prefs;
+use super::scrollbar::ScrollMetrics;
+use super::settings::{self, SettingsView};
+use super::charts::{CalendarHeatmap, ChartSeries, HourlyChart, Sparkline};
+use super::theme::{Theme, ThemeMode};
+use raw_window_handle::HasWindowHandle;
+use chrono::{Datelike, Days, Local, NaiveDate};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Width of the typing intensity meter in the menu bar
+const INTENSITY_METER_WIDTH: f32 = 60.0;
+
+/// Period of WPM history shown in the WPM card's sparkline
+const SPARKLINE_SPAN: Duration = Duration::from_secs(10 * 60);
+
+/// Time between updates while input arrives, unless configured otherwise
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shortest configurable time between updates, about one frame
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Time between updates once no input arrived for `IDLE_AFTER`, to save
+/// power while the window is left open
+const IDLE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const IDLE_AFTER: Duration = Duration::from_secs(5);
+
+/// Time between updates while the window is in the background or minimized
+const UNFOCUSED_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Refresh tasks alive, logged to confirm that a restarted task replaces the
+/// previous one
+static REFRESH_TASKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a refresh task in `REFRESH_TASKS` for as long as it runs
+struct RefreshTaskGuard;
+
+impl RefreshTaskGuard {
+    fn start() -> Self {
+        let running = REFRESH_TASKS.fetch_add(1, Ordering::Relaxed) + 1;
+        if running > 1 {
+            log::warn!("{} dashboard refresh tasks are running", running);
+        } else {
+            log::debug!("Dashboard refresh task started");
+        }
+        Self
+    }
+}
+
+impl Drop for RefreshTaskGuard {
+    fn drop(&mut self) {
+        REFRESH_TASKS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// How long the copy button shows a check mark after copying the summary
+const COPIED_FEEDBACK: Duration = Duration::from_millis(1500);
+
+/// How long the key milestone bar is highlighted after a round total is passed
+const MILESTONE_HIGHLIGHT: Duration = Duration::from_secs(4);
+
+/// Lengths offered for the Top Keys list, and the one shown until another is chosen
+const TOP_KEYS_COUNTS: &[usize] = &[10, 20, 50, 100];
+const DEFAULT_TOP_KEYS: usize = 20;
+
+/// Window size of the compact view
+pub const COMPACT_SIZE: Size<Pixels> = size(px(380.0), px(44.0));
+
+/// Horizontal space beside the keyboard heatmap: content padding, the Top
+/// Keys sidebar and the gap between them
+const HEATMAP_SIDE_SPACE: f32 = 2.0 * 16.0 + 256.0 + 16.0;
+
+/// Window size restored when leaving the compact view without a previous size
+const FULL_SIZE: Size<Pixels> = size(px(1200.0), px(800.0));
+
+/// Smallest window the full dashboard can be resized to
+const FULL_MIN_SIZE: Size<Pixels> = size(px(800.0), px(600.0));
+
+/// Full dashboard or a single row of today's numbers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ViewMode {
+    #[default]
+    Full,
+    Compact,
+}
+
+/// Convert a GPUI keystroke to the key name used in the statistics
+fn keystroke_name(keystroke: &Keystroke) -> String {
+    if keystroke.key.len() == 1 {
+        keystroke.key.to_uppercase()
+    } else {
+        // Capitalize first letter for special keys
+        let mut c = keystroke.key.chars();
+        match c.next() {
+            None => String::new(),
+            Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+        }
+    }
+}
+
+/// Scroll state of the container scrolled by `handle`, as of its last layout
+fn scroll_metrics(handle: &ScrollHandle) -> ScrollMetrics {
+    ScrollMetrics {
+        viewport: f32::from(handle.bounds().size.height),
+        max_offset: f32::from(handle.max_offset().height),
+        offset: -f32::from(handle.offset().y),
+    }
+}
+
+/// Parse a remembered setting, falling back to the default if unset or unknown
+fn remembered<T: std::str::FromStr + Default>(value: &Option<String>) -> T {
+    value.as_deref().and_then(|v| v.parse().ok()).unwrap_or_default()
+}
+
+/// Let the window shrink to the compact view only while it is shown.
+/// Returns `false` where the platform cannot change the minimum size of an
+/// open window.
+fn set_min_size(window: &Window, mode: ViewMode) -> bool {
+    let min = match mode {
+        ViewMode::Compact => COMPACT_SIZE,
+        ViewMode::Full => FULL_MIN_SIZE,
+    };
+    let applied = HasWindowHandle::window_handle(window)
+        .map(|handle| platform::set_min_size(handle.as_raw(), min.width.into(), min.height.into()))
+        .unwrap_or(false);
+    if !applied {
+        log::debug!("The minimum window size cannot be changed here");
+    }
+    applied
+}
+
+/// Hour of the day in 12-hour time, e.g. "3 PM"
+fn hour_label(hour: u8) -> String {
+    let suffix = if hour < 12 { "AM" } else { "PM" };
+    match hour % 12 {
+        0 => format!("12 {}", suffix),
+        h => format!("{} {}", h, suffix),
+    }
+}
+
+/// Number of days, e.g. "1 day" or "12 days"
+fn days_label(days: u32) -> String {
+    format!("{} {}", days, if days == 1 { "day" } else { "days" })
+}
 
 /// Main dashboard view showing all statistics
 pub struct Dashboard {
@@ -11,51 +171,416 @@ pub struct Dashboard {
     focus_handle: FocusHandle,
     main_scroll: ScrollHandle,
     top_scroll: ScrollHandle,
+    /// Scrollbar thumb being dragged: its container, the pointer height and
+    /// the scroll offset when the drag started
+    scrollbar_drag: Option<(ScrollHandle, Pixels, f32)>,
+    heat_scheme: HeatColorScheme,
+    heat_scale: HeatScale,
+    keyboard_layout: KeyboardLayout,
+    keyboard_size: KeyboardSize,
+    /// Rows of `layout.json`, if the user defined a layout
+    custom_layout: Option<Vec<Vec<KeySlot>>>,
+    heatmap_display: HeatmapDisplay,
+    heatmap_period: HeatmapPeriod,
+    /// Half-life of the recency weighting in the "Recent" heatmap
+    recent_half_life_days: f64,
+    /// Key presses a day needs to count towards the streak
+    streak_min_keys: u64,
+    top_keys_filter: KeyCategory,
+    /// Keys shown in the Top Keys list, one of `TOP_KEYS_COUNTS`
+    top_keys_count: usize,
+    /// Choices remembered in `prefs.toml`
+    prefs: Prefs,
+    /// Text typed into the search box of the Top Keys list
+    top_keys_query: String,
+    top_keys_search_focus: FocusHandle,
+    activity_series: ChartSeries,
+    /// Draw today's running total over the hourly chart
+    show_running_total: bool,
+    cursor_grid: CursorGrid,
+    show_calendar: bool,
+    /// Outcome of the last import or export, shown in the status bar
+    file_status: Option<Result<String, String>>,
+    /// Date range being picked for an export, if the picker is open
+    export_range: Option<(NaiveDate, NaiveDate)>,
+    /// Policy and backend the input listener was started with, reused on retry
+    unmapped_keys: UnmappedKeys,
+    /// Rename keys recorded under older names in the displayed stats
+    key_aliases: KeyAliases,
+    /// Names keys are shown under
+    key_labels: KeyLabels,
+    listener_backend: ListenerBackend,
+    /// Show why the listener is offline; closes once it is live again
+    listener_panel_open: bool,
+    /// The listener was seen stopped since it last ran
+    listener_stopped: bool,
+    /// The warning that input may be missed (Wayland) was closed
+    listener_warning_dismissed: bool,
+    /// Input monitoring permission, checked at startup and on retry
+    permission: PermissionStatus,
+    /// The permission screen was put aside to look at the recorded stats
+    permission_screen_dismissed: bool,
+    /// Key last pressed while the window had focus, flashed on the heatmap
+    last_press: Option<(String, Instant)>,
+    /// When the summary was last copied to the clipboard
+    copied_at: Option<Instant>,
+    /// Key milestone counted towards at the last refresh, `None` past the last
+    key_milestone: Option<u64>,
+    /// When the last round key total was passed while the dashboard was open
+    key_milestone_passed_at: Option<Instant>,
+    view_mode: ViewMode,
+    /// Window size before switching to the compact view
+    full_size: Option<Size<Pixels>>,
+    /// The platform keeps the compact minimum size, so the full dashboard
+    /// grows the window back itself when it is made smaller
+    grow_to_min_size: bool,
+    /// Keep the compact window above other windows
+    compact_on_top: bool,
+    /// Settings panel, also the source of the settings it changes
+    settings: Entity<SettingsView>,
+    settings_open: bool,
+    theme_mode: ThemeMode,
+    /// Colors of `theme_mode`
+    theme: Theme,
+    /// Refreshes the stats and redraws; restarted when the window gains
+    /// focus, which drops the previous one
+    refresh_task: Task<()>,
+    _activation: Subscription,
 }
 
 impl Dashboard {
-    pub fn new(cx: &mut Context<Self>, stats_manager: StatsManager) -> Self {
+    pub fn new(
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        stats_manager: StatsManager,
+        config: &Config,
+        file_config: Option<Config>,
+    ) -> Self {
         let stats_snapshot = stats_manager.snapshot();
+        let prefs = Prefs::load();
+        let custom_layout = keyboard_layout::load_custom_layout();
+        // The config takes precedence over the choices remembered from the dashboard
+        let keyboard_layout = match config.keyboard_layout.unwrap_or_else(|| remembered(&prefs.heatmap_layout)) {
+            KeyboardLayout::Custom if custom_layout.is_none() => KeyboardLayout::Ansi,
+            layout => layout,
+        };
+        let keyboard_size = config.keyboard_size.unwrap_or_else(|| remembered(&prefs.heatmap_size));
+        let heat_scale = remembered(&prefs.heatmap_scale);
+        let theme_mode = config.theme.unwrap_or_else(|| remembered(&prefs.theme));
+        let key_milestone = stats_snapshot.next_milestone().map(|(target, _, _)| target);
+        let top_keys_count = prefs.top_keys_count.unwrap_or(DEFAULT_TOP_KEYS);
         let focus_handle = cx.focus_handle();
+        let grow_to_min_size = !set_min_size(window, ViewMode::Full);
+        let theme = theme_mode.theme().with_accents(&config.accent_colors);
+        let settings = cx.new(|_| SettingsView::new(config.clone(), file_config, stats_manager.clone(), theme));
         Self {
             stats_manager,
             stats_snapshot,
             focus_handle,
             main_scroll: ScrollHandle::new(),
             top_scroll: ScrollHandle::new(),
+            scrollbar_drag: None,
+            heat_scheme: config.heatmap_palette,
+            heat_scale,
+            keyboard_layout,
+            keyboard_size,
+            custom_layout,
+            heatmap_display: HeatmapDisplay::default(),
+            heatmap_period: HeatmapPeriod::default(),
+            recent_half_life_days: config.recent_half_life_days,
+            streak_min_keys: config.streak_min_keys,
+            top_keys_filter: KeyCategory::default(),
+            top_keys_count,
+            prefs,
+            top_keys_query: String::new(),
+            top_keys_search_focus: cx.focus_handle(),
+            activity_series: ChartSeries::default(),
+            show_running_total: false,
+            cursor_grid: CursorGrid::default(),
+            show_calendar: true,
+            file_status: None,
+            export_range: None,
+            unmapped_keys: config.unmapped_keys,
+            key_aliases: config.key_aliases.clone(),
+            key_labels: config.key_labels.clone(),
+            listener_backend: config.backend,
+            listener_panel_open: false,
+            listener_stopped: false,
+            listener_warning_dismissed: false,
+            permission: listener::check_permissions(),
+            permission_screen_dismissed: false,
+            last_press: None,
+            copied_at: None,
+            key_milestone,
+            key_milestone_passed_at: None,
+            view_mode: ViewMode::default(),
+            full_size: None,
+            grow_to_min_size,
+            compact_on_top: config.compact_on_top,
+            settings,
+            settings_open: false,
+            theme_mode,
+            theme,
+            refresh_task: Self::spawn_refresh(window, cx),
+            // Back to the fast rate right away instead of after a slow interval
+            _activation: cx.observe_window_activation(window, |this, window, cx| {
+                this.refresh_task = Self::spawn_refresh(window, cx);
+            }),
+        }
+    }
+    
+    /// Start refreshing the stats and redrawing, at `refresh_interval`
+    fn spawn_refresh(window: &mut Window, cx: &mut Context<Self>) -> Task<()> {
+        cx.spawn_in(window, async move |this, cx| {
+            let _guard = RefreshTaskGuard::start();
+            loop {
+                let Ok(interval) = this.update_in(cx, |dashboard, window, cx| {
+                    dashboard.refresh();
+                    cx.notify();
+                    dashboard.refresh_interval(window, cx)
+                }) else {
+                    break;
+                };
+                cx.background_executor().timer(interval).await;
+            }
+        })
+    }
+    
+    /// Time until the next refresh: the configured interval while input
+    /// arrives, slower when idle or when the window is in the background or
+    /// minimized
+    fn refresh_interval(&self, window: &Window, cx: &App) -> Duration {
+        let interval = Duration::from_millis(self.settings.read(cx).config().refresh_ms).max(MIN_REFRESH_INTERVAL);
+        if !window.is_window_active() {
+            return interval.max(UNFOCUSED_REFRESH_INTERVAL);
+        }
+        match self.stats_manager.idle_time() {
+            Some(idle) if idle < IDLE_AFTER => interval,
+            _ => interval.max(IDLE_REFRESH_INTERVAL),
+        }
+    }
+    
+    /// Switch between the full dashboard and the compact view, resizing the window
+    fn set_view_mode(&mut self, mode: ViewMode, window: &mut Window) {
+        if mode == self.view_mode {
+            return;
+        }
+        match mode {
+            ViewMode::Compact => {
+                self.full_size = Some(window.viewport_size());
+                set_min_size(window, mode);
+                window.resize(COMPACT_SIZE);
+            }
+            ViewMode::Full => {
+                let restored = self.full_size.take().unwrap_or(FULL_SIZE);
+                window.resize(restored.max(&FULL_MIN_SIZE));
+                set_min_size(window, mode);
+            }
+        }
+        if self.compact_on_top {
+            let on_top = mode == ViewMode::Compact;
+            let applied = HasWindowHandle::window_handle(window)
+                .map(|handle| platform::set_always_on_top(handle.as_raw(), on_top))
+                .unwrap_or(false);
+            if !applied && on_top {
+                log::warn!("Keeping the compact window on top is not supported here");
+            }
+        }
+        self.view_mode = mode;
+    }
+    
+    /// Ask for a stats file from another machine and merge it into ours
+    fn import_stats_file(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        });
+        let stats_manager = self.stats_manager.clone();
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let status = cx.background_executor().spawn(async move {
+                match stats_manager.import_and_merge(&path) {
+                    Ok(report) => Ok(format!(
+                        "📥 Imported {} keys from {} day(s), {} overlapping",
+                        report.keys_added,
+                        report.days_merged,
+                        report.overlapping_days.len()
+                    )),
+                    Err(e) => Err(format!("Import failed: {}", e)),
+                }
+            }).await;
+            let _ = this.update(cx, |dashboard, cx| {
+                dashboard.file_status = Some(status);
+                cx.notify();
+            });
+        }).detach();
+    }
+    
+    /// Ask where to save the days from `from` to `to` and export them there
+    fn export_range_file(&mut self, from: NaiveDate, to: NaiveDate, cx: &mut Context<Self>) {
+        if from > to {
+            self.file_status = Some(Err(format!("Export failed: start date {} is after end date {}", from, to)));
+            return;
+        }
+        let directory = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let name = format!("finger-{}-to-{}.json", from, to);
+        let path = cx.prompt_for_new_path(&directory, Some(&name));
+        let stats_manager = self.stats_manager.clone();
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let status = cx.background_executor().spawn(async move {
+                match stats_manager.export_range(from, to, &path) {
+                    Ok(days) => Ok(format!("📤 Exported {} day(s) to {}", days, path.display())),
+                    Err(e) => Err(format!("Export failed: {}", e)),
+                }
+            }).await;
+            let _ = this.update(cx, |dashboard, cx| {
+                dashboard.file_status = Some(status);
+                dashboard.export_range = None;
+                cx.notify();
+            });
+        }).detach();
+    }
+    
+    /// Ask where to save this year's Year in Review and write it there
+    fn export_year_review(&mut self, cx: &mut Context<Self>) {
+        let year = self.stats_snapshot.today().year();
+        let directory = dirs::document_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let name = format!("finger-{}-in-review.md", year);
+        let path = cx.prompt_for_new_path(&directory, Some(&name));
+        let stats = self.stats_snapshot.clone();
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let status = cx.background_executor().spawn(async move {
+                match report::write_year_review(&stats, year, &path) {
+                    Ok(()) => Ok(format!("📅 Wrote the {} Year in Review to {}", year, path.display())),
+                    Err(e) => Err(format!("Report failed: {}", e)),
+                }
+            }).await;
+            let _ = this.update(cx, |dashboard, cx| {
+                dashboard.file_status = Some(status);
+                cx.notify();
+            });
+        }).detach();
+    }
+    
+    /// Update the remembered choices, saving them in the background if they changed
+    fn remember(&mut self, cx: &mut Context<Self>, update: impl FnOnce(&mut Prefs)) {
+        let before = self.prefs.clone();
+        update(&mut self.prefs);
+        if self.prefs != before {
+            let prefs = self.prefs.clone();
+            cx.background_executor().spawn(async move { prefs.save() }).detach();
         }
     }
     
     /// Refresh statistics snapshot
     pub fn refresh(&mut self) {
+        self.stats_manager.check_rollover();
+        self.stats_manager.update_smoothed_wpm();
         self.stats_snapshot = self.stats_manager.snapshot();
+        self.stats_snapshot.apply_key_aliases(&self.key_aliases);
+        
+        let target = self.stats_snapshot.next_milestone().map(|(target, _, _)| target);
+        if target != self.key_milestone {
+            self.key_milestone_passed_at = Some(Instant::now());
+        }
+        self.key_milestone = target;
     }
 }
 
 impl Render for Dashboard {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Refresh stats
-        self.refresh();
+        let key_scale = self.settings.read(cx).config().key_scale;
+        // The refresh task redraws far less often than the flash fades
+        if self.last_press.as_ref().is_some_and(|(_, at)| at.elapsed() < PRESS_FLASH) {
+            window.request_animation_frame();
+        }
         
-        // Schedule next refresh (100ms) - real-time updates
-        cx.spawn_in(window, async move |this, mut cx| {
-            cx.background_executor().timer(Duration::from_millis(100)).await;
-            let _ = this.update(cx, |dashboard, cx| {
-                dashboard.refresh();
-                cx.notify();
-            });
-        }).detach();
-
+        if self.view_mode == ViewMode::Compact {
+            return self.render_compact(cx).into_any_element();
+        }
+        if self.grow_to_min_size {
+            let size = window.viewport_size();
+            if size.width < FULL_MIN_SIZE.width || size.height < FULL_MIN_SIZE.height {
+                window.resize(size.max(&FULL_MIN_SIZE));
+            }
+        }
         
         let stats = &self.stats_snapshot;
         let today_keys = stats.today_keys();
         let today_clicks = stats.today_clicks();
         let today_distance = stats.today_distance();
-        let wpm = stats.current_wpm();
+        let (today_drags, today_drag_distance) = stats.today_drags();
+        let other_button_clicks = stats.other_button_clicks();
+        let wpm = stats.display_wpm();
+        let peak_wpm = stats.peak_wpm_today();
+        let corrected_wpm = stats.corrected_wpm();
         let session = stats.session_duration();
-        let total_keys: u64 = stats.key_counts.values().sum();
+        let total_keys = stats.total_keys();
         let total_clicks: u64 = stats.mouse_clicks.values().sum();
-        let top_keys = stats.top_keys(20);
+        let top_keys = stats.search_top_keys(&self.top_keys_query, self.top_keys_count, self.top_keys_filter, |key| self.key_labels.get(key));
+        let top_apps = stats.top_apps(10);
+        let devices = stats.devices();
+        let recent_sessions: Vec<SessionRecord> = stats.sessions().into_iter().take(10).collect();
+        let top_bigrams = stats.top_bigrams(15);
+        let hand_balance = stats.hand_balance();
+        let finger_load = stats.finger_load();
+        let current_streak = stats.current_streak(self.streak_min_keys);
+        let longest_streak = stats.longest_streak(self.streak_min_keys);
+        let peak_hour = stats.peak_hour();
+        let peak_day = stats.peak_day();
+        let (weekdays, weekends) = stats.weekday_weekend_split();
+        let active_minutes = (stats.total_active_seconds / 60.0) as u64;
+        let dropped_events = self.stats_manager.dropped_events();
+        let storage_error = self.stats_manager.storage_error();
+        let load_warning = self.stats_manager.load_warning();
+        if self.stats_manager.is_listener_active() {
+            self.listener_panel_open = false;
+            self.listener_stopped = false;
+            self.permission = PermissionStatus::Granted;
+        } else if !self.listener_stopped {
+            // Opened once when the listener fails; closing it keeps it closed
+            self.listener_panel_open = true;
+            self.listener_stopped = true;
+        }
+        let show_permission_screen = self.permission == PermissionStatus::Denied && !self.permission_screen_dismissed;
+        let listener_panel = self.listener_panel_open.then(|| {
+            self.stats_manager.get_listener_error()
+                .unwrap_or_else(|| "The input listener is not running".to_string())
+        });
+        let listener_warning = self.stats_manager.get_listener_warning().filter(|_| !self.listener_warning_dismissed);
+        let privacy_mode = stats.privacy_mode;
+        let heatmap_width = window.viewport_size().width - px(HEATMAP_SIDE_SPACE);
+        // Key identities are hidden in privacy mode
+        let heatmap_counts = if privacy_mode {
+            HashMap::new()
+        } else {
+            match self.heatmap_period {
+                HeatmapPeriod::AllTime => stats.key_counts.clone(),
+                HeatmapPeriod::Recent => stats.decayed_key_counts(self.recent_half_life_days)
+                    .into_iter()
+                    .map(|(key, count)| (key, count.round() as u64))
+                    .collect(),
+                HeatmapPeriod::Session => stats.session_key_counts.clone(),
+            }
+        };
+        let row_usage = keyboard_layout::row_totals(&heatmap_counts);
         
         // Wrap everything in a relative container to position resize handles
         let stats_manager = self.stats_manager.clone();
@@ -64,19 +589,17 @@ impl Render for Dashboard {
             .relative()
             .size_full()
             .track_focus(&self.focus_handle) // Use tracked focus handle
-            .on_key_down(move |event, _window, _cx| {
-                let keystroke = &event.keystroke;
-                let key = if keystroke.key.len() == 1 {
-                    keystroke.key.to_uppercase()
-                } else {
-                    // Capitalize first letter for special keys
-                    let mut c = keystroke.key.chars();
-                    match c.next() {
-                        None => String::new(),
-                        Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
-                    }
-                };
-                stats_manager.record_key(key);
+            .on_key_down(cx.listener(move |this, event: &KeyDownEvent, _window, cx| {
+                let name = keystroke_name(&event.keystroke);
+                stats_manager.record_key(name.clone());
+                this.last_press = Some((name, Instant::now()));
+                cx.notify();
+            }))
+            .on_key_up({
+                let stats_manager = self.stats_manager.clone();
+                move |event, _window, _cx| {
+                    stats_manager.record_key_release(&keystroke_name(&event.keystroke));
+                }
             })
             .on_mouse_down(MouseButton::Left, {
                 let stats_manager = self.stats_manager.clone();
@@ -90,12 +613,28 @@ impl Render for Dashboard {
                     stats_manager.record_click("Right".to_string());
                 }
             })
+            // Scrollbar thumbs keep following the pointer outside their track
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _window, cx| {
+                let Some((handle, start_y, start_offset)) = &this.scrollbar_drag else {
+                    return;
+                };
+                if event.pressed_button != Some(MouseButton::Left) {
+                    this.scrollbar_drag = None;
+                    return;
+                }
+                let offset = scroll_metrics(handle).dragged(*start_offset, f32::from(event.position.y - *start_y));
+                handle.set_offset(point(handle.offset().x, px(-offset)));
+                cx.notify();
+            }))
+            .on_mouse_up(MouseButton::Left, cx.listener(|this, _event, _window, _cx| {
+                this.scrollbar_drag = None;
+            }))
             .child(
                 div()
                     .id("main-container")
                     .size_full()
-                    .bg(rgb(0x0f0f14))
-                    .text_color(rgb(0xe0e0e0))
+                    .bg(rgb(self.theme.background))
+                    .text_color(rgb(self.theme.text))
                     .font_family("JetBrains Mono")
                     .flex()
                     .flex_col()
@@ -105,9 +644,9 @@ impl Render for Dashboard {
                             .id("menu-bar")
                             .w_full()
                             .h_10()
-                            .bg(rgb(0x16161e))
+                            .bg(rgb(self.theme.bar))
                             .border_b_1()
-                            .border_color(rgb(0x2a2a3a))
+                            .border_color(rgb(self.theme.border))
                             .flex()
                             .items_center()
                             .px_4()
@@ -125,16 +664,16 @@ impl Render for Dashboard {
                                         div()
                                             .text_base()
                                             .font_weight(FontWeight::BOLD)
-                                            .text_color(rgb(0x7aa2f7))
+                                            .text_color(rgb(self.theme.blue))
                                             .child("⌨️ Finger Monitor")
                                     )
                                     .child(
                                         div()
                                             .text_xs()
-                                            .text_color(rgb(0x565f89))
+                                            .text_color(rgb(self.theme.text_muted))
                                             .px_2()
                                             .py_1()
-                                            .bg(rgb(0x1a1b26))
+                                            .bg(rgb(self.theme.surface))
                                             .rounded_md()
                                             .child("v0.1.0")
                                     )
@@ -145,12 +684,12 @@ impl Render for Dashboard {
                                     .flex()
                                     .items_center()
                                     .gap_2()
-                                    .child(div().text_xs().text_color(rgb(0x565f89)).child("Session"))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("Session"))
                                     .child(
                                         div()
                                             .text_sm()
                                             .font_weight(FontWeight::MEDIUM)
-                                            .text_color(rgb(0x9ece6a))
+                                            .text_color(rgb(self.theme.green))
                                             .child(format!("{:02}:{:02}:{:02}",
                                                 session.as_secs() / 3600,
                                                 (session.as_secs() % 3600) / 60,
@@ -158,20 +697,195 @@ impl Render for Dashboard {
                                             ))
                                     )
                             )
+                            .child(self.render_intensity_meter(stats.typing_intensity()))
+                            // Import another machine's stats
+                            .child(
+                                div()
+                                    .id("btn-import")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child("📥")
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.import_stats_file(cx);
+                                    }))
+                            )
+                            // Export a date range
+                            .child(
+                                div()
+                                    .id("btn-export")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .when(self.export_range.is_some(), |this| this.bg(rgb(self.theme.border)))
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child("📤")
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        let today = this.stats_snapshot.today();
+                                        this.export_range = match this.export_range {
+                                            Some(_) => None,
+                                            None => Some((today - Days::new(6), today)),
+                                        };
+                                        cx.notify();
+                                    }))
+                            )
+                            // Copy today's summary; Shift copies the multi-line version
+                            .child({
+                                let copied = self.copied_at.is_some_and(|at| at.elapsed() < COPIED_FEEDBACK);
+                                div()
+                                    .id("btn-copy-summary")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .when(copied, |this| this.bg(rgb(self.theme.border)))
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child(if copied { "✅" } else { "📋" })
+                                    .on_click(cx.listener(|this, ev: &ClickEvent, _window, cx| {
+                                        let text = this.stats_snapshot.summary_text(ev.modifiers().shift);
+                                        cx.write_to_clipboard(ClipboardItem::new_string(text));
+                                        this.copied_at = Some(Instant::now());
+                                        cx.notify();
+                                    }))
+                            })
+                            // Year in Review
+                            .child(
+                                div()
+                                    .id("btn-year-review")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child("📅")
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.export_year_review(cx);
+                                    }))
+                            )
+                            // Privacy mode toggle
+                            .child({
+                                let stats_manager = self.stats_manager.clone();
+                                div()
+                                    .id("btn-privacy")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .when(privacy_mode, |this| this.bg(rgb(self.theme.border)))
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child(if privacy_mode { "🔒" } else { "🔓" })
+                                    .on_click(move |_ev, _window, _cx| {
+                                        stats_manager.set_privacy_mode(!privacy_mode);
+                                    })
+                            })
+                            // Light/dark theme toggle
+                            .child(
+                                div()
+                                    .id("btn-theme")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child(match self.theme_mode {
+                                        ThemeMode::Dark => "🌙",
+                                        ThemeMode::Light => "☀",
+                                    })
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.theme_mode = this.theme_mode.next();
+                                        this.theme = this.theme_mode.theme().with_accents(&this.settings.read(cx).config().accent_colors);
+                                        let id = this.theme_mode.id();
+                                        this.remember(cx, |prefs| prefs.theme = Some(id.to_string()));
+                                        let theme = this.theme;
+                                        this.settings.update(cx, |settings, _| settings.set_theme(theme));
+                                        cx.notify();
+                                    }))
+                            )
+                            // Settings
+                            .child(
+                                div()
+                                    .id("btn-settings")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .when(self.settings_open, |this| this.bg(rgb(self.theme.border)))
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child("⚙")
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.settings_open = !this.settings_open;
+                                        cx.notify();
+                                    }))
+                            )
+                            // Pause-in-fullscreen toggle
+                            .child({
+                                let stats_manager = self.stats_manager.clone();
+                                let pauses = stats_manager.pauses_in_fullscreen();
+                                div()
+                                    .id("btn-fullscreen-pause")
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .text_sm()
+                                    .when(pauses, |this| this.bg(rgb(self.theme.border)))
+                                    .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                    .child("🎬")
+                                    .on_click(move |_ev, _window, _cx| {
+                                        stats_manager.set_pause_in_fullscreen(!pauses);
+                                    })
+                            })
                             .child({
                                 let is_active = self.stats_manager.is_listener_active();
-                                let (color, text) = if is_active {
-                                    (rgb(0x73daca), "LIVE")
+                                let pause_reason = self.stats_manager.pause_reason().filter(|_| is_active);
+                                let (color, text) = if pause_reason == Some(PauseReason::ExcludedApp) {
+                                    (rgb(self.theme.yellow), "MUTED")
+                                } else if pause_reason == Some(PauseReason::Fullscreen) {
+                                    (rgb(self.theme.yellow), "PAUSED · FULLSCREEN")
+                                } else if is_active {
+                                    (rgb(self.theme.teal), "LIVE")
                                 } else {
-                                    (rgb(0xf7768e), "OFFLINE")
+                                    (rgb(self.theme.red), "OFFLINE")
                                 };
                                 
+                                // Offline, the badge opens the panel explaining why
                                 div()
+                                    .id("btn-listener-status")
                                     .flex()
                                     .items_center()
                                     .gap_1()
+                                    .px_1()
+                                    .rounded_md()
                                     .child(div().w_2().h_2().rounded_full().bg(color))
                                     .child(div().text_xs().text_color(color).child(text))
+                                    .when(!is_active, |this| {
+                                        this.cursor_pointer()
+                                            .hover(|s| s.bg(rgb(self.theme.border_strong)))
+                                            .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                                            .child(div().text_xs().text_color(color).child(if self.listener_panel_open { "▴" } else { "▾" }))
+                                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                                this.listener_panel_open = !this.listener_panel_open;
+                                                cx.notify();
+                                            }))
+                                    })
                             })
                             // Window control buttons (simple style)
                             .child(
@@ -181,6 +895,29 @@ impl Render for Dashboard {
                                     .gap_1()
                                     .ml_3()
                                     .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation()) // Prevent dragging when clicking buttons
+                                    // Compact view button
+                                    .child(
+                                        div()
+                                            .id("btn-compact")
+                                            .w_7()
+                                            .h_7()
+                                            .rounded_md()
+                                            .bg(rgb(self.theme.border))
+                                            .border_1()
+                                            .border_color(rgb(self.theme.border_strong))
+                                            .hover(|s| s.bg(rgb(self.theme.border_strong)).border_color(rgb(self.theme.border_hover)))
+                                            .cursor_pointer()
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .text_sm()
+                                            .text_color(rgb(self.theme.text_dim))
+                                            .child("⊟")
+                                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                                this.set_view_mode(ViewMode::Compact, window);
+                                                cx.notify();
+                                            }))
+                                    )
                                     // Minimize button
                                     .child(
                                         div()
@@ -188,16 +925,16 @@ impl Render for Dashboard {
                                             .w_7()
                                             .h_7()
                                             .rounded_md()
-                                            .bg(rgb(0x2a2a3a))
+                                            .bg(rgb(self.theme.border))
                                             .border_1()
-                                            .border_color(rgb(0x3a3a4a))
-                                            .hover(|s| s.bg(rgb(0x3a3a4a)).border_color(rgb(0x4a4a5a)))
+                                            .border_color(rgb(self.theme.border_strong))
+                                            .hover(|s| s.bg(rgb(self.theme.border_strong)).border_color(rgb(self.theme.border_hover)))
                                             .cursor_pointer()
                                             .flex()
                                             .items_center()
                                             .justify_center()
                                             .text_sm()
-                                            .text_color(rgb(0x888898))
+                                            .text_color(rgb(self.theme.text_dim))
                                             .child("—")
                                             .on_click(move |_ev, window, _cx| {
                                                 window.minimize_window();
@@ -210,16 +947,16 @@ impl Render for Dashboard {
                                             .w_7()
                                             .h_7()
                                             .rounded_md()
-                                            .bg(rgb(0x2a2a3a))
+                                            .bg(rgb(self.theme.border))
                                             .border_1()
-                                            .border_color(rgb(0x3a3a4a))
-                                            .hover(|s| s.bg(rgb(0x3a3a4a)).border_color(rgb(0x4a4a5a)))
+                                            .border_color(rgb(self.theme.border_strong))
+                                            .hover(|s| s.bg(rgb(self.theme.border_strong)).border_color(rgb(self.theme.border_hover)))
                                             .cursor_pointer()
                                             .flex()
                                             .items_center()
                                             .justify_center()
                                             .text_sm()
-                                            .text_color(rgb(0x888898))
+                                            .text_color(rgb(self.theme.text_dim))
                                             .child("☐")
                                             .on_click(move |_ev, window, _cx| {
                                                 window.toggle_fullscreen();
@@ -232,16 +969,16 @@ impl Render for Dashboard {
                                             .w_7()
                                             .h_7()
                                             .rounded_md()
-                                            .bg(rgb(0x2a2a3a))
+                                            .bg(rgb(self.theme.border))
                                             .border_1()
-                                            .border_color(rgb(0x3a3a4a))
-                                            .hover(|s| s.bg(rgb(0x5a2a2a)).border_color(rgb(0x7a3a3a)).text_color(rgb(0xff6666)))
+                                            .border_color(rgb(self.theme.border_strong))
+                                            .hover(|s| s.bg(rgb(self.theme.error_hover)).border_color(rgb(self.theme.error_border)).text_color(rgb(self.theme.error_text)))
                                             .cursor_pointer()
                                             .flex()
                                             .items_center()
                                             .justify_center()
                                             .text_sm()
-                                            .text_color(rgb(0x888898))
+                                            .text_color(rgb(self.theme.text_dim))
                                             .child("✕")
                                             .on_click(move |_ev, _window, cx| {
                                                 cx.quit();
@@ -266,26 +1003,96 @@ impl Render for Dashboard {
                                     .flex()
                                     .flex_col()
                                     .gap_4()
+                                    // Why nothing is being recorded
+                                    .when_some(listener_panel, |this, error| this.child(self.render_listener_panel(error, cx)))
+                                    .when_some(listener_warning, |this, warning| this.child(self.render_listener_warning(warning, cx)))
+                                    .when_some(self.export_range, |this, range| this.child(self.render_export_picker(range, cx)))
+                                    .when(self.settings_open, |this| this.child(self.settings.clone()))
                                     // Stats cards row
                                     .child(
                                         div()
                                             .flex()
                                             .gap_3()
                                             .flex_wrap()
-                                            .child(self.render_stat_card("Today Keys", &format!("{}", today_keys), "⌨️", rgb(0x7aa2f7).into()))
-                                            .child(self.render_stat_card("Today Clicks", &format!("{}", today_clicks), "🖱️", rgb(0xbb9af7).into()))
-                                            .child(self.render_stat_card("Distance", &format!("{:.2} m", today_distance / 1000.0), "📏", rgb(0x9ece6a).into()))
-                                            .child(self.render_stat_card("WPM", &format!("{:.0}", wpm), "⚡", rgb(0xff9e64).into()))
+                                            .child(
+                                                self.render_stat_card("Today Keys", &format_count(today_keys), "⌨️", rgb(self.theme.blue).into())
+                                                    .child(self.render_delta(today_keys as f64, stats.yesterday_keys() as f64))
+                                            )
+                                            .child(
+                                                self.render_stat_card("Today Clicks", &format_count(today_clicks), "🖱️", rgb(self.theme.purple).into())
+                                                    .child(self.render_delta(today_clicks as f64, stats.yesterday_clicks() as f64))
+                                            )
+                                            .child(
+                                                self.render_stat_card("Distance", &format!("{:.2} m", today_distance / 1000.0), "📏", rgb(self.theme.green).into())
+                                                    .child(self.render_delta(today_distance, stats.yesterday_distance()))
+                                            )
+                                            .child(
+                                                self.render_stat_card("WPM", &format!("{:.0}", wpm), "⚡", rgb(self.theme.orange).into())
+                                                    .child(Sparkline::new(stats.wpm_history(SPARKLINE_SPAN), rgb(self.theme.orange)).theme(self.theme))
+                                            )
+                                            .child(
+                                                self.render_stat_card("APM", &format!("{:.0}", stats.current_apm()), "🎮", rgb(self.theme.cyan).into())
+                                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(format!("peak {:.0} today", stats.peak_apm_today())))
+                                            )
+                                            .child(
+                                                self.render_stat_card("Correction Rate", &format!("{:.1}%", stats.today_correction_ratio() * 100.0), "✏️", rgb(self.theme.yellow).into())
+                                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(format!("{:.1}% all-time", stats.correction_ratio() * 100.0)))
+                                            )
+                                            .child(
+                                                self.render_stat_card("Averages", &format!("{:.1} keys/min", stats.avg_keys_per_minute()), "📐", rgb(self.theme.cyan).into())
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(rgb(self.theme.text_muted))
+                                                            .child(format!(
+                                                                "{:.1} clicks/min · {}h {:02}m active",
+                                                                stats.avg_clicks_per_minute(),
+                                                                active_minutes / 60,
+                                                                active_minutes % 60
+                                                            ))
+                                                    )
+                                            )
+                                            .child(
+                                                self.render_stat_card(
+                                                    "Most active",
+                                                    &peak_hour.map(|(hour, _)| hour_label(hour)).unwrap_or_else(|| "—".to_string()),
+                                                    "💡",
+                                                    rgb(self.theme.teal).into(),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(rgb(self.theme.text_muted))
+                                                        .child(format!("Busiest day: {}", peak_day.map(|(date, _)| date).unwrap_or_else(|| "—".to_string())))
+                                                )
+                                            )
+                                            .child(
+                                                self.render_stat_card("Streak", &days_label(current_streak), "🔥", rgb(self.theme.red).into())
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(rgb(self.theme.text_muted))
+                                                            .child(format!("Record: {}", days_label(longest_streak)))
+                                                    )
+                                            )
                                     )
                                     // Second row - All time stats
                                     .child(
                                         div()
                                             .flex()
                                             .gap_3()
-                                            .child(self.render_stat_card_small("All-time Keys", &format!("{}", total_keys), rgb(0x7aa2f7).into()))
-                                            .child(self.render_stat_card_small("All-time Clicks", &format!("{}", total_clicks), rgb(0xbb9af7).into()))
-                                            .child(self.render_stat_card_small("Total Distance", &format!("{:.2} km", stats.mouse_distance / 1_000_000.0), rgb(0x9ece6a).into()))
-                                            .child(self.render_stat_card_small("Scroll", &format!("{}", stats.scroll_distance), rgb(0xe0af68).into()))
+                                            .child(self.render_stat_card_small("All-time Keys", &format_count(total_keys), rgb(self.theme.blue).into()))
+                                            .child(self.render_stat_card_small("All-time Clicks", &format_count(total_clicks), rgb(self.theme.purple).into()))
+                                            .child(self.render_stat_card_small("Total Distance", &format!("{:.2} km", stats.mouse_distance / 1_000_000.0), rgb(self.theme.green).into()))
+                                            .child(self.render_stat_card_small("Scroll", &stats.scroll_display(self.stats_manager.scroll_unit()), rgb(self.theme.yellow).into()))
+                                    )
+                                    // Weekdays vs weekends
+                                    .child(
+                                        div()
+                                            .flex()
+                                            .gap_3()
+                                            .child(self.render_week_part_card("Weekdays", &weekdays, weekends.total_keys, "💼", rgb(self.theme.cyan).into()))
+                                            .child(self.render_week_part_card("Weekends", &weekends, weekdays.total_keys, "🏖️", rgb(self.theme.green).into()))
                                     )
                                     // Main content row
                                     .child(
@@ -302,29 +1109,64 @@ impl Render for Dashboard {
                                                     .flex_col()
                                                     .child(
                                                         div()
-                                                            .text_base()
-                                                            .font_weight(FontWeight::SEMIBOLD)
                                                             .mb_3()
-                                                            .child("🌡️ Keyboard Heatmap")
+                                                            .flex()
+                                                            .items_center()
+                                                            .justify_between()
+                                                            .child(
+                                                                div()
+                                                                    .text_base()
+                                                                    .font_weight(FontWeight::SEMIBOLD)
+                                                                    .child("🌡️ Keyboard Heatmap")
+                                                            )
+                                                            .child(
+                                                                div()
+                                                                    .flex()
+                                                                    .items_center()
+                                                                    .gap_3()
+                                                                    .child(self.render_period_selector(cx))
+                                                                    .child(self.render_display_selector(cx))
+                                                                    .child(self.render_layout_selector(cx))
+                                                                    .child(self.render_scheme_selector(cx))
+                                                            )
                                                     )
+                                                    .when(privacy_mode, |this| {
+                                                        this.child(self.render_privacy_banner())
+                                                    })
                                                     .child(
                                                         div()
                                                             .flex_1()
                                                             .flex()
                                                             .items_center()
                                                             .justify_center()
-                                                            .child(KeyboardHeatmap::new(stats.key_counts.clone()))
+                                                            .child(
+                                                                KeyboardHeatmap::new(heatmap_counts, self.heat_scheme, self.heatmap_display)
+                                                                    .scale(self.heat_scale)
+                                                                    .layout(self.keyboard_layout)
+                                                                    .size(self.keyboard_size)
+                                                                    .custom_layout(self.custom_layout.clone())
+                                                                    .theme(self.theme)
+                                                                    .last_press(self.last_press.clone())
+                                                                    .key_labels(self.key_labels.clone())
+                                                                    .map(|heatmap| match key_scale {
+                                                                        KeyScale::Auto => heatmap.fit_width(heatmap_width),
+                                                                        KeyScale::Factor(factor) => heatmap.key_scale(factor),
+                                                                    })
+                                                            )
                                                     )
+                                                    .child(self.render_row_usage(&row_usage))
+                                                    .child(self.render_hand_balance(hand_balance))
+                                                    .child(self.render_finger_usage(&finger_load))
                                             )
                                             // Top keys sidebar with scroll
                                             .child(
                                                 div()
                                                     .w_64()
-                                                    .bg(rgb(0x1a1b26))
+                                                    .bg(rgb(self.theme.surface))
                                                     .rounded_xl()
                                                     .p_4()
                                                     .border_1()
-                                                    .border_color(rgb(0x2a2a3a))
+                                                    .border_color(rgb(self.theme.border))
                                                     .flex()
                                                     .flex_col()
                                                     .max_h_full()
@@ -337,13 +1179,12 @@ impl Render for Dashboard {
                                                             .items_center()
                                                             .justify_between()
                                                             .child("🔥 Top Keys")
-                                                            .child(
-                                                                div()
-                                                                    .text_xs()
-                                                                    .text_color(rgb(0x565f89))
-                                                                    .child(format!("({})", top_keys.len()))
-                                                            )
+                                                            .when(!privacy_mode, |this| this.child(self.render_top_keys_count(cx)))
                                                     )
+                                                    .when(!privacy_mode, |this| {
+                                                        this.child(self.render_top_keys_search(window, cx))
+                                                            .child(self.render_category_filter(cx))
+                                                    })
                                                     // Scrollable keys list with scrollbar
                                                     .child(
                                                         div()
@@ -357,13 +1198,38 @@ impl Render for Dashboard {
                                                                     .track_scroll(&self.top_scroll)
                                                                     .overflow_y_scroll()
                                                                     .overflow_x_hidden()
-                                                                    .children(
-                                                                        top_keys.iter().enumerate().map(|(i, (key, count))| {
-                                                                            self.render_top_key_item(i + 1, key, *count)
+                                                                    .when(privacy_mode, |this| {
+                                                                        this.child(
+                                                                            div()
+                                                                                .p_2()
+                                                                                .text_xs()
+                                                                                .text_color(rgb(self.theme.text_muted))
+                                                                                .child("🔒 Keys hidden in privacy mode")
+                                                                        )
+                                                                        .children(
+                                                                            stats.category_breakdown().into_iter().enumerate().map(|(i, (bucket, count))| {
+                                                                                self.render_top_key_item(i + 1, bucket.label(), count, total_keys)
+                                                                            })
+                                                                        )
+                                                                    })
+                                                                    .when(!privacy_mode, |this| {
+                                                                        this.children(
+                                                                            top_keys.iter().map(|(rank, key, count)| {
+                                                                                self.render_top_key_item(*rank, self.key_labels.display(key), *count, total_keys)
+                                                                            })
+                                                                        )
+                                                                        .when(top_keys.is_empty() && !self.top_keys_query.is_empty(), |this| {
+                                                                            this.child(
+                                                                                div()
+                                                                                    .p_2()
+                                                                                    .text_xs()
+                                                                                    .text_color(rgb(self.theme.text_muted))
+                                                                                    .child(format!("No key matches \"{}\"", self.top_keys_query))
+                                                                            )
                                                                         })
-                                                                    )
+                                                                    })
                                                             )
-                                                            .child(self.render_scrollbar(&self.top_scroll))
+                                                            .children(self.render_scrollbar(&self.top_scroll, cx))
                                                     )
                                             )
                                     )
@@ -372,36 +1238,74 @@ impl Render for Dashboard {
                                         div()
                                             .flex()
                                             .gap_3()
-                                            .child(self.render_mouse_card("Left Click", stats.mouse_clicks.get("Left").copied().unwrap_or(0), rgb(0x7aa2f7)))
-                                            .child(self.render_mouse_card("Right Click", stats.mouse_clicks.get("Right").copied().unwrap_or(0), rgb(0xbb9af7)))
-                                            .child(self.render_mouse_card("Middle Click", stats.mouse_clicks.get("Middle").copied().unwrap_or(0), rgb(0x9ece6a)))
+                                            .child(self.render_click_card(stats, "Left", rgb(self.theme.blue)))
+                                            .child(self.render_click_card(stats, "Right", rgb(self.theme.purple)))
+                                            .child(self.render_click_card(stats, "Middle", rgb(self.theme.green)))
+                                            // Side and extra buttons, once used
+                                            .children(
+                                                ["Back", "Forward"]
+                                                    .into_iter()
+                                                    .filter(|button| stats.mouse_clicks.contains_key(*button))
+                                                    .map(|button| self.render_click_card(stats, button, rgb(self.theme.cyan)))
+                                            )
+                                            .when(other_button_clicks > 0, |row| {
+                                                row.child(self.render_mouse_card("Other Buttons", other_button_clicks, None, rgb(self.theme.text_dim)))
+                                            })
+                                            .child(self.render_mouse_card(
+                                                &format!("Drags today · {:.1} m", today_drag_distance / 1000.0),
+                                                today_drags,
+                                                None,
+                                                rgb(self.theme.yellow),
+                                            ))
                                     )
+                                    // Where the cursor spends its time
+                                    .child(self.render_cursor_panel(stats, cx))
+                                    // Per-application breakdown
+                                    .child(self.render_app_panel(&top_apps))
+                                    // Per-device breakdown
+                                    .child(self.render_device_panel(&devices))
+                                    // Latest sessions
+                                    .child(self.render_session_panel(&recent_sessions))
+                                    // Milestones reached and still ahead
+                                    .child(self.render_achievement_panel(stats))
+                                    // Most common key sequences
+                                    .child(self.render_bigram_panel(&top_bigrams, privacy_mode))
                                     // Hourly chart
                                     .child(
                                         div()
                                             .h_40()
-                                            .bg(rgb(0x1a1b26))
+                                            .bg(rgb(self.theme.surface))
                                             .rounded_xl()
                                             .p_4()
                                             .border_1()
-                                            .border_color(rgb(0x2a2a3a))
+                                            .border_color(rgb(self.theme.border))
                                             .flex()
                                             .flex_col()
                                             .child(
                                                 div()
-                                                    .text_base()
-                                                    .font_weight(FontWeight::SEMIBOLD)
                                                     .mb_2()
-                                                    .child("📊 Today's Activity")
+                                                    .flex()
+                                                    .items_center()
+                                                    .justify_between()
+                                                    .child(
+                                                        div()
+                                                            .text_base()
+                                                            .font_weight(FontWeight::SEMIBOLD)
+                                                            .child("📊 Today's Activity")
+                                                    )
+                                                    .child(self.render_series_selector(cx))
                                             )
                                             .child(
                                                 div()
                                                     .flex_1()
-                                                    .child(HourlyChart::new(stats.hourly_key_counts.clone()))
+                                                    .child(self.activity_chart(stats))
                                             )
                                     )
+                                    // Year at a glance
+                                    .child(self.render_calendar_panel(stats, cx))
                             )
-                            .child(self.render_scrollbar(&self.main_scroll))
+                            .children(self.render_scrollbar(&self.main_scroll, cx))
+                            .when(show_permission_screen, |this| this.child(self.render_permission_screen(cx)))
                     )
                     // Status Bar
                     .child(
@@ -409,9 +1313,9 @@ impl Render for Dashboard {
                             .id("status-bar")
                             .w_full()
                             .h_7()
-                            .bg(rgb(0x16161e))
+                            .bg(rgb(self.theme.bar))
                             .border_t_1()
-                            .border_color(rgb(0x2a2a3a))
+                            .border_color(rgb(self.theme.border))
                             .flex()
                             .items_center()
                             .px_4()
@@ -421,42 +1325,84 @@ impl Render for Dashboard {
                                     .flex()
                                     .items_center()
                                     .gap_1()
-                                    .child(div().text_xs().text_color(rgb(0x565f89)).child("Total:"))
-                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(0x7aa2f7)).child(format!("{} keys", total_keys)))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("Total:"))
+                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(self.theme.blue)).child(format!("{} keys", format_count(total_keys))))
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_1()
+                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(self.theme.purple)).child(format!("{} clicks", format_count(total_clicks))))
                             )
                             .child(
                                 div()
                                     .flex()
                                     .items_center()
                                     .gap_1()
-                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(0xbb9af7)).child(format!("{} clicks", total_clicks)))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("WPM:"))
+                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(self.theme.orange)).child(format!("{:.0}", wpm)))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(format!("(net {:.0})", corrected_wpm)))
                             )
                             .child(
                                 div()
                                     .flex()
                                     .items_center()
                                     .gap_1()
-                                    .child(div().text_xs().text_color(rgb(0x565f89)).child("WPM:"))
-                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(0xff9e64)).child(format!("{:.0}", wpm)))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("Peak today:"))
+                                    .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(self.theme.orange)).child(format!("{:.0} WPM", peak_wpm)))
                             )
+                            .when(dropped_events > 0, |this| {
+                                this.child(
+                                    div()
+                                        .flex()
+                                        .items_center()
+                                        .gap_1()
+                                        .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("Dropped:"))
+                                        .child(div().text_xs().font_weight(FontWeight::MEDIUM).text_color(rgb(self.theme.red)).child(format!("{} events", format_count(dropped_events))))
+                                )
+                            })
+                            .when_some(self.file_status.clone(), |this, status| {
+                                let (color, text) = match status {
+                                    Ok(text) => (rgb(self.theme.green), text),
+                                    Err(text) => (rgb(self.theme.red), format!("⚠ {}", text)),
+                                };
+                                this.child(div().text_xs().text_color(color).child(text))
+                            })
+                            .when_some(storage_error, |this, error| {
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(rgb(self.theme.red))
+                                        .child(format!("⚠ Not saving: {}", error))
+                                )
+                            })
+                            .when_some(load_warning, |this, warning| {
+                                this.child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(rgb(self.theme.orange))
+                                        .child(format!("⚠ {}", warning))
+                                )
+                            })
                             .child(div().flex_1())
                             .child(
                                 div()
                                     .text_xs()
-                                    .text_color(rgb(0x565f89))
-                                    .child("~/.local/share/rust-finger")
+                                    .text_color(rgb(self.theme.text_muted))
+                                    .child(self.stats_manager.data_path().display().to_string())
                             )
                     )
             )
             // Resize Handles
             // Top
-            .child(self.render_resize_handle(ResizeEdge::Top, 4.0, true))
+            .child(self.render_resize_handle(ResizeEdge::Top, 4.0))
             // Bottom
-            .child(self.render_resize_handle(ResizeEdge::Bottom, 4.0, true))
+            .child(self.render_resize_handle(ResizeEdge::Bottom, 4.0))
             // Left
-            .child(self.render_resize_handle(ResizeEdge::Left, 4.0, false))
+            .child(self.render_resize_handle(ResizeEdge::Left, 4.0))
             // Right
-            .child(self.render_resize_handle(ResizeEdge::Right, 4.0, false))
+            .child(self.render_resize_handle(ResizeEdge::Right, 4.0))
             // TopLeft
             .child(self.render_resize_corner(ResizeEdge::TopLeft))
             // TopRight
@@ -465,11 +1411,12 @@ impl Render for Dashboard {
             .child(self.render_resize_corner(ResizeEdge::BottomLeft))
             // BottomRight
             .child(self.render_resize_corner(ResizeEdge::BottomRight))
+            .into_any_element()
     }
 }
 
 impl Dashboard {
-    fn render_resize_handle(&self, edge: ResizeEdge, size: f32, horizontal: bool) -> Div {
+    fn render_resize_handle(&self, edge: ResizeEdge, size: f32) -> Div {
         let mut div = div()
             .absolute()
             .bg(rgba(0x00000000)); // Transparent
@@ -507,55 +1454,1128 @@ impl Dashboard {
             window.start_window_resize(edge);
         })
     }
-
-    fn render_stat_card(&self, label: &str, value: &str, icon: &str, accent_color: Hsla) -> Div {
+    
+    /// Share of the character keys pressed on each row, the home row highlighted;
+    /// nothing while no character key of the heatmap period was pressed
+    fn render_row_usage(&self, rows: &[(KeyRow, u64)]) -> Div {
+        let total: u64 = rows.iter().map(|(_, count)| count).sum();
+        
         div()
-            .flex_1()
-            .min_w_40()
-            .bg(rgb(0x1a1b26))
-            .rounded_xl()
-            .p_4()
-            .border_1()
-            .border_color(rgb(0x2a2a3a))
-            .shadow_sm()
-            .hover(|s| s.border_color(accent_color).bg(rgb(0x1f2030)).shadow_md())
+            .mt_3()
+            .flex()
+            .justify_between()
+            .text_xs()
+            .when(total > 0, |this| {
+                this.children(rows.iter().map(|(row, count)| {
+                    let color = if *row == KeyRow::Home { self.theme.green } else { self.theme.text_muted };
+                    div()
+                        .flex()
+                        .gap_1()
+                        .child(div().text_color(rgb(self.theme.text_faint)).child(format!("{} row", row.label())))
+                        .child(
+                            div()
+                                .font_weight(FontWeight::MEDIUM)
+                                .text_color(rgb(color))
+                                .child(format!("{:.0}%", *count as f64 / total as f64 * 100.0))
+                        )
+                }))
+            })
+    }
+    
+    /// Horizontal split bar showing left vs right hand share
+    fn render_hand_balance(&self, (left, right, both): (u64, u64, u64)) -> Div {
+        let sided = left + right;
+        let left_share = if sided > 0 { left as f32 / sided as f32 } else { 0.5 };
+        
+        div()
+            .mt_3()
             .flex()
             .flex_col()
             .gap_1()
             .child(
                 div()
                     .flex()
-                    .items_center()
-                    .gap_2()
-                    .child(div().text_lg().child(icon.to_string()))
-                    .child(div().text_xs().text_color(rgb(0x565f89)).child(label.to_string()))
+                    .justify_between()
+                    .text_xs()
+                    .child(
+                        div()
+                            .text_color(rgb(self.theme.blue))
+                            .child(format!("✋ Left {:.0}% ({})", left_share * 100.0, left))
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(self.theme.text_muted))
+                            .child(format!("Both {}", both))
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(self.theme.purple))
+                            .child(format!("({}) {:.0}% Right 🤚", right, (1.0 - left_share) * 100.0))
+                    )
             )
             .child(
                 div()
-                    .text_2xl()
-                    .font_weight(FontWeight::BOLD)
-                    .text_color(accent_color)
-                    .child(value.to_string())
+                    .h_2()
+                    .w_full()
+                    .flex()
+                    .rounded_full()
+                    .overflow_hidden()
+                    .bg(rgb(self.theme.raised))
+                    .when(sided > 0, |this| {
+                        this.child(div().h_full().w(relative(left_share)).bg(rgb(self.theme.blue)))
+                            .child(div().h_full().flex_1().bg(rgb(self.theme.purple)))
+                    })
             )
     }
     
-    fn render_stat_card_small(&self, label: &str, value: &str, accent_color: Hsla) -> Div {
+    /// Ten columns, one per finger, with bar height relative to the busiest finger
+    fn render_finger_usage(&self, usage: &HashMap<Finger, u64>) -> Div {
+        let total: u64 = usage.values().sum();
+        let max = usage.values().copied().max().unwrap_or(0);
+        
         div()
-            .flex_1()
-            .bg(rgb(0x1a1b26))
-            .rounded_lg()
-            .p_3()
-            .border_1()
-            .border_color(rgb(0x2a2a3a))
-            .shadow_sm()
+            .mt_3()
+            .h_24()
             .flex()
-            .items_center()
-            .justify_between()
-            .child(
+            .gap_2()
+            .children(Finger::ALL.iter().map(|finger| {
+                let count = usage.get(finger).copied().unwrap_or(0);
+                let height = if max > 0 { (count as f32 / max as f32).max(0.02) } else { 0.02 };
+                let share = if total > 0 { count as f32 / total as f32 * 100.0 } else { 0.0 };
+                let color = match finger {
+                    Finger::LeftThumb | Finger::RightThumb => rgb(self.theme.green),
+                    Finger::LeftPinky | Finger::LeftRing | Finger::LeftMiddle | Finger::LeftIndex => rgb(self.theme.blue),
+                    _ => rgb(self.theme.purple),
+                };
+                
                 div()
-                    .text_xs()
-                    .text_color(rgb(0x565f89))
-                    .child(label.to_string())
+                    .flex_1()
+                    .h_full()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .justify_end()
+                    .gap_1()
+                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(format!("{:.0}%", share)))
+                    .child(
+                        div()
+                            .flex_1()
+                            .w_full()
+                            .flex()
+                            .flex_col()
+                            .justify_end()
+                            .child(div().w_full().h(relative(height)).rounded_t_sm().bg(color))
+                    )
+                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(finger.short_label()))
+            }))
+    }
+    
+    fn render_cursor_panel(&self, stats: &Stats, cx: &mut Context<Self>) -> Div {
+        let (grid, hint) = match self.cursor_grid {
+            CursorGrid::Movement => (
+                &stats.mouse_position_grid,
+                "Where the cursor rests on the primary display; other monitors count at its edges",
+            ),
+            CursorGrid::Clicks => (&stats.click_position_grid, "Where clicks land on the primary display"),
+        };
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_base()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("🖱️ Cursor Heatmap")
+                    )
+                    .child(self.render_cursor_grid_selector(cx))
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(self.theme.text_muted))
+                    .child(if grid.is_empty() { "Use the mouse to fill the map" } else { hint })
+            )
+            .child(div().flex().justify_center().child(MouseHeatmap::new(grid.to_vec(), self.heat_scheme, self.heat_scale).theme(self.theme)))
+    }
+    
+    fn render_app_panel(&self, apps: &[(String, u64, u64)]) -> Div {
+        let max = apps.iter().map(|(_, keys, clicks)| keys + clicks).max().unwrap_or(0);
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .mb_2()
+                    .child("🪟 By Application")
+            )
+            .when(apps.is_empty(), |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("No application data yet"))
+            })
+            .children(apps.iter().map(|(app, keys, clicks)| {
+                let share = if max > 0 { (keys + clicks) as f32 / max as f32 } else { 0.0 };
+                
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .text_xs()
+                    .child(div().w_40().overflow_hidden().child(app.clone()))
+                    .child(
+                        div()
+                            .flex_1()
+                            .h_2()
+                            .rounded_full()
+                            .bg(rgb(self.theme.raised))
+                            .child(div().h_full().w(relative(share)).rounded_full().bg(rgb(self.theme.cyan)))
+                    )
+                    .child(div().w_20().text_color(rgb(self.theme.blue)).child(format!("⌨️ {}", format_count(*keys))))
+                    .child(div().w_20().text_color(rgb(self.theme.purple)).child(format!("🖱️ {}", format_count(*clicks))))
+            }))
+    }
+    
+    /// Totals per input device; devices the listener is not reading from
+    /// (unplugged, or seen in an earlier run) are grayed out
+    fn render_device_panel(&self, devices: &[DeviceUsage]) -> Div {
+        let today = Local::now().date_naive();
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .mb_2()
+                    .child("🔌 Devices")
+            )
+            .when(devices.is_empty(), |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("No device data yet"))
+            })
+            .children(devices.iter().map(|device| {
+                let connected = self.stats_manager.is_device_connected(&device.name);
+                let last_seen = match device.last_seen {
+                    Some(seen) if seen.date_naive() == today => seen.format("%H:%M").to_string(),
+                    Some(seen) => seen.format("%Y-%m-%d").to_string(),
+                    None => "—".to_string(),
+                };
+                let (name_color, keys_color, clicks_color) = if connected {
+                    (self.theme.text, self.theme.blue, self.theme.purple)
+                } else {
+                    (self.theme.text_faint, self.theme.text_faint, self.theme.text_faint)
+                };
+                
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .text_xs()
+                    .child(div().flex_1().overflow_hidden().text_color(rgb(name_color)).child(device.name.clone()))
+                    .child(div().w_24().text_color(rgb(keys_color)).child(format!("⌨️ {}", format_count(device.keys))))
+                    .child(div().w_24().text_color(rgb(clicks_color)).child(format!("🖱️ {}", format_count(device.clicks))))
+                    .child(
+                        div()
+                            .w_24()
+                            .text_color(rgb(self.theme.text_muted))
+                            .child(format!("last {}", last_seen))
+                    )
+            }))
+    }
+    
+    /// Start, length and totals of the latest sessions, the running one first
+    fn render_session_panel(&self, sessions: &[SessionRecord]) -> Div {
+        let today = Local::now().date_naive();
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .mb_2()
+                    .child("🕒 Recent Sessions")
+            )
+            .when(sessions.is_empty(), |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("No sessions yet"))
+            })
+            .children(sessions.iter().map(|session| {
+                let started = if session.started_at.date_naive() == today {
+                    format!("Today {}", session.started_at.format("%H:%M"))
+                } else {
+                    session.started_at.format("%Y-%m-%d %H:%M").to_string()
+                };
+                let minutes = session.duration().as_secs() / 60;
+                
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .text_xs()
+                    .child(div().flex_1().child(started))
+                    .child(div().w_20().text_color(rgb(self.theme.text_dim)).child(format!("{}h {:02}m", minutes / 60, minutes % 60)))
+                    .child(div().w_24().text_color(rgb(self.theme.blue)).child(format!("⌨️ {}", format_count(session.keys))))
+                    .child(div().w_24().text_color(rgb(self.theme.purple)).child(format!("🖱️ {}", format_count(session.clicks))))
+                    .child(div().w_20().text_color(rgb(self.theme.orange)).child(format!("⚡ {:.0} WPM", session.peak_wpm)))
+            }))
+    }
+    
+    /// Every milestone as a badge: unlocked ones with their date, locked
+    /// ones grayed out
+    fn render_achievement_panel(&self, stats: &Stats) -> Div {
+        let unlocked = MILESTONES.iter().filter(|milestone| stats.achieved.contains_key(milestone.id)).count();
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(div().text_base().font_weight(FontWeight::SEMIBOLD).child("🏆 Achievements"))
+                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(format!("{} of {}", unlocked, MILESTONES.len())))
+            )
+            .child(self.render_key_milestone(stats))
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_2()
+                    .children(MILESTONES.iter().map(|milestone| {
+                        let reached = stats.achieved.get(milestone.id);
+                        let (border, text, detail) = match reached {
+                            Some(date) => (self.theme.yellow, self.theme.text, date.format("%Y-%m-%d").to_string()),
+                            None => (self.theme.border, self.theme.text_faint, "Locked".to_string()),
+                        };
+                        
+                        div()
+                            .w_40()
+                            .p_2()
+                            .rounded_lg()
+                            .bg(rgb(self.theme.raised))
+                            .border_1()
+                            .border_color(rgb(border))
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .when(reached.is_none(), |badge| badge.opacity(0.5))
+                            .child(div().text_lg().child(milestone.icon))
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .child(div().text_xs().text_color(rgb(text)).child(milestone.title))
+                                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(detail))
+                            )
+                    }))
+            )
+    }
+    
+    /// Bar filling towards the next round key total, highlighted for a moment
+    /// after one is passed
+    fn render_key_milestone(&self, stats: &Stats) -> Div {
+        let passed = self.key_milestone_passed_at.is_some_and(|at| at.elapsed() < MILESTONE_HIGHLIGHT);
+        let (target, progress) = match stats.next_milestone() {
+            Some((target, _, progress)) => (Some(target), progress),
+            None => (None, 1.0),
+        };
+        let current = stats.total_keys();
+        let (label, color) = match target {
+            _ if passed => (format!("🎉 {} keys reached!", format_count(current)), self.theme.yellow),
+            Some(target) => (format!("{} keys to go", format_count(target - current)), self.theme.text_muted),
+            None => ("🏆 Every key milestone reached".to_string(), self.theme.text_muted),
+        };
+        
+        div()
+            .p_2()
+            .rounded_lg()
+            .bg(rgb(self.theme.raised))
+            .border_1()
+            .border_color(rgb(if passed { self.theme.yellow } else { self.theme.border }))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .flex()
+                    .justify_between()
+                    .text_xs()
+                    .child(div().text_color(rgb(color)).child(label))
+                    .children(target.map(|target| {
+                        div().text_color(rgb(self.theme.text_dim)).child(format!("Next: {} keys", format_count(target)))
+                    }))
+            )
+            .child(
+                div()
+                    .w_full()
+                    .h_2()
+                    .rounded_full()
+                    .bg(rgb(self.theme.surface))
+                    .child(
+                        div()
+                            .h_full()
+                            .w(relative(progress.clamp(0.0, 1.0)))
+                            .rounded_full()
+                            .bg(rgb(if passed { self.theme.yellow } else { self.theme.blue }))
+                    )
+            )
+    }
+    
+    /// Single draggable row with today's keys, clicks and WPM
+    fn render_compact(&self, cx: &mut Context<Self>) -> Stateful<Div> {
+        let stats = &self.stats_snapshot;
+        let recording = self.stats_manager.is_listener_active() && self.stats_manager.pause_reason().is_none();
+        let status_color = if recording { rgb(self.theme.teal) } else { rgb(self.theme.red) };
+        
+        div()
+            .id("compact-bar")
+            .size_full()
+            .flex()
+            .items_center()
+            .gap_4()
+            .px_3()
+            .bg(rgb(self.theme.bar))
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .text_color(rgb(self.theme.text))
+            .font_family("JetBrains Mono")
+            .text_sm()
+            .on_mouse_down(MouseButton::Left, move |_ev, window, _cx| {
+                window.start_window_move();
+            })
+            .child(div().w_2().h_2().rounded_full().bg(status_color))
+            .child(div().text_color(rgb(self.theme.blue)).child(format!("⌨️ {}", format_count(stats.today_keys()))))
+            .child(div().text_color(rgb(self.theme.purple)).child(format!("🖱️ {}", format_count(stats.today_clicks()))))
+            .child(div().flex_1().text_color(rgb(self.theme.yellow)).child(format!("⚡ {:.0} WPM", stats.display_wpm())))
+            .child(
+                div()
+                    .id("btn-expand")
+                    .w_7()
+                    .h_7()
+                    .rounded_md()
+                    .bg(rgb(self.theme.border))
+                    .border_1()
+                    .border_color(rgb(self.theme.border_strong))
+                    .hover(|s| s.bg(rgb(self.theme.border_strong)).border_color(rgb(self.theme.border_hover)))
+                    .cursor_pointer()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_color(rgb(self.theme.text_dim))
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .child("⊞")
+                    .on_click(cx.listener(|this, _ev, window, cx| {
+                        this.set_view_mode(ViewMode::Full, window);
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    /// Panel explaining why the input listener stopped and how to fix it, with
+    /// a retry button
+    fn render_listener_panel(&self, error: String, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .px_4()
+            .py_3()
+            .rounded_xl()
+            .bg(rgb(self.theme.error_surface))
+            .border_1()
+            .border_color(rgb(self.theme.red))
+            .child(div().text_lg().child("⚠"))
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(self.theme.red))
+                            .child("Input is not being recorded")
+                    )
+                    .child(div().text_xs().text_color(rgb(self.theme.text_bright)).child(error))
+                    .child(div().text_xs().text_color(rgb(self.theme.text_dim)).child(listener::permission_guidance(self.listener_backend)))
+            )
+            .child(
+                div()
+                    .id("btn-retry-listener")
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .text_sm()
+                    .bg(rgb(self.theme.border_strong))
+                    .hover(|s| s.bg(rgb(self.theme.border_hover)))
+                    .child("Retry")
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        InputListener::restart(this.stats_manager.clone(), this.unmapped_keys, this.listener_backend);
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    /// Dismissible banner for a listener that runs but may miss input
+    fn render_listener_warning(&self, warning: String, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_3()
+            .px_4()
+            .py_3()
+            .rounded_xl()
+            .bg(rgb(self.theme.surface))
+            .border_1()
+            .border_color(rgb(self.theme.yellow))
+            .child(div().text_lg().text_color(rgb(self.theme.yellow)).child("⚠"))
+            .child(
+                div()
+                    .flex_1()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(self.theme.yellow))
+                            .child("Some input may not be recorded")
+                    )
+                    .child(div().text_xs().text_color(rgb(self.theme.text_bright)).child(warning))
+            )
+            .child(
+                div()
+                    .id("btn-dismiss-listener-warning")
+                    .w_7()
+                    .h_7()
+                    .rounded_md()
+                    .cursor_pointer()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .text_sm()
+                    .text_color(rgb(self.theme.text_dim))
+                    .hover(|s| s.bg(rgb(self.theme.raised_hover)))
+                    .child("✕")
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.listener_warning_dismissed = true;
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    /// First-run screen shown over the stats while input monitoring has not
+    /// been allowed (macOS Accessibility)
+    fn render_permission_screen(&self, cx: &mut Context<Self>) -> Div {
+        let button = |id: &'static str, label: &'static str, primary: bool| {
+            div()
+                .id(id)
+                .px_4()
+                .py_2()
+                .rounded_md()
+                .cursor_pointer()
+                .text_sm()
+                .when(primary, |this| this.bg(rgb(self.theme.blue)).text_color(rgb(self.theme.background)))
+                .when(!primary, |this| this.bg(rgb(self.theme.border_strong)).text_color(rgb(self.theme.text)))
+                .hover(|s| s.opacity(0.85))
+                .child(label)
+        };
+        
+        div()
+            .absolute()
+            .size_full()
+            .bg(rgb(self.theme.background))
+            .flex()
+            .items_center()
+            .justify_center()
+            .child(
+                div()
+                    .w(px(480.))
+                    .p_6()
+                    .rounded_xl()
+                    .bg(rgb(self.theme.surface))
+                    .border_1()
+                    .border_color(rgb(self.theme.border))
+                    .flex()
+                    .flex_col()
+                    .gap_3()
+                    .child(div().text_2xl().child("🔐"))
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(rgb(self.theme.text))
+                            .child("Allow Finger Monitor to see your input")
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(self.theme.text_dim))
+                            .child("macOS only shares key presses and clicks with apps you allow under Accessibility. Turn on Finger Monitor there (or the terminal you started it from), then come back and check again.")
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .mt_2()
+                            .child(
+                                button("btn-open-accessibility", "Open Accessibility Settings", true)
+                                    .on_click(|_ev, _window, _cx| {
+                                        if !platform::open_accessibility_settings() {
+                                            log::warn!("Could not open the Accessibility settings");
+                                        }
+                                    })
+                            )
+                            .child(
+                                button("btn-check-permission", "Check Again", false)
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.permission = listener::check_permissions();
+                                        if this.permission == PermissionStatus::Granted {
+                                            InputListener::restart(this.stats_manager.clone(), this.unmapped_keys, this.listener_backend);
+                                        }
+                                        cx.notify();
+                                    }))
+                            )
+                            .child(
+                                button("btn-dismiss-permission", "Not Now", false)
+                                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                                        this.permission_screen_dismissed = true;
+                                        cx.notify();
+                                    }))
+                            )
+                    )
+            )
+    }
+    
+    /// Start and end date of an export, each moved a day at a time
+    fn render_export_picker(&self, (from, to): (NaiveDate, NaiveDate), cx: &mut Context<Self>) -> Div {
+        let stepper = |id: &'static str, label: &'static str, date: NaiveDate, set: fn(&mut Self, NaiveDate), cx: &mut Context<Self>| {
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(label))
+                .child(
+                    self.render_toggle((id, 0usize), "◀", false)
+                        .on_click(cx.listener(move |this, _ev, _window, cx| {
+                            set(this, date - Days::new(1));
+                            cx.notify();
+                        }))
+                )
+                .child(div().text_sm().child(date.format("%Y-%m-%d").to_string()))
+                .child(
+                    self.render_toggle((id, 1usize), "▶", false)
+                        .on_click(cx.listener(move |this, _ev, _window, cx| {
+                            set(this, date + Days::new(1));
+                            cx.notify();
+                        }))
+                )
+        };
+        
+        div()
+            .flex()
+            .items_center()
+            .gap_4()
+            .px_4()
+            .py_3()
+            .rounded_xl()
+            .bg(rgb(self.theme.surface))
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .child(div().text_sm().font_weight(FontWeight::SEMIBOLD).child("📤 Export days"))
+            .child(stepper("export-from", "From", from, |this, date| {
+                this.export_range = this.export_range.map(|(_, to)| (date, to));
+            }, cx))
+            .child(stepper("export-to", "To", to, |this, date| {
+                this.export_range = this.export_range.map(|(from, _)| (from, date));
+            }, cx))
+            .when(from > to, |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.red)).child("Start is after end"))
+            })
+            .child(div().flex_1())
+            .child(
+                self.render_toggle("btn-export-save", "Export…", true)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.export_range_file(from, to, cx);
+                        cx.notify();
+                    }))
+            )
+            .child(
+                self.render_toggle("btn-export-cancel", "Cancel", false)
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.export_range = None;
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    fn render_calendar_panel(&self, stats: &Stats, cx: &mut Context<Self>) -> Div {
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .id("toggle-calendar")
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .text_base()
+                    .font_weight(FontWeight::SEMIBOLD)
+                    .child(if self.show_calendar { "▾" } else { "▸" })
+                    .child("📅 Past Year")
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.show_calendar = !this.show_calendar;
+                        cx.notify();
+                    }))
+            )
+            .when(self.show_calendar, |this| {
+                this.child(CalendarHeatmap::new(stats.keys_per_day(), stats.today()).theme(self.theme))
+            })
+    }
+    
+    fn render_bigram_panel(&self, bigrams: &[(String, String, u64)], privacy_mode: bool) -> Div {
+        let fingers = FingerMap::default();
+        let max = bigrams.first().map(|(_, _, count)| *count).unwrap_or(0);
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .mb_2()
+                    .child(
+                        div()
+                            .text_base()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .child("🔗 Key Sequences")
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(self.theme.yellow))
+                            .child("same finger")
+                    )
+            )
+            .when(privacy_mode, |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("🔒 Hidden in privacy mode"))
+            })
+            .when(!privacy_mode && bigrams.is_empty(), |this| {
+                this.child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("No key sequences yet"))
+            })
+            .when(!privacy_mode, |this| {
+                this.children(bigrams.iter().map(|(first, second, count)| {
+                    let share = if max > 0 { *count as f32 / max as f32 } else { 0.0 };
+                    let color = if bigrams::same_finger(&fingers, first, second) {
+                        rgb(self.theme.yellow)
+                    } else {
+                        rgb(self.theme.cyan)
+                    };
+                    
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_3()
+                        .text_xs()
+                        .child(
+                            div()
+                                .w_20()
+                                .text_color(color)
+                                .child(format!("{} → {}", self.key_labels.display(first), self.key_labels.display(second)))
+                        )
+                        .child(
+                            div()
+                                .flex_1()
+                                .h_2()
+                                .rounded_full()
+                                .bg(rgb(self.theme.raised))
+                                .child(div().h_full().w(relative(share)).rounded_full().bg(color))
+                        )
+                        .child(div().w_16().text_color(rgb(self.theme.blue)).child(format_count(*count)))
+                }))
+            })
+    }
+    
+    fn render_privacy_banner(&self) -> Div {
+        div()
+            .mb_2()
+            .px_3()
+            .py_2()
+            .rounded_lg()
+            .bg(rgb(self.theme.raised))
+            .border_1()
+            .border_color(rgb(self.theme.yellow))
+            .text_xs()
+            .text_color(rgb(self.theme.yellow))
+            .child("🔒 Privacy mode is on: key presses are only counted per category (letters, digits, symbols, whitespace, navigation), not per key.")
+    }
+    
+    fn render_scheme_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(HeatColorScheme::ALL.iter().enumerate().map(|(i, scheme)| {
+                let scheme = *scheme;
+                self.render_toggle(("heat-scheme", i), scheme.label(), scheme == self.heat_scheme)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.heat_scheme = scheme;
+                        cx.notify();
+                    }))
+            }))
+            // Cycles through the color scales
+            .child(
+                self.render_toggle("heat-scale", &format!("Scale: {}", self.heat_scale.label()), self.heat_scale != HeatScale::Linear)
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.heat_scale = this.heat_scale.next();
+                        let id = this.heat_scale.id();
+                        this.remember(cx, |prefs| prefs.heatmap_scale = Some(id.to_string()));
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    fn render_cursor_grid_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(CursorGrid::ALL.iter().enumerate().map(|(i, grid)| {
+                let grid = *grid;
+                self.render_toggle(("cursor-grid", i), grid.label(), grid == self.cursor_grid)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.cursor_grid = grid;
+                        cx.notify();
+                    }))
+            }))
+    }
+    
+    /// Search box filtering the Top Keys list by key name as it is typed;
+    /// Escape clears it
+    fn render_top_keys_search(&self, window: &Window, cx: &mut Context<Self>) -> Stateful<Div> {
+        let focused = self.top_keys_search_focus.is_focused(window);
+        let (text, color) = if self.top_keys_query.is_empty() && !focused {
+            ("🔍 Search keys".to_string(), self.theme.text_faint)
+        } else {
+            (format!("🔍 {}{}", self.top_keys_query, if focused { "▏" } else { "" }), self.theme.text)
+        };
+        
+        div()
+            .id("top-keys-search")
+            .track_focus(&self.top_keys_search_focus)
+            .mb_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .text_xs()
+            .bg(rgb(self.theme.inset))
+            .border_1()
+            .border_color(rgb(if focused { self.theme.blue } else { self.theme.border }))
+            .cursor_text()
+            .text_color(rgb(color))
+            .child(text)
+            .on_click(cx.listener(|this, _ev, window, cx| {
+                window.focus(&this.top_keys_search_focus);
+                cx.notify();
+            }))
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                let keystroke = &event.keystroke;
+                match keystroke.key.as_str() {
+                    "backspace" => {
+                        this.top_keys_query.pop();
+                    }
+                    "escape" => {
+                        this.top_keys_query.clear();
+                        window.focus(&this.focus_handle);
+                    }
+                    _ if keystroke.modifiers.control || keystroke.modifiers.platform => return,
+                    _ => match keystroke.key_char.as_deref() {
+                        Some(typed) if !typed.chars().any(char::is_control) => this.top_keys_query.push_str(typed),
+                        _ => return,
+                    },
+                }
+                this.top_scroll.set_offset(point(px(0.), px(0.)));
+                cx.notify();
+            }))
+    }
+    
+    fn render_category_filter(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .flex_wrap()
+            .gap_1()
+            .mb_3()
+            .children(KeyCategory::ALL.iter().enumerate().map(|(i, category)| {
+                let category = *category;
+                self.render_toggle(("top-keys-filter", i), category.label(), category == self.top_keys_filter)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.top_keys_filter = category;
+                        this.top_scroll.set_offset(point(px(0.), px(0.)));
+                        cx.notify();
+                    }))
+            }))
+    }
+    
+    fn render_series_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(ChartSeries::ALL.iter().enumerate().map(|(i, series)| {
+                let series = *series;
+                self.render_toggle(("activity-series", i), series.label(), series == self.activity_series)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.activity_series = series;
+                        cx.notify();
+                    }))
+            }))
+            .child(
+                self.render_toggle("activity-running-total", "Σ Total", self.show_running_total)
+                    .ml_2()
+                    .on_click(cx.listener(|this, _ev, _window, cx| {
+                        this.show_running_total = !this.show_running_total;
+                        cx.notify();
+                    }))
+            )
+    }
+    
+    fn activity_chart(&self, stats: &Stats) -> HourlyChart {
+        let keys = stats.today_hourly_keys();
+        let clicks = stats.today_hourly_clicks();
+        let chart = match self.activity_series {
+            ChartSeries::Keys => HourlyChart::new(keys),
+            ChartSeries::Clicks => HourlyChart::new(clicks).color(rgb(self.theme.purple)),
+            ChartSeries::Combined => HourlyChart::new(keys).overlay(clicks, rgb(self.theme.purple)),
+        };
+        let chart = chart.running_total(self.show_running_total).theme(self.theme);
+        // The default palette keeps the series colors
+        if self.heat_scheme == HeatColorScheme::Classic {
+            chart
+        } else {
+            chart.palette(self.heat_scheme)
+        }
+    }
+    
+    fn render_period_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(HeatmapPeriod::ALL.iter().enumerate().map(|(i, period)| {
+                let period = *period;
+                self.render_toggle(("heatmap-period", i), period.label(), period == self.heatmap_period)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.heatmap_period = period;
+                        cx.notify();
+                    }))
+            }))
+    }
+    
+    fn render_display_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(HeatmapDisplay::ALL.iter().enumerate().map(|(i, display)| {
+                let display = *display;
+                self.render_toggle(("heatmap-display", i), display.label(), display == self.heatmap_display)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.heatmap_display = display;
+                        cx.notify();
+                    }))
+            }))
+    }
+    
+    fn render_layout_selector(&self, cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .children(KeyboardLayout::ALL.iter().enumerate().filter(|(_, layout)| {
+                **layout != KeyboardLayout::Custom || self.custom_layout.is_some()
+            }).map(|(i, layout)| {
+                let layout = *layout;
+                self.render_toggle(("keyboard-layout", i), layout.label(), layout == self.keyboard_layout)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.keyboard_layout = layout;
+                        this.remember(cx, |prefs| prefs.heatmap_layout = Some(layout.id().to_string()));
+                        cx.notify();
+                    }))
+            }))
+            // A custom layout defines all of its keys itself
+            .when(self.keyboard_layout != KeyboardLayout::Custom, |this| {
+                this.child(div().w_2())
+                    .children(KeyboardSize::ALL.iter().enumerate().map(|(i, size)| {
+                        let size = *size;
+                        self.render_toggle(("keyboard-size", i), size.label(), size == self.keyboard_size)
+                            .on_click(cx.listener(move |this, _ev, _window, cx| {
+                                this.keyboard_size = size;
+                                this.remember(cx, |prefs| prefs.heatmap_size = Some(size.id().to_string()));
+                                cx.notify();
+                            }))
+                    }))
+            })
+    }
+    
+    /// Small pill button used for mode selectors
+    fn render_toggle(&self, id: impl Into<ElementId>, label: &str, active: bool) -> Stateful<Div> {
+        let (bg, text) = if active {
+            (rgb(self.theme.blue), rgb(self.theme.surface))
+        } else {
+            (rgb(self.theme.raised), rgb(self.theme.text_dim))
+        };
+        
+        div()
+            .id(id.into())
+            .px_2()
+            .py_px()
+            .rounded_md()
+            .text_xs()
+            .bg(bg)
+            .text_color(text)
+            .cursor_pointer()
+            .hover(|s| s.border_color(rgb(self.theme.blue)))
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .child(label.to_string())
+    }
+    
+    fn render_stat_card(&self, label: &str, value: &str, icon: &str, accent_color: Hsla) -> Div {
+        div()
+            .flex_1()
+            .min_w_40()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .shadow_sm()
+            .hover(|s| s.border_color(accent_color).bg(rgb(self.theme.surface_hover)).shadow_md())
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_lg().child(icon.to_string()))
+                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(label.to_string()))
+            )
+            .child(
+                div()
+                    .text_2xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(accent_color)
+                    .child(value.to_string())
+            )
+    }
+    
+    /// Change versus yesterday shown under a card value, e.g. "▲ 12% vs yesterday"
+    fn render_delta(&self, today: f64, yesterday: f64) -> Div {
+        let (text, color) = match percent_change(today, yesterday) {
+            Some(change) if change >= 0.0 => (format!("▲ {:.0}% vs yesterday", change), rgb(self.theme.green)),
+            Some(change) => (format!("▼ {:.0}% vs yesterday", -change), rgb(self.theme.red)),
+            None if today > 0.0 => ("new".to_string(), rgb(self.theme.text_muted)),
+            None => (String::new(), rgb(self.theme.text_muted)),
+        };
+        
+        div()
+            .text_xs()
+            .text_color(color)
+            .child(text)
+    }
+    
+    /// Totals of one part of the week, with its share of all key presses
+    fn render_week_part_card(&self, label: &str, part: &DailyStats, other_keys: u64, icon: &str, accent_color: Hsla) -> Div {
+        let all_keys = part.total_keys + other_keys;
+        let detail = if all_keys == 0 {
+            "No history yet".to_string()
+        } else {
+            format!(
+                "{:.0}% of keys · {} clicks · {:.2} km",
+                part.total_keys as f64 / all_keys as f64 * 100.0,
+                format_count(part.total_clicks),
+                part.total_distance / 1_000_000.0
+            )
+        };
+        self.render_stat_card(label, &format!("{} keys", format_count(part.total_keys)), icon, accent_color)
+            .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(detail))
+    }
+    
+    fn render_stat_card_small(&self, label: &str, value: &str, accent_color: Hsla) -> Div {
+        div()
+            .flex_1()
+            .bg(rgb(self.theme.surface))
+            .rounded_lg()
+            .p_3()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .shadow_sm()
+            .flex()
+            .items_center()
+            .justify_between()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(self.theme.text_muted))
+                    .child(label.to_string())
             )
             .child(
                 div()
@@ -566,12 +2586,37 @@ impl Dashboard {
             )
     }
     
-    fn render_top_key_item(&self, rank: usize, key: &str, count: u64) -> Div {
+    /// − n + control choosing how many keys the Top Keys list shows
+    fn render_top_keys_count(&self, cx: &mut Context<Self>) -> Div {
+        let stepper = |id: &'static str, label: &str, up: bool| {
+            self.render_toggle(id, label, false)
+                .on_click(cx.listener(move |this, _ev, _window, cx| {
+                    this.top_keys_count = settings::step(TOP_KEYS_COUNTS, this.top_keys_count, up);
+                    let count = this.top_keys_count;
+                    this.remember(cx, |prefs| prefs.top_keys_count = Some(count));
+                    cx.notify();
+                }))
+        };
+        
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .text_xs()
+            .font_weight(FontWeight::NORMAL)
+            .text_color(rgb(self.theme.text_muted))
+            .child(stepper("top-keys-fewer", "−", false))
+            .child(div().w_8().text_center().child(self.top_keys_count.to_string()))
+            .child(stepper("top-keys-more", "+", true))
+    }
+    
+    /// Row of the Top Keys list, with the share of all `total` key presses
+    fn render_top_key_item(&self, rank: usize, key: &str, count: u64, total: u64) -> Div {
         let rank_color = match rank {
             1 => rgb(0xffd700),
             2 => rgb(0xc0c0c0),
             3 => rgb(0xcd7f32),
-            _ => rgb(0x565f89),
+            _ => rgb(self.theme.text_muted),
         };
         
         div()
@@ -581,7 +2626,7 @@ impl Dashboard {
             .py_1()
             .px_2()
             .rounded_md()
-            .hover(|s| s.bg(rgb(0x292e42)))
+            .hover(|s| s.bg(rgb(self.theme.raised_hover)))
             .child(
                 div()
                     .w_5()
@@ -594,7 +2639,7 @@ impl Dashboard {
                 div()
                     .px_2()
                     .py_px()
-                    .bg(rgb(0x24283b))
+                    .bg(rgb(self.theme.raised))
                     .rounded_sm()
                     .text_xs()
                     .font_weight(FontWeight::MEDIUM)
@@ -606,19 +2651,68 @@ impl Dashboard {
             .child(
                 div()
                     .text_xs()
-                    .text_color(rgb(0x7aa2f7))
-                    .child(format!("{}", count))
+                    .text_color(rgb(self.theme.text_muted))
+                    .child(format!("{:.1}%", count as f64 / total.max(1) as f64 * 100.0))
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(self.theme.blue))
+                    .child(format_count(count))
             )
     }
     
-    fn render_mouse_card(&self, label: &str, count: u64, color: Rgba) -> Div {
+    /// Bar in the menu bar filled by the current typing intensity (0-1),
+    /// redrawn with every refresh so it pulses with the typing
+    fn render_intensity_meter(&self, intensity: f32) -> Div {
+        let color = if intensity > 0.8 {
+            rgb(self.theme.red)
+        } else if intensity > 0.5 {
+            rgb(self.theme.yellow)
+        } else {
+            rgb(self.theme.green)
+        };
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child("Intensity"))
+            .child(
+                div()
+                    .w(px(INTENSITY_METER_WIDTH))
+                    .h_2()
+                    .rounded_full()
+                    .bg(rgb(self.theme.surface))
+                    .child(
+                        div()
+                            .h_full()
+                            .w(px(INTENSITY_METER_WIDTH * intensity.clamp(0.0, 1.0)))
+                            .rounded_full()
+                            .bg(color)
+                    )
+            )
+    }
+    
+    /// Mouse card of one button, noting how many of its clicks were double or triple clicks
+    fn render_click_card(&self, stats: &Stats, button: &str, color: Rgba) -> Div {
+        let count_of = |counts: &HashMap<String, u64>| counts.get(button).copied().unwrap_or(0);
+        let (doubles, triples) = (count_of(&stats.double_clicks), count_of(&stats.triple_clicks));
+        let detail = match (doubles, triples) {
+            (0, 0) => None,
+            (doubles, 0) => Some(format!("of which {} double", doubles)),
+            (doubles, triples) => Some(format!("of which {} double · {} triple", doubles, triples)),
+        };
+        self.render_mouse_card(&format!("{} Click", button), count_of(&stats.mouse_clicks), detail, color)
+    }
+    
+    fn render_mouse_card(&self, label: &str, count: u64, detail: Option<String>, color: Rgba) -> Div {
         div()
             .flex_1()
-            .bg(rgb(0x1a1b26))
+            .bg(rgb(self.theme.surface))
             .rounded_xl()
             .p_4()
             .border_1()
-            .border_color(rgb(0x2a2a3a))
+            .border_color(rgb(self.theme.border))
             .hover(|s| s.border_color(color))
             .flex()
             .flex_col()
@@ -629,38 +2723,74 @@ impl Dashboard {
                     .text_2xl()
                     .font_weight(FontWeight::BOLD)
                     .text_color(color)
-                    .child(format!("{}", count))
+                    .child(format_count(count))
             )
             .child(
                 div()
                     .text_sm()
-                    .text_color(rgb(0x565f89))
+                    .text_color(rgb(self.theme.text_muted))
                     .child(label.to_string())
             )
+            .when_some(detail, |card, detail| {
+                card.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(self.theme.text_faint))
+                        .child(detail)
+                )
+            })
     }
 
     
-    fn render_scrollbar(&self, _handle: &ScrollHandle) -> Div {
-        // Simple scrollbar track indicator
-        // Note: GPUI's flex_grow() doesn't take percentage arguments,
-        // so we show a static scrollbar indicator
-        div()
-            .absolute()
-            .top_0()
-            .right_0()
-            .bottom_0()
-            .w_2()
-            .bg(rgb(0x1a1b26)) // Dark track
-            .rounded_full()
-            .child(
-                div()
-                    .w_full()
-                    .h_8() // Fixed height thumb
-                    .mt_2()
-                    .bg(rgb(0x3b3b4f))
-                    .rounded_full()
-                    .hover(|s| s.bg(rgb(0x565f89)))
-            )
+    /// Scrollbar along the right edge of the container scrolled by `handle`,
+    /// with a thumb that can be dragged and a track that pages when clicked;
+    /// `None` while the content fits
+    fn render_scrollbar(&self, handle: &ScrollHandle, cx: &mut Context<Self>) -> Option<Div> {
+        let metrics = scroll_metrics(handle);
+        if metrics.fits() {
+            return None;
+        }
+        let (top, height) = metrics.thumb();
+        let dragging = self.scrollbar_drag.as_ref().is_some_and(|(dragged, _, _)| dragged.bounds() == handle.bounds());
+        
+        Some(
+            div()
+                .absolute()
+                .top_0()
+                .right_0()
+                .bottom_0()
+                .w_2()
+                .bg(rgb(self.theme.surface)) // Track
+                .rounded_full()
+                .on_mouse_down(MouseButton::Left, cx.listener({
+                    let handle = handle.clone();
+                    move |_this, event: &MouseDownEvent, _window, cx| {
+                        let metrics = scroll_metrics(&handle);
+                        let offset = metrics.paged(f32::from(event.position.y - handle.bounds().top()));
+                        handle.set_offset(point(handle.offset().x, px(-offset)));
+                        cx.stop_propagation();
+                        cx.notify();
+                    }
+                }))
+                .child(
+                    div()
+                        .absolute()
+                        .top(px(top))
+                        .h(px(height))
+                        .w_full()
+                        .bg(rgb(if dragging { self.theme.text_muted } else { self.theme.border_strong }))
+                        .rounded_full()
+                        .hover(|s| s.bg(rgb(self.theme.text_muted)))
+                        .on_mouse_down(MouseButton::Left, cx.listener({
+                            let handle = handle.clone();
+                            move |this, event: &MouseDownEvent, _window, cx| {
+                                this.scrollbar_drag = Some((handle.clone(), event.position.y, scroll_metrics(&handle).offset));
+                                cx.stop_propagation();
+                                cx.notify();
+                            }
+                        }))
+                )
+        )
     }
 }
 
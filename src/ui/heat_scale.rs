@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Mapping from a key's press count to its heat intensity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatScale {
+    /// Proportional to the count of the most used key
+    #[default]
+    Linear,
+    /// `ln(count + 1) / ln(max + 1)`, so a few dominant keys do not wash out the rest
+    Log,
+    /// Rank of the count among all used keys
+    Percentile,
+}
+
+impl HeatScale {
+    pub const ALL: [HeatScale; 3] = [HeatScale::Linear, HeatScale::Log, HeatScale::Percentile];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatScale::Linear => "Linear",
+            HeatScale::Log => "Log",
+            HeatScale::Percentile => "Rank",
+        }
+    }
+    
+    /// Name used in the stats file
+    pub fn id(&self) -> &'static str {
+        match self {
+            HeatScale::Linear => "linear",
+            HeatScale::Log => "log",
+            HeatScale::Percentile => "percentile",
+        }
+    }
+    
+    /// The mode after this one, for a cycling toggle
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|scale| scale == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+    
+    /// Intensity in 0.0..=1.0 of `count`, given the counts of all used keys
+    /// sorted in ascending order
+    pub fn intensity(&self, count: u64, sorted_counts: &[u64]) -> f32 {
+        let max = sorted_counts.last().copied().unwrap_or(0);
+        if count == 0 || max == 0 {
+            return 0.0;
+        }
+        let intensity = match self {
+            HeatScale::Linear => count as f64 / max as f64,
+            HeatScale::Log => (count as f64 + 1.0).ln() / (max as f64 + 1.0).ln(),
+            HeatScale::Percentile => {
+                // Keys with equal counts share a rank
+                let below = sorted_counts.partition_point(|c| *c < count);
+                (below + 1) as f64 / sorted_counts.len() as f64
+            }
+        };
+        (intensity as f32).min(1.0)
+    }
+    
+    /// Smallest count that reaches `intensity`, roughly the inverse of
+    /// [`HeatScale::intensity`]; used to label the legend
+    pub fn count_at(&self, intensity: f32, sorted_counts: &[u64]) -> u64 {
+        let max = sorted_counts.last().copied().unwrap_or(0);
+        let intensity = intensity.clamp(0.0, 1.0) as f64;
+        // Rounding up, with some slack so that exp(ln(x)) still lands on x
+        let round_up = |value: f64| (value - 1e-6).ceil() as u64;
+        let count = match self {
+            HeatScale::Linear => round_up(intensity * max as f64),
+            HeatScale::Log => round_up((intensity * (max as f64 + 1.0).ln()).exp() - 1.0),
+            HeatScale::Percentile => {
+                let rank = (intensity * sorted_counts.len() as f64).ceil() as usize;
+                sorted_counts.get(rank.saturating_sub(1)).copied().unwrap_or(0)
+            }
+        };
+        count.clamp(1, max.max(1))
+    }
+}
+
+impl FromStr for HeatScale {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HeatScale::ALL
+            .into_iter()
+            .find(|scale| scale.id() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown heat scale: {}", s))
+    }
+}
+
+/// Palette mapping a heat intensity to a color, for the heatmaps and charts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatColorScheme {
+    /// Blue → cyan → amber → red
+    #[default]
+    Classic,
+    #[serde(alias = "gray")]
+    Grayscale,
+    /// Dark purple → teal → yellow, evenly bright steps that also read with
+    /// color vision deficiencies
+    Viridis,
+    Thermal,
+}
+
+impl HeatColorScheme {
+    pub const ALL: [HeatColorScheme; 4] = [
+        HeatColorScheme::Classic,
+        HeatColorScheme::Grayscale,
+        HeatColorScheme::Viridis,
+        HeatColorScheme::Thermal,
+    ];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatColorScheme::Classic => "Classic",
+            HeatColorScheme::Grayscale => "Gray",
+            HeatColorScheme::Viridis => "Viridis",
+            HeatColorScheme::Thermal => "Thermal",
+        }
+    }
+    
+    /// Name used in the config file
+    pub fn id(&self) -> &'static str {
+        match self {
+            HeatColorScheme::Classic => "classic",
+            HeatColorScheme::Grayscale => "grayscale",
+            HeatColorScheme::Viridis => "viridis",
+            HeatColorScheme::Thermal => "thermal",
+        }
+    }
+    
+    /// Gradient stops from lowest to highest usage, as 0xRRGGBB values
+    fn stops(&self) -> &'static [u32] {
+        match self {
+            HeatColorScheme::Classic => &[0x4a6aa8, 0x4ab8a8, 0xe0b050, 0xe07050],
+            HeatColorScheme::Grayscale => &[0x4a4a58, 0x8a8a98, 0xf0f0f0],
+            // matplotlib's viridis sampled at 0, 1/8, ..., 1
+            HeatColorScheme::Viridis => &[
+                0x440154, 0x472d7b, 0x3b528b, 0x2c728e, 0x21918c, 0x28ae80, 0x5ec962, 0xaddc30, 0xfde725,
+            ],
+            HeatColorScheme::Thermal => &[0x1f1f8f, 0x8f1f8f, 0xe0402a, 0xf0a020, 0xfff0a0],
+        }
+    }
+    
+    /// Red, green and blue (0-1) for an intensity in 0.0..=1.0, interpolated
+    /// linearly between the stops so that the colors change continuously
+    pub fn color_at(&self, intensity: f32) -> [f32; 3] {
+        let stops = self.stops();
+        let position = intensity.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - index as f32;
+        let channel = |color: u32, shift: u32| ((color >> shift) & 0xff) as f32 / 255.0;
+        [16, 8, 0].map(|shift| {
+            let (from, to) = (channel(stops[index], shift), channel(stops[index + 1], shift));
+            from + (to - from) * t
+        })
+    }
+}
+
+impl FromStr for HeatColorScheme {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HeatColorScheme::ALL
+            .into_iter()
+            .find(|scheme| scheme.id() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown heatmap palette: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two dominant keys and a long tail, like Space and E against the rest
+    const COUNTS: [u64; 6] = [1, 9, 99, 99, 999, 9999];
+
+    fn intensities(scale: HeatScale) -> Vec<f32> {
+        COUNTS.iter().map(|count| scale.intensity(*count, &COUNTS)).collect()
+    }
+
+    #[test]
+    fn linear_is_relative_to_max() {
+        let linear = intensities(HeatScale::Linear);
+        assert_eq!(linear[5], 1.0);
+        assert!((linear[4] - 0.0999).abs() < 1e-4);
+        assert!(linear[0] < 0.01);
+    }
+
+    #[test]
+    fn log_spreads_the_tail() {
+        let log = intensities(HeatScale::Log);
+        for (value, expected) in log.iter().zip([0.075257, 0.25, 0.5, 0.5, 0.75, 1.0]) {
+            assert!((value - expected).abs() < 1e-4, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn percentile_ranks_keys() {
+        let ranks = intensities(HeatScale::Percentile);
+        let sixths: Vec<f32> = [1.0, 2.0, 3.0, 3.0, 5.0, 6.0].iter().map(|n| n / 6.0).collect();
+        assert_eq!(ranks, sixths);
+    }
+
+    #[test]
+    fn legend_bounds_invert_intensity() {
+        assert_eq!(HeatScale::Linear.count_at(0.5, &COUNTS), 5000);
+        assert_eq!(HeatScale::Log.count_at(0.5, &COUNTS), 99);
+        assert_eq!(HeatScale::Percentile.count_at(0.5, &COUNTS), 99);
+        for scale in HeatScale::ALL {
+            assert_eq!(scale.count_at(0.0, &COUNTS), 1);
+            assert_eq!(scale.count_at(1.0, &COUNTS), 9999);
+            assert_eq!(scale.count_at(0.5, &[]), 1);
+        }
+    }
+
+    fn hex(color: [f32; 3]) -> u32 {
+        color.iter().fold(0, |hex, channel| hex << 8 | (channel * 255.0).round() as u32)
+    }
+
+    #[test]
+    fn palettes_span_their_stops() {
+        assert_eq!(hex(HeatColorScheme::Viridis.color_at(0.0)), 0x440154);
+        assert_eq!(hex(HeatColorScheme::Viridis.color_at(1.0)), 0xfde725);
+        assert_eq!(hex(HeatColorScheme::Viridis.color_at(0.5)), 0x21918c);
+        // Out of range intensities are clamped
+        assert_eq!(hex(HeatColorScheme::Viridis.color_at(-1.0)), 0x440154);
+        assert_eq!(hex(HeatColorScheme::Viridis.color_at(2.0)), 0xfde725);
+        for scheme in HeatColorScheme::ALL {
+            assert_eq!(hex(scheme.color_at(0.0)), scheme.stops()[0]);
+            assert_eq!(hex(scheme.color_at(1.0)), *scheme.stops().last().unwrap());
+            assert_eq!(scheme.id().parse(), Ok(scheme));
+        }
+    }
+
+    #[test]
+    fn palettes_are_continuous() {
+        // Halfway between the first two Classic stops, 0x4a6aa8 and 0x4ab8a8
+        assert_eq!(hex(HeatColorScheme::Classic.color_at(1.0 / 6.0)), 0x4a91a8);
+        let steps: Vec<_> = (0..=100).map(|i| HeatColorScheme::Viridis.color_at(i as f32 / 100.0)).collect();
+        for pair in steps.windows(2) {
+            let jump = pair[0].iter().zip(pair[1]).map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+            assert!(jump < 0.1, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn unused_keys_are_cold() {
+        for scale in HeatScale::ALL {
+            assert_eq!(scale.intensity(0, &COUNTS), 0.0);
+            assert_eq!(scale.intensity(5, &[]), 0.0);
+            assert_eq!(scale.next().next().next(), scale);
+            assert_eq!(scale.id().parse(), Ok(scale));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Name shown for `key` on this platform when the config sets none
+fn default_label(key: &str) -> Option<&'static str> {
+    match key {
+        "Meta" if cfg!(target_os = "macos") => Some("Cmd"),
+        "Meta" => Some("Win"),
+        "Alt" if cfg!(target_os = "macos") => Some("Opt"),
+        _ => None,
+    }
+}
+
+/// Names keys are shown under in the heatmap, the Top Keys list and the key
+/// sequences, from the `[key_labels]` table of the config on top of platform
+/// defaults such as "Cmd" for Meta on macOS.
+///
+/// Unlike `KeyAliases`, labels only change what is displayed: the stats keep
+/// counting keys under their recorded names.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(from = "HashMap<String, String>", into = "HashMap<String, String>")]
+pub struct KeyLabels {
+    custom: HashMap<String, String>,
+}
+
+impl KeyLabels {
+    /// Label of `key`, or `None` to show its recorded name
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.custom.get(key).map(String::as_str).or_else(|| default_label(key))
+    }
+    
+    /// Name `key` is shown under
+    pub fn display<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get(key).unwrap_or(key)
+    }
+}
+
+impl From<HashMap<String, String>> for KeyLabels {
+    fn from(custom: HashMap<String, String>) -> Self {
+        Self { custom }
+    }
+}
+
+impl From<KeyLabels> for HashMap<String, String> {
+    fn from(labels: KeyLabels) -> Self {
+        labels.custom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> KeyLabels {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<HashMap<_, _>>().into()
+    }
+
+    #[test]
+    fn platform_defaults() {
+        let labels = KeyLabels::default();
+        assert_eq!(labels.display("Meta"), if cfg!(target_os = "macos") { "Cmd" } else { "Win" });
+        assert_eq!(labels.display("A"), "A");
+        assert_eq!(labels.get("Space"), None);
+    }
+
+    #[test]
+    fn custom_labels_win() {
+        let labels = labels(&[("`", "Backtick"), ("Meta", "Super")]);
+        assert_eq!(labels.display("`"), "Backtick");
+        assert_eq!(labels.display("Meta"), "Super");
+        // Labels are not followed like aliases
+        let labels = self::labels(&[("A", "B"), ("B", "C")]);
+        assert_eq!(labels.display("A"), "B");
+    }
+}
@@ -1,99 +1,311 @@
 use gpui::*;
 use gpui::prelude::FluentBuilder;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
-/// Keyboard layout for QWERTY
-const KEYBOARD_ROWS: &[&[&str]] = &[
-    &["`", "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "=", "Backspace"],
-    &["Tab", "Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P", "[", "]", "\\"],
-    &["CapsLock", "A", "S", "D", "F", "G", "H", "J", "K", "L", ";", "'", "Enter"],
-    &["Shift", "Z", "X", "C", "V", "B", "N", "M", ",", ".", "/", "Shift"],
-    &["Ctrl", "Meta", "Alt", "Space", "Alt", "Meta", "Ctrl"],
-];
-
-/// Key widths in units (1 unit = standard key width)
-fn get_key_width(key: &str) -> f32 {
-    match key {
-        "Backspace" => 2.0,
-        "Tab" => 1.5,
-        "\\" => 1.5,
-        "CapsLock" => 1.75,
-        "Enter" => 2.25,
-        "Shift" => 2.25,
-        "Ctrl" | "Meta" | "Alt" => 1.25,
-        "Space" => 6.25,
-        _ => 1.0,
+use crate::format::format_compact;
+use super::heat_scale::{HeatColorScheme, HeatScale};
+use super::key_labels::KeyLabels;
+use super::keyboard_layout::{KeyboardLayout, KeyboardSize, KeySlot, KEY_SCALE_RANGE};
+use super::theme::Theme;
+
+/// Width of a 1u key, its height and the margin around each key, in pixels
+/// at a key scale of 1
+const KEY_UNIT: f32 = 38.0;
+const KEY_HEIGHT: f32 = 36.0;
+const KEY_MARGIN: f32 = 2.0;
+
+/// Label size at a key scale of 1, and the smallest it shrinks to
+const KEY_TEXT_SIZE: f32 = 12.0;
+const MIN_KEY_TEXT_SIZE: f32 = 8.0;
+
+/// Horizontal space taken by the keyboard case and plate around the key
+/// blocks (padding and borders), and between two blocks
+const CASE_WIDTH: f32 = 2.0 * 12.0 + 2.0 + 2.0 * 8.0 + 2.0;
+const BLOCK_GAP: f32 = 12.0;
+
+/// How long a key pressed in the window flashes, fading out
+pub const PRESS_FLASH: Duration = Duration::from_millis(200);
+
+/// Number of color buckets in the legend under the keyboard
+const LEGEND_BUCKETS: usize = 5;
+
+/// What the label under each key shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatmapDisplay {
+    /// Absolute number of presses
+    #[default]
+    Count,
+    /// Share of all key presses
+    Percent,
+}
+
+impl HeatmapDisplay {
+    pub const ALL: [HeatmapDisplay; 2] = [HeatmapDisplay::Count, HeatmapDisplay::Percent];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatmapDisplay::Count => "Count",
+            HeatmapDisplay::Percent => "%",
+        }
+    }
+}
+
+/// Which key presses the heatmap is colored by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeatmapPeriod {
+    /// Every press since recording started
+    #[default]
+    AllTime,
+    /// Presses weighted by recency, so keys no longer used cool down
+    Recent,
+    /// Presses since the app started
+    Session,
+}
+
+impl HeatmapPeriod {
+    pub const ALL: [HeatmapPeriod; 3] = [HeatmapPeriod::AllTime, HeatmapPeriod::Recent, HeatmapPeriod::Session];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            HeatmapPeriod::AllTime => "All Time",
+            HeatmapPeriod::Recent => "Recent",
+            HeatmapPeriod::Session => "Session",
+        }
+    }
+}
+
+impl HeatColorScheme {
+    /// Face color for a usage intensity in 0.0..=1.0
+    pub fn face_color(&self, intensity: f32) -> Rgba {
+        let [r, g, b] = self.color_at(intensity);
+        Rgba { r, g, b, a: 1.0 }
+    }
+}
+
+/// Linear interpolation between two colors
+fn lerp(from: Rgba, to: Rgba, t: f32) -> Rgba {
+    Rgba {
+        r: from.r + (to.r - from.r) * t,
+        g: from.g + (to.g - from.g) * t,
+        b: from.b + (to.b - from.b) * t,
+        a: from.a + (to.a - from.a) * t,
+    }
+}
+
+/// Lighten (positive) or darken (negative) a color by a fixed amount per channel
+fn shade(color: Rgba, amount: f32) -> Rgba {
+    Rgba {
+        r: (color.r + amount).clamp(0.0, 1.0),
+        g: (color.g + amount).clamp(0.0, 1.0),
+        b: (color.b + amount).clamp(0.0, 1.0),
+        a: color.a,
     }
 }
 
 /// Keyboard heatmap component with realistic key styling
 pub struct KeyboardHeatmap {
     key_counts: HashMap<String, u64>,
-    max_count: u64,
+    /// Counts of all used keys, ascending, for the heat scale
+    sorted_counts: Vec<u64>,
+    total_count: u64,
+    scheme: HeatColorScheme,
+    scale: HeatScale,
+    display: HeatmapDisplay,
+    layout: KeyboardLayout,
+    size: KeyboardSize,
+    custom_rows: Option<Vec<Vec<KeySlot>>>,
+    /// Factor applied to key sizes, margins and labels
+    key_scale: f32,
+    theme: Theme,
+    /// Key last pressed while the window had focus, and when
+    last_press: Option<(String, Instant)>,
+    key_labels: KeyLabels,
 }
 
 impl KeyboardHeatmap {
-    pub fn new(key_counts: HashMap<String, u64>) -> Self {
-        let max_count = key_counts.values().copied().max().unwrap_or(1);
-        Self { key_counts, max_count }
+    pub fn new(key_counts: HashMap<String, u64>, scheme: HeatColorScheme, display: HeatmapDisplay) -> Self {
+        let mut sorted_counts: Vec<u64> = key_counts.values().copied().filter(|c| *c > 0).collect();
+        sorted_counts.sort_unstable();
+        let total_count = key_counts.values().sum();
+        Self {
+            key_counts,
+            sorted_counts,
+            total_count,
+            scheme,
+            scale: HeatScale::default(),
+            display,
+            layout: KeyboardLayout::default(),
+            size: KeyboardSize::default(),
+            custom_rows: None,
+            key_scale: 1.0,
+            theme: Theme::default(),
+            last_press: None,
+            key_labels: KeyLabels::default(),
+        }
+    }
+    
+    /// Show keys under their configured labels
+    pub fn key_labels(mut self, labels: KeyLabels) -> Self {
+        self.key_labels = labels;
+        self
+    }
+    
+    /// Set the colors of the case, plate and unused keys
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+    
+    /// Flash the key pressed at the given time until `PRESS_FLASH` has passed
+    pub fn last_press(mut self, press: Option<(String, Instant)>) -> Self {
+        self.last_press = press;
+        self
+    }
+    
+    /// Strength of the press flash on `key`: 1 when just pressed, fading to 0
+    fn press_flash(&self, key: &str) -> f32 {
+        match &self.last_press {
+            Some((pressed, at)) if pressed == key => 1.0 - (at.elapsed().as_secs_f32() / PRESS_FLASH.as_secs_f32()).min(1.0),
+            _ => 0.0,
+        }
+    }
+    
+    /// Draw the keys `factor` times their default size
+    pub fn key_scale(mut self, factor: f32) -> Self {
+        self.key_scale = factor;
+        self
+    }
+    
+    /// Scale the keys so the keyboard fills `available` width, within
+    /// `KEY_SCALE_RANGE`. Call after choosing the layout and size.
+    pub fn fit_width(mut self, available: Pixels) -> Self {
+        let blocks = self.blocks();
+        let keys_width: f32 = blocks.iter().map(|(rows, _)| Self::block_width(rows)).sum();
+        let chrome = CASE_WIDTH + BLOCK_GAP * blocks.len().saturating_sub(1) as f32;
+        let (min, max) = KEY_SCALE_RANGE;
+        if keys_width > 0.0 {
+            self.key_scale = ((f32::from(available) - chrome) / keys_width).clamp(min, max);
+        }
+        self
+    }
+    
+    /// Set how counts map to heat colors
+    pub fn scale(mut self, scale: HeatScale) -> Self {
+        self.scale = scale;
+        self
+    }
+    
+    /// Set the physical layout to draw
+    pub fn layout(mut self, layout: KeyboardLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+    
+    /// Rows drawn for `KeyboardLayout::Custom`, replacing all built-in blocks
+    pub fn custom_layout(mut self, rows: Option<Vec<Vec<KeySlot>>>) -> Self {
+        self.custom_rows = rows;
+        self
+    }
+    
+    /// Set the form factor, i.e. which blocks surround the main keys
+    pub fn size(mut self, size: KeyboardSize) -> Self {
+        self.size = size;
+        self
     }
     
     /// Get heat color based on key usage intensity
     fn heat_color(&self, key: &str) -> (Rgba, Rgba, Rgba) {
         let count = self.key_counts.get(key).copied().unwrap_or(0);
-        let intensity = if self.max_count > 0 {
-            (count as f32 / self.max_count as f32).min(1.0)
-        } else {
-            0.0
-        };
+        let intensity = self.scale.intensity(count, &self.sorted_counts);
         
         // Returns (top_color, face_color, shadow_color)
         if intensity < 0.01 {
-            // Not used - dark gray with 3D effect
-            (rgb(0x3a3a4a), rgb(0x2a2a3a), rgb(0x1a1a2a))
-        } else if intensity < 0.25 {
-            // Low usage - blue
-            (rgb(0x5b7bb8), rgb(0x4a6aa8), rgb(0x3a5a98))
-        } else if intensity < 0.5 {
-            // Medium usage - cyan/teal
-            (rgb(0x5bc8b8), rgb(0x4ab8a8), rgb(0x3aa898))
-        } else if intensity < 0.75 {
-            // High usage - yellow/amber
-            (rgb(0xf0c060), rgb(0xe0b050), rgb(0xd0a040))
+            // Not used - plain key with 3D effect
+            (rgb(self.theme.key_top), rgb(self.theme.key_face), rgb(self.theme.key_shadow))
         } else {
-            // Very high usage - orange/red
-            (rgb(0xf08060), rgb(0xe07050), rgb(0xd06040))
+            let face = self.scheme.face_color(intensity);
+            (shade(face, 0.0625), face, shade(face, -0.0625))
         }
     }
     
-    fn render_key(&self, key: &str) -> impl IntoElement {
-        let width = get_key_width(key);
-        let count = self.key_counts.get(key).copied().unwrap_or(0);
+    /// Text shown under the key label
+    fn count_label(&self, count: u64) -> String {
+        match self.display {
+            HeatmapDisplay::Count => format_compact(count),
+            HeatmapDisplay::Percent if self.total_count == 0 => "0%".to_string(),
+            HeatmapDisplay::Percent => {
+                let percent = count as f64 / self.total_count as f64 * 100.0;
+                if percent < 0.1 {
+                    "<0.1%".to_string()
+                } else {
+                    format!("{:.1}%", percent)
+                }
+            }
+        }
+    }
+    
+    /// Pick a readable label color for the given key face
+    fn label_color(face: Rgba) -> Rgba {
+        let luminance = 0.299 * face.r + 0.587 * face.g + 0.114 * face.b;
+        if luminance > 0.6 {
+            rgb(0x1a1b26)
+        } else {
+            rgb(0xffffff)
+        }
+    }
+    
+    fn render_key(&self, key_slot: &KeySlot) -> AnyElement {
+        let key: &str = &key_slot.key;
+        let width = key_slot.width;
+        let key_width = px(width * KEY_UNIT * self.key_scale);
+        let key_height = px(KEY_HEIGHT * self.key_scale);
+        let margin = px(KEY_MARGIN * self.key_scale);
+        if key_slot.is_gap() {
+            return div().w(key_width).h(key_height).m(margin).into_any_element();
+        }
+        let text_size = px((KEY_TEXT_SIZE * self.key_scale).max(MIN_KEY_TEXT_SIZE));
+        // Continuation slots share the color of their key but carry no text
+        let count = if key_slot.continuation {
+            0
+        } else {
+            self.key_counts.get(key).copied().unwrap_or(0)
+        };
         let (top_color, face_color, _shadow_color) = self.heat_color(key);
+        let flash = self.press_flash(key);
+        let face_color = lerp(face_color, rgb(self.theme.cyan), flash * 0.7);
+        let label_color = Self::label_color(face_color);
         
-        let display_key = match key {
+        let label = key_slot.label.as_deref().or_else(|| self.key_labels.get(key).filter(|_| !key_slot.continuation));
+        let display_key = label.unwrap_or(match key {
             "Backspace" => "⌫",
             "Tab" => "Tab",
             "CapsLock" => "Caps",
             "Enter" => "Enter",
             "Shift" => "Shift",
             "Ctrl" => "Ctrl",
-            "Meta" => "Win",
             "Alt" => "Alt",
             "Space" => "",
-            _ => key,
-        };
-        
-        let key_width = px(width * 38.0);
-        let key_height = px(36.0);
+            "NumLock" => "Num",
+            "PrintScreen" => "PrtSc",
+            "ScrollLock" => "ScrLk",
+            "PauseBreak" => "Pause",
+            "Insert" => "Ins",
+            "Delete" => "Del",
+            "PageUp" => "PgUp",
+            "PageDown" => "PgDn",
+            "KPEnter" => "Ent",
+            _ if key_slot.continuation => "",
+            _ => key.strip_prefix("KP").unwrap_or(key),
+        });
         
         // Outer container with shadow
         div()
             .w(key_width)
             .h(key_height)
-            .m(px(2.0))
+            .m(margin)
             .rounded_md()
-            .bg(rgb(0x0a0a10)) // Deep shadow base
+            .bg(rgb(self.theme.key_shadow)) // Deep shadow base
             .shadow_md()
             // Inner key with 3D effect
             .child(
@@ -104,6 +316,7 @@ impl KeyboardHeatmap {
                     .bg(face_color)
                     .border_1()
                     .border_color(rgba(0xffffff20))
+                    .when(flash > 0.0, |this| this.border_color(rgb(self.theme.cyan)))
                     .relative()
                     // Top highlight edge
                     .child(
@@ -128,29 +341,95 @@ impl KeyboardHeatmap {
                             // Key label
                             .child(
                                 div()
-                                    .text_xs()
+                                    .text_size(text_size)
                                     .font_family("JetBrains Mono")
                                     .font_weight(FontWeight::SEMIBOLD)
-                                    .text_color(rgb(0xffffff))
+                                    .text_color(label_color)
                                     .child(display_key.to_string())
                             )
                             // Count display
                             .when(count > 0, |this: Div| {
                                 this.child(
                                     div()
-                                        .text_xs()
+                                        .text_size(text_size)
                                         .font_family("JetBrains Mono")
-                                        .text_color(rgba(0xffffffcc))
-                                        .child(if count > 999 {
-                                            format!("{}k", count / 1000)
-                                        } else {
-                                            format!("{}", count)
-                                        })
+                                        .text_color(Hsla::from(label_color).opacity(0.8))
+                                        .child(self.count_label(count))
                                 )
                             })
                     )
-                    .hover(|s| s.border_color(rgb(0x7aa2f7)).shadow_lg())
+                    .hover(|s| s.border_color(rgb(self.theme.blue)).shadow_lg())
             )
+            .into_any_element()
+    }
+}
+
+impl KeyboardHeatmap {
+    /// Key blocks from left to right, each with whether its rows are centered
+    fn blocks(&self) -> Vec<(Vec<Vec<KeySlot>>, bool)> {
+        if let Some(custom) = self.custom_rows.as_ref().filter(|_| self.layout == KeyboardLayout::Custom) {
+            return vec![(custom.clone(), true)];
+        }
+        let main: Vec<Vec<KeySlot>> = self.size.function_row()
+            .into_iter()
+            .chain(self.layout.rows())
+            .collect();
+        [Some(main), self.size.nav_rows(), self.size.numpad_rows()]
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, rows)| rows.map(|rows| (rows, i == 0)))
+            .collect()
+    }
+    
+    /// Width in pixels of the widest row of a block at a key scale of 1
+    fn block_width(rows: &[Vec<KeySlot>]) -> f32 {
+        rows.iter()
+            .map(|row| row.iter().map(|slot| slot.width * KEY_UNIT + 2.0 * KEY_MARGIN).sum::<f32>())
+            .fold(0.0, f32::max)
+    }
+    
+    /// Color buckets with the range of counts (or shares) each one covers
+    fn render_legend(&self) -> Div {
+        div()
+            .flex()
+            .justify_center()
+            .gap_3()
+            .text_xs()
+            .font_family("JetBrains Mono")
+            .text_color(rgb(self.theme.text_dim))
+            .children((0..LEGEND_BUCKETS).map(|bucket| {
+                let from = bucket as f32 / LEGEND_BUCKETS as f32;
+                let to = (bucket + 1) as f32 / LEGEND_BUCKETS as f32;
+                let low = self.scale.count_at(from, &self.sorted_counts);
+                let high = self.scale.count_at(to, &self.sorted_counts);
+                let range = if low >= high {
+                    self.count_label(high)
+                } else {
+                    format!("{}–{}", self.count_label(low), self.count_label(high))
+                };
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(div().w_3().h_3().rounded_sm().bg(self.scheme.face_color((from + to) / 2.0)))
+                    .child(range)
+            }))
+    }
+    
+    /// Render a block of key rows, centered or left-aligned
+    fn render_block(&self, rows: &[Vec<KeySlot>], centered: bool) -> Div {
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_px()
+            .children(rows.iter().map(|row| {
+                div()
+                    .flex()
+                    .when(centered, |this| this.justify_center())
+                    .when(!centered, |this| this.w_full())
+                    .children(row.iter().map(|key_slot| self.render_key(key_slot)))
+            }))
     }
 }
 
@@ -161,29 +440,23 @@ impl IntoElement for KeyboardHeatmap {
         // Keyboard base with realistic styling
         div()
             .p_3()
-            .bg(rgb(0x1a1a24))
+            .bg(rgb(self.theme.case))
             .rounded_xl()
             .border_1()
-            .border_color(rgb(0x2a2a3a))
+            .border_color(rgb(self.theme.border))
             .shadow_lg()
             // Inner keyboard plate
             .child(
                 div()
                     .p_2()
-                    .bg(rgb(0x12121a))
+                    .bg(rgb(self.theme.inset))
                     .rounded_lg()
                     .border_1()
-                    .border_color(rgb(0x252530))
+                    .border_color(rgb(self.theme.inset_border))
                     .flex()
-                    .flex_col()
-                    .items_center()
-                    .gap_px()
-                    .children(KEYBOARD_ROWS.iter().map(|row| {
-                        div()
-                            .flex()
-                            .justify_center()
-                            .children(row.iter().map(|key| self.render_key(key)))
-                    }))
+                    .gap(px(BLOCK_GAP))
+                    .children(self.blocks().iter().map(|(rows, centered)| self.render_block(rows, *centered)))
             )
+            .when(!self.sorted_counts.is_empty(), |this| this.child(div().mt_2().child(self.render_legend())))
     }
 }
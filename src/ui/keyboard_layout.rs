@@ -0,0 +1,473 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::config::Config;
+
+/// Keyboard layout for QWERTY
+const KEYBOARD_ROWS: &[&[&str]] = &[
+    &["`", "1", "2", "3", "4", "5", "6", "7", "8", "9", "0", "-", "=", "Backspace"],
+    &["Tab", "Q", "W", "E", "R", "T", "Y", "U", "I", "O", "P", "[", "]", "\\"],
+    &["CapsLock", "A", "S", "D", "F", "G", "H", "J", "K", "L", ";", "'", "Enter"],
+    &["Shift", "Z", "X", "C", "V", "B", "N", "M", ",", ".", "/", "Shift"],
+    &["Ctrl", "Meta", "Alt", "Space", "Alt", "Meta", "Ctrl"],
+];
+
+/// Key widths in units (1 unit = standard key width)
+fn get_key_width(key: &str) -> f32 {
+    match key {
+        "Backspace" => 2.0,
+        "Tab" => 1.5,
+        "\\" => 1.5,
+        "CapsLock" => 1.75,
+        "Enter" => 2.25,
+        "Shift" => 2.25,
+        "Ctrl" | "Meta" | "Alt" => 1.25,
+        "Space" => 6.25,
+        _ => 1.0,
+    }
+}
+
+/// Character row of the main block, as touch typists name them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRow {
+    Number,
+    Top,
+    Home,
+    Bottom,
+}
+
+impl KeyRow {
+    /// Rows in `KEYBOARD_ROWS` order
+    pub const ALL: [KeyRow; 4] = [KeyRow::Number, KeyRow::Top, KeyRow::Home, KeyRow::Bottom];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyRow::Number => "Number",
+            KeyRow::Top => "Top",
+            KeyRow::Home => "Home",
+            KeyRow::Bottom => "Bottom",
+        }
+    }
+    
+    /// Row of a character key on the QWERTY layout. Modifiers, Space and the
+    /// wide keys at the row ends (Tab, Enter, ...) belong to no row.
+    pub fn of(key: &str) -> Option<KeyRow> {
+        if key.chars().count() != 1 {
+            return None;
+        }
+        KEYBOARD_ROWS
+            .iter()
+            .zip(KeyRow::ALL)
+            .find(|(keys, _)| keys.contains(&key))
+            .map(|(_, row)| row)
+    }
+}
+
+/// Key presses per character row, in `KeyRow::ALL` order; other keys are left out
+pub fn row_totals(key_counts: &HashMap<String, u64>) -> [(KeyRow, u64); 4] {
+    let mut totals = KeyRow::ALL.map(|row| (row, 0));
+    for (key, count) in key_counts {
+        if let Some(row) = KeyRow::of(key) {
+            totals[row as usize].1 += count;
+        }
+    }
+    totals
+}
+
+/// A key position within a layout row; an empty `key` is a gap between groups
+#[derive(Debug, Clone)]
+pub struct KeySlot {
+    /// Key name as recorded in the statistics
+    pub key: Cow<'static, str>,
+    /// Width in units (1 unit = standard key width)
+    pub width: f32,
+    /// Lower half of a key spanning two rows (ISO Enter), drawn without a label
+    pub continuation: bool,
+    /// Text printed on the key; derived from `key` when unset
+    pub label: Option<Cow<'static, str>>,
+}
+
+const fn slot(key: &'static str, width: f32) -> KeySlot {
+    KeySlot { key: Cow::Borrowed(key), width, continuation: false, label: None }
+}
+
+const fn continuation(key: &'static str, width: f32) -> KeySlot {
+    KeySlot { key: Cow::Borrowed(key), width, continuation: true, label: None }
+}
+
+/// Empty space between key groups
+const fn gap(width: f32) -> KeySlot {
+    slot("", width)
+}
+
+/// Esc and function keys above the main block, grouped in fours
+const FUNCTION_ROW: &[KeySlot] = &[
+    slot("Esc", 1.0), gap(1.0),
+    slot("F1", 1.0), slot("F2", 1.0), slot("F3", 1.0), slot("F4", 1.0), gap(0.5),
+    slot("F5", 1.0), slot("F6", 1.0), slot("F7", 1.0), slot("F8", 1.0), gap(0.5),
+    slot("F9", 1.0), slot("F10", 1.0), slot("F11", 1.0), slot("F12", 1.0),
+];
+
+/// Navigation and arrow cluster between the main block and the numpad,
+/// aligned with the function row at the top
+const NAV_ROWS: &[&[KeySlot]] = &[
+    &[slot("PrintScreen", 1.0), slot("ScrollLock", 1.0), slot("PauseBreak", 1.0)],
+    &[slot("Insert", 1.0), slot("Home", 1.0), slot("PageUp", 1.0)],
+    &[slot("Delete", 1.0), slot("End", 1.0), slot("PageDown", 1.0)],
+    &[gap(3.0)],
+    &[gap(1.0), slot("↑", 1.0), gap(1.0)],
+    &[slot("←", 1.0), slot("↓", 1.0), slot("→", 1.0)],
+];
+
+/// ISO layout: tall two-row Enter and the extra `<>` key left of Z
+const ISO_ROWS: &[&[KeySlot]] = &[
+    &[
+        slot("`", 1.0), slot("1", 1.0), slot("2", 1.0), slot("3", 1.0), slot("4", 1.0),
+        slot("5", 1.0), slot("6", 1.0), slot("7", 1.0), slot("8", 1.0), slot("9", 1.0),
+        slot("0", 1.0), slot("-", 1.0), slot("=", 1.0), slot("Backspace", 2.0),
+    ],
+    &[
+        slot("Tab", 1.5), slot("Q", 1.0), slot("W", 1.0), slot("E", 1.0), slot("R", 1.0),
+        slot("T", 1.0), slot("Y", 1.0), slot("U", 1.0), slot("I", 1.0), slot("O", 1.0),
+        slot("P", 1.0), slot("[", 1.0), slot("]", 1.0), slot("Enter", 1.5),
+    ],
+    &[
+        slot("CapsLock", 1.75), slot("A", 1.0), slot("S", 1.0), slot("D", 1.0), slot("F", 1.0),
+        slot("G", 1.0), slot("H", 1.0), slot("J", 1.0), slot("K", 1.0), slot("L", 1.0),
+        slot(";", 1.0), slot("'", 1.0), slot("\\", 1.0), continuation("Enter", 1.25),
+    ],
+    &[
+        slot("Shift", 1.25), slot("<>", 1.0), slot("Z", 1.0), slot("X", 1.0), slot("C", 1.0),
+        slot("V", 1.0), slot("B", 1.0), slot("N", 1.0), slot("M", 1.0), slot(",", 1.0),
+        slot(".", 1.0), slot("/", 1.0), slot("Shift", 2.75),
+    ],
+    &[
+        slot("Ctrl", 1.25), slot("Meta", 1.25), slot("Alt", 1.25), slot("Space", 6.25),
+        slot("AltGr", 1.25), slot("Meta", 1.25), slot("Ctrl", 1.25),
+    ],
+];
+
+/// Numeric keypad block with tall `+` and Enter keys, level with the
+/// number row when drawn next to the function row
+const NUMPAD_ROWS: &[&[KeySlot]] = &[
+    &[gap(4.0)],
+    &[slot("NumLock", 1.0), slot("KP/", 1.0), slot("KP*", 1.0), slot("KP-", 1.0)],
+    &[slot("KP7", 1.0), slot("KP8", 1.0), slot("KP9", 1.0), slot("KP+", 1.0)],
+    &[slot("KP4", 1.0), slot("KP5", 1.0), slot("KP6", 1.0), continuation("KP+", 1.0)],
+    &[slot("KP1", 1.0), slot("KP2", 1.0), slot("KP3", 1.0), slot("KPEnter", 1.0)],
+    &[slot("KP0", 2.0), slot("KP.", 1.0), continuation("KPEnter", 1.0)],
+];
+
+impl KeySlot {
+    pub fn is_gap(&self) -> bool {
+        self.key.is_empty()
+    }
+}
+
+/// Physical keyboard layout drawn by the heatmap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout {
+    #[default]
+    Ansi,
+    Iso,
+    /// User-defined board read from `layout.json`, see `load_custom_layout`
+    Custom,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 3] = [KeyboardLayout::Ansi, KeyboardLayout::Iso, KeyboardLayout::Custom];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Ansi => "ANSI",
+            KeyboardLayout::Iso => "ISO",
+            KeyboardLayout::Custom => "Custom",
+        }
+    }
+    
+    /// Name used in the config file, on the command line and in the stats file
+    pub fn id(&self) -> &'static str {
+        match self {
+            KeyboardLayout::Ansi => "ansi",
+            KeyboardLayout::Iso => "iso",
+            KeyboardLayout::Custom => "custom",
+        }
+    }
+    
+    /// Rows of key slots of the main alphanumeric block, top to bottom.
+    /// The custom layout has no built-in rows and falls back to ANSI.
+    pub fn rows(&self) -> Vec<Vec<KeySlot>> {
+        match self {
+            KeyboardLayout::Ansi | KeyboardLayout::Custom => KEYBOARD_ROWS
+                .iter()
+                .map(|row| row.iter().map(|key| slot(key, get_key_width(key))).collect())
+                .collect(),
+            KeyboardLayout::Iso => ISO_ROWS.iter().map(|row| row.to_vec()).collect(),
+        }
+    }
+}
+
+impl FromStr for KeyboardLayout {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        KeyboardLayout::ALL
+            .into_iter()
+            .find(|layout| layout.id() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown keyboard layout: {}", s))
+    }
+}
+
+/// Location of the custom layout, next to `config.toml`
+pub fn custom_layout_path() -> PathBuf {
+    Config::path().with_file_name("layout.json")
+}
+
+/// Load the custom layout if there is one. An invalid file is logged and
+/// ignored, so the heatmap falls back to the built-in ANSI layout.
+pub fn load_custom_layout() -> Option<Vec<Vec<KeySlot>>> {
+    let path = custom_layout_path();
+    let content = fs::read_to_string(&path).ok()?;
+    match parse_custom_layout(&content) {
+        Ok(rows) => {
+            log::info!("Loaded keyboard layout from {}", path.display());
+            Some(rows)
+        }
+        Err(e) => {
+            log::warn!("Ignoring invalid keyboard layout {}: {}, using ANSI", path.display(), e);
+            None
+        }
+    }
+}
+
+/// One entry of a custom layout row: a key or a `gap`. Unknown fields are ignored.
+#[derive(Debug, Deserialize)]
+struct CustomEntry {
+    key: Option<String>,
+    label: Option<String>,
+    width: Option<f32>,
+    gap: Option<f32>,
+}
+
+/// Parse a custom layout: an array of rows, each an array of
+/// `{ "key": "A", "label": "A", "width": 1.0 }` objects or `{ "gap": 0.5 }`
+/// spacers. `width` defaults to 1 and `label` to the key name.
+fn parse_custom_layout(json: &str) -> Result<Vec<Vec<KeySlot>>, String> {
+    let rows: Vec<Vec<CustomEntry>> = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if rows.iter().all(|row| row.is_empty()) {
+        return Err("no keys".to_string());
+    }
+    
+    rows.into_iter().enumerate().map(|(r, row)| {
+        row.into_iter().enumerate().map(|(i, entry)| {
+            let width = entry.gap.or(entry.width).unwrap_or(1.0);
+            if !(width.is_finite() && width > 0.0) {
+                return Err(format!("row {}, entry {}: invalid width {}", r + 1, i + 1, width));
+            }
+            if entry.gap.is_some() {
+                return Ok(gap(width));
+            }
+            let key = entry.key
+                .filter(|key| !key.is_empty())
+                .ok_or_else(|| format!("row {}, entry {}: missing key", r + 1, i + 1))?;
+            Ok(KeySlot {
+                key: Cow::Owned(key),
+                width,
+                continuation: false,
+                label: entry.label.map(Cow::Owned),
+            })
+        }).collect()
+    }).collect()
+}
+
+/// Form factor: which key blocks are drawn around the main block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum KeyboardSize {
+    /// Main block only
+    #[serde(rename = "60")]
+    Compact,
+    /// Function row plus navigation and arrow cluster
+    #[default]
+    #[serde(rename = "tkl")]
+    Tkl,
+    /// Tenkeyless plus the numeric keypad
+    #[serde(rename = "full")]
+    Full,
+}
+
+impl KeyboardSize {
+    pub const ALL: [KeyboardSize; 3] = [KeyboardSize::Compact, KeyboardSize::Tkl, KeyboardSize::Full];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyboardSize::Compact => "60%",
+            KeyboardSize::Tkl => "TKL",
+            KeyboardSize::Full => "Full",
+        }
+    }
+    
+    /// Name used in the config file, on the command line and in the stats file
+    pub fn id(&self) -> &'static str {
+        match self {
+            KeyboardSize::Compact => "60",
+            KeyboardSize::Tkl => "tkl",
+            KeyboardSize::Full => "full",
+        }
+    }
+    
+    /// Esc and F-key row above the main block
+    pub fn function_row(&self) -> Option<Vec<KeySlot>> {
+        (*self != KeyboardSize::Compact).then(|| FUNCTION_ROW.to_vec())
+    }
+    
+    /// Navigation and arrow cluster right of the main block
+    pub fn nav_rows(&self) -> Option<Vec<Vec<KeySlot>>> {
+        (*self != KeyboardSize::Compact).then(|| NAV_ROWS.iter().map(|row| row.to_vec()).collect())
+    }
+    
+    /// Numeric keypad at the right edge
+    pub fn numpad_rows(&self) -> Option<Vec<Vec<KeySlot>>> {
+        (*self == KeyboardSize::Full).then(|| NUMPAD_ROWS.iter().map(|row| row.to_vec()).collect())
+    }
+}
+
+impl FromStr for KeyboardSize {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().trim_end_matches('%') {
+            "60" => Ok(KeyboardSize::Compact),
+            "tkl" => Ok(KeyboardSize::Tkl),
+            "full" => Ok(KeyboardSize::Full),
+            other => Err(format!("unknown keyboard size: {}", other)),
+        }
+    }
+}
+
+/// Smallest and largest factor by which the heatmap keys are scaled
+pub const KEY_SCALE_RANGE: (f32, f32) = (0.5, 1.5);
+
+/// Size of the heatmap keys: a fixed factor of the default size, or fitted
+/// to the width available to the heatmap
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "KeyScaleValue", into = "KeyScaleValue")]
+pub enum KeyScale {
+    Auto,
+    Factor(f32),
+}
+
+impl Default for KeyScale {
+    fn default() -> Self {
+        KeyScale::Factor(1.0)
+    }
+}
+
+/// `key_scale` as written in the config file: `"auto"` or a number
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum KeyScaleValue {
+    Factor(f32),
+    Name(String),
+}
+
+impl From<KeyScale> for KeyScaleValue {
+    fn from(scale: KeyScale) -> Self {
+        match scale {
+            KeyScale::Auto => KeyScaleValue::Name("auto".to_string()),
+            KeyScale::Factor(factor) => KeyScaleValue::Factor(factor),
+        }
+    }
+}
+
+impl TryFrom<KeyScaleValue> for KeyScale {
+    type Error = String;
+    
+    fn try_from(value: KeyScaleValue) -> Result<Self, Self::Error> {
+        let (min, max) = KEY_SCALE_RANGE;
+        match value {
+            KeyScaleValue::Name(name) if name.eq_ignore_ascii_case("auto") => Ok(KeyScale::Auto),
+            KeyScaleValue::Name(name) => Err(format!("unknown key scale: {}", name)),
+            KeyScaleValue::Factor(factor) if (min..=max).contains(&factor) => Ok(KeyScale::Factor(factor)),
+            KeyScaleValue::Factor(factor) => Err(format!("key scale {} is outside {}-{}", factor, min, max)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn width(row: &[KeySlot]) -> f32 {
+        row.iter().map(|slot| slot.width).sum()
+    }
+
+    #[test]
+    fn rows_line_up() {
+        for layout in KeyboardLayout::ALL {
+            assert_eq!(width(&layout.rows()[0]), width(FUNCTION_ROW), "{}", layout.label());
+        }
+        assert!(NAV_ROWS.iter().all(|row| width(row) == 3.0));
+        assert!(NUMPAD_ROWS.iter().all(|row| width(row) == 4.0));
+    }
+
+    #[test]
+    fn parses_custom_layout() {
+        let rows = parse_custom_layout(r#"[
+            [{ "key": "Q" }, { "key": "W", "width": 1.5, "color": "red" }, { "gap": 2 }, { "key": "Space", "label": "␣" }],
+            []
+        ]"#).unwrap();
+        assert_eq!(rows.len(), 2);
+        let keys: Vec<&str> = rows[0].iter().map(|slot| slot.key.as_ref()).collect();
+        assert_eq!(keys, vec!["Q", "W", "", "Space"]);
+        assert_eq!(rows[0][0].width, 1.0);
+        assert_eq!(rows[0][1].width, 1.5);
+        assert!(rows[0][2].is_gap() && rows[0][2].width == 2.0);
+        assert_eq!(rows[0][3].label.as_deref(), Some("␣"));
+    }
+
+    #[test]
+    fn rejects_malformed_custom_layout() {
+        assert!(parse_custom_layout(r#"[[{ "key": "A" }"#).is_err());
+        assert!(parse_custom_layout(r#"{ "rows": [] }"#).is_err());
+        assert!(parse_custom_layout("[]").is_err());
+        assert_eq!(parse_custom_layout(r#"[[{ "label": "A" }]]"#).unwrap_err(), "row 1, entry 1: missing key");
+        assert_eq!(parse_custom_layout(r#"[[{ "key": "A" }, { "key": "B", "width": 0 }]]"#).unwrap_err(), "row 1, entry 2: invalid width 0");
+    }
+
+    #[test]
+    fn example_layout_is_valid() {
+        let rows = parse_custom_layout(include_str!("../../examples/layout.json")).unwrap();
+        assert!(rows.iter().flatten().any(|slot| slot.key == "Space"));
+    }
+
+    #[test]
+    fn character_keys_are_sorted_into_rows() {
+        assert_eq!(KeyRow::of("1"), Some(KeyRow::Number));
+        assert_eq!(KeyRow::of("\\"), Some(KeyRow::Top));
+        assert_eq!(KeyRow::of(";"), Some(KeyRow::Home));
+        assert_eq!(KeyRow::of("/"), Some(KeyRow::Bottom));
+        for key in ["Space", "Shift", "Enter", "Tab", "CapsLock", "F1", "KP1"] {
+            assert_eq!(KeyRow::of(key), None, "{}", key);
+        }
+
+        let counts: HashMap<String, u64> = [("A", 5), ("J", 3), ("Q", 2), ("Z", 1), ("Space", 9)]
+            .map(|(key, count)| (key.to_string(), count))
+            .into();
+        assert_eq!(row_totals(&counts), [(KeyRow::Number, 0), (KeyRow::Top, 2), (KeyRow::Home, 8), (KeyRow::Bottom, 1)]);
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("ISO".parse(), Ok(KeyboardLayout::Iso));
+        assert_eq!("60%".parse(), Ok(KeyboardSize::Compact));
+        for size in KeyboardSize::ALL {
+            assert_eq!(size.id().parse(), Ok(size));
+        }
+        assert!("dvorak".parse::<KeyboardLayout>().is_err());
+    }
+}
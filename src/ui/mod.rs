@@ -1,4 +1,12 @@
 pub mod app;
 pub mod dashboard;
 pub mod keyboard_heatmap;
+pub mod keyboard_layout;
+pub mod mouse_heatmap;
+pub mod scrollbar;
 pub mod charts;
+pub mod heat_scale;
+pub mod key_labels;
+pub mod prefs;
+pub mod settings;
+pub mod theme;
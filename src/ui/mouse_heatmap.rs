@@ -0,0 +1,92 @@
+use gpui::*;
+
+use crate::stats::mouse_grid::{GRID_COLUMNS, GRID_ROWS};
+use super::heat_scale::{HeatColorScheme, HeatScale};
+use super::theme::Theme;
+
+/// Edge length of one grid cell in pixels
+const CELL_SIZE: f32 = 8.0;
+
+/// Which screen grid the mouse heatmap shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorGrid {
+    /// Where the cursor rests
+    #[default]
+    Movement,
+    /// Where clicks land
+    Clicks,
+}
+
+impl CursorGrid {
+    pub const ALL: [CursorGrid; 2] = [CursorGrid::Movement, CursorGrid::Clicks];
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            CursorGrid::Movement => "Movement",
+            CursorGrid::Clicks => "Clicks",
+        }
+    }
+}
+
+/// Where on the screen the cursor rests or clicks, one colored cell per grid cell
+pub struct MouseHeatmap {
+    grid: Vec<u64>,
+    /// Counts of all visited cells, ascending, for the heat scale
+    sorted_counts: Vec<u64>,
+    scheme: HeatColorScheme,
+    scale: HeatScale,
+    theme: Theme,
+}
+
+impl MouseHeatmap {
+    pub fn new(grid: Vec<u64>, scheme: HeatColorScheme, scale: HeatScale) -> Self {
+        let mut sorted_counts: Vec<u64> = grid.iter().copied().filter(|c| *c > 0).collect();
+        sorted_counts.sort_unstable();
+        Self {
+            grid,
+            sorted_counts,
+            scheme,
+            scale,
+            theme: Theme::default(),
+        }
+    }
+    
+    /// Set the colors of the background and unvisited cells
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+    
+    fn cell_color(&self, count: u64) -> Rgba {
+        let intensity = self.scale.intensity(count, &self.sorted_counts);
+        if intensity < 0.01 {
+            rgb(self.theme.inset)
+        } else {
+            self.scheme.face_color(intensity)
+        }
+    }
+}
+
+impl IntoElement for MouseHeatmap {
+    type Element = Div;
+    
+    fn into_element(self) -> Self::Element {
+        div()
+            .p_1()
+            .bg(rgb(self.theme.inset))
+            .rounded_md()
+            .border_1()
+            .border_color(rgb(self.theme.inset_border))
+            .flex()
+            .flex_col()
+            .children((0..GRID_ROWS).map(|row| {
+                div().flex().children((0..GRID_COLUMNS).map(|column| {
+                    let count = self.grid.get(row * GRID_COLUMNS + column).copied().unwrap_or(0);
+                    div()
+                        .w(px(CELL_SIZE))
+                        .h(px(CELL_SIZE))
+                        .bg(self.cell_color(count))
+                }))
+            }))
+    }
+}
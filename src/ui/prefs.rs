@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Choices made in the dashboard and remembered between runs, kept in
+/// `prefs.toml` next to the config file rather than with the stats
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Prefs {
+    /// Keyboard layout, size and color scale last chosen for the heatmap, by name
+    pub heatmap_layout: Option<String>,
+    pub heatmap_size: Option<String>,
+    pub heatmap_scale: Option<String>,
+    
+    /// Number of keys last chosen for the Top Keys list
+    pub top_keys_count: Option<usize>,
+    
+    /// Dashboard theme last chosen, by name
+    pub theme: Option<String>,
+}
+
+impl Prefs {
+    pub fn path() -> PathBuf {
+        Config::path().with_file_name("prefs.toml")
+    }
+    
+    /// Load the remembered choices; a missing or invalid file remembers nothing
+    pub fn load() -> Self {
+        Self::load_from(&Self::path())
+    }
+    
+    fn load_from(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        toml::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid preferences {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+    
+    /// Write the choices to `prefs.toml`, logging a failure
+    pub fn save(&self) {
+        let path = Self::path();
+        if let Err(e) = self.save_to(&path) {
+            log::warn!("Failed to save preferences to {}: {}", path.display(), e);
+        }
+    }
+    
+    fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_choices_load_back() {
+        let dir = std::env::temp_dir().join(format!("finger-prefs-{}", std::process::id()));
+        let path = dir.join("prefs.toml");
+        assert_eq!(Prefs::load_from(&path), Prefs::default());
+
+        let prefs = Prefs { heatmap_layout: Some("iso".to_string()), top_keys_count: Some(20), ..Prefs::default() };
+        prefs.save_to(&path).unwrap();
+        assert_eq!(Prefs::load_from(&path), prefs);
+
+        fs::write(&path, "top_keys_count = \"many\"").unwrap();
+        assert_eq!(Prefs::load_from(&path), Prefs::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+/// Shortest scrollbar thumb in pixels, so it stays easy to grab on long content
+const MIN_THUMB_HEIGHT: f32 = 24.0;
+
+/// Vertical scroll state of a scroll container, in pixels, for drawing and
+/// dragging its scrollbar. The track is as tall as the container.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollMetrics {
+    /// Visible height of the container, also the length of the track
+    pub viewport: f32,
+    /// How far the content can scroll: its height minus the viewport
+    pub max_offset: f32,
+    /// How far the content is scrolled down, from 0 to `max_offset`
+    pub offset: f32,
+}
+
+impl ScrollMetrics {
+    /// Whether all of the content is visible, so no scrollbar is needed
+    pub fn fits(&self) -> bool {
+        self.viewport <= 0.0 || self.max_offset < 1.0
+    }
+    
+    /// Top and height of the thumb, proportional to the part of the content
+    /// in view
+    pub fn thumb(&self) -> (f32, f32) {
+        if self.fits() {
+            return (0.0, self.viewport.max(0.0));
+        }
+        let content = self.viewport + self.max_offset;
+        let height = (self.viewport * self.viewport / content).max(MIN_THUMB_HEIGHT).min(self.viewport);
+        let top = (self.viewport - height) * (self.offset / self.max_offset).clamp(0.0, 1.0);
+        (top, height)
+    }
+    
+    /// Offset after dragging the thumb by `delta` pixels from where it was
+    /// when the content was scrolled to `start_offset`
+    pub fn dragged(&self, start_offset: f32, delta: f32) -> f32 {
+        let (_, height) = self.thumb();
+        let travel = self.viewport - height;
+        if travel <= 0.0 {
+            return self.offset;
+        }
+        (start_offset + delta * self.max_offset / travel).clamp(0.0, self.max_offset.max(0.0))
+    }
+    
+    /// Offset after a click on the track `y` pixels from its top: a page
+    /// towards the click, or unchanged on the thumb
+    pub fn paged(&self, y: f32) -> f32 {
+        let (top, height) = self.thumb();
+        let offset = if y < top {
+            self.offset - self.viewport
+        } else if y > top + height {
+            self.offset + self.viewport
+        } else {
+            self.offset
+        };
+        offset.clamp(0.0, self.max_offset.max(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 400 px of a 1600 px page
+    fn metrics(offset: f32) -> ScrollMetrics {
+        ScrollMetrics { viewport: 400.0, max_offset: 1200.0, offset }
+    }
+
+    #[test]
+    fn thumb_follows_the_offset() {
+        assert_eq!(metrics(0.0).thumb(), (0.0, 100.0));
+        assert_eq!(metrics(600.0).thumb(), (150.0, 100.0));
+        assert_eq!(metrics(1200.0).thumb(), (300.0, 100.0));
+        // Very long content keeps a grabbable thumb
+        let long = ScrollMetrics { viewport: 400.0, max_offset: 100_000.0, offset: 100_000.0 };
+        assert_eq!(long.thumb(), (400.0 - MIN_THUMB_HEIGHT, MIN_THUMB_HEIGHT));
+    }
+
+    #[test]
+    fn fitting_content_needs_no_scrollbar() {
+        assert!(ScrollMetrics { viewport: 400.0, max_offset: 0.0, offset: 0.0 }.fits());
+        // Not laid out yet
+        assert!(ScrollMetrics { viewport: 0.0, max_offset: 0.0, offset: 0.0 }.fits());
+        assert!(!metrics(0.0).fits());
+    }
+
+    #[test]
+    fn dragging_moves_the_content_in_proportion() {
+        // The thumb travels 300 px for 1200 px of content
+        assert_eq!(metrics(0.0).dragged(0.0, 150.0), 600.0);
+        assert_eq!(metrics(600.0).dragged(600.0, -75.0), 300.0);
+        assert_eq!(metrics(0.0).dragged(0.0, 1000.0), 1200.0);
+        assert_eq!(metrics(0.0).dragged(0.0, -10.0), 0.0);
+    }
+
+    #[test]
+    fn clicking_the_track_pages() {
+        assert_eq!(metrics(600.0).paged(50.0), 200.0);
+        assert_eq!(metrics(600.0).paged(350.0), 1000.0);
+        assert_eq!(metrics(600.0).paged(200.0), 600.0);
+        assert_eq!(metrics(1000.0).paged(10.0), 600.0);
+        assert_eq!(metrics(1000.0).paged(399.0), 1200.0);
+    }
+}
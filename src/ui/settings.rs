@@ -0,0 +1,220 @@
+use gpui::*;
+use gpui::prelude::FluentBuilder;
+use std::time::Duration;
+use crate::config::Config;
+use crate::stats::StatsManager;
+use super::keyboard_layout::KeyScale;
+use super::theme::Theme;
+
+/// Choices offered for each stepped setting, in increasing order
+const SAVE_INTERVALS_SECS: &[u64] = &[15, 30, 60, 120, 300, 600, 1800];
+const DEDUP_WINDOWS_MS: &[u64] = &[0, 10, 20, 50, 100, 200];
+const REFRESH_RATES_MS: &[u64] = &[16, 33, 50, 100, 250, 500, 1000];
+const KEY_SCALES: &[f32] = &[0.5, 0.75, 0.9, 1.0, 1.1, 1.25, 1.5];
+
+/// Next larger or smaller choice than `current`, or `current` at either end
+pub(super) fn step<T: PartialOrd + Copy>(choices: &[T], current: T, up: bool) -> T {
+    let next = if up {
+        choices.iter().find(|choice| **choice > current)
+    } else {
+        choices.iter().rev().find(|choice| **choice < current)
+    };
+    next.copied().unwrap_or(current)
+}
+
+/// Settings panel opened with the gear button in the menu bar. Every change
+/// is applied right away and written to `config.toml`.
+pub struct SettingsView {
+    /// Settings in effect, including those given on the command line
+    config: Config,
+    /// Settings in the config file, which changes are saved on top of;
+    /// `None` if the file could not be read, so it is not overwritten
+    file: Option<Config>,
+    stats_manager: StatsManager,
+    theme: Theme,
+    /// Outcome of the last write of the config file
+    status: Option<Result<String, String>>,
+}
+
+impl SettingsView {
+    pub fn new(config: Config, file: Option<Config>, stats_manager: StatsManager, theme: Theme) -> Self {
+        Self { config, file, stats_manager, theme, status: None }
+    }
+    
+    /// Settings as last changed, for the parts of the dashboard that read them
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+    
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+    
+    /// Change the config, apply it to the stats manager and save the change
+    fn change(&mut self, cx: &mut Context<Self>, change: impl FnOnce(&mut Config)) {
+        let before = self.config.clone();
+        change(&mut self.config);
+        self.stats_manager.set_save_interval(Duration::from_secs(self.config.save_interval_secs));
+        self.stats_manager.set_dedup_window(Duration::from_millis(self.config.dedup_window_ms));
+        self.status = Some(self.save(&before));
+        cx.notify();
+    }
+    
+    /// Write the settings changed since `before` to the config file, keeping
+    /// everything else as the file has it
+    fn save(&mut self, before: &Config) -> Result<String, String> {
+        let path = Config::path();
+        let Some(file) = &self.file else {
+            return Err(format!("Not saved: {} could not be read, fix it and restart first", path.display()));
+        };
+        let file = file.with_changes(before, &self.config)
+            .map_err(|e| format!("Could not save {}: {}", path.display(), e))?;
+        file.save().map_err(|e| format!("Could not save {}: {}", path.display(), e))?;
+        self.file = Some(file);
+        Ok(format!("Saved to {}", path.display()))
+    }
+    
+    fn render_button(&self, id: impl Into<ElementId>, label: &str, active: bool) -> Stateful<Div> {
+        div()
+            .id(id.into())
+            .px_2()
+            .py_px()
+            .rounded_md()
+            .text_xs()
+            .bg(rgb(if active { self.theme.blue } else { self.theme.raised }))
+            .text_color(rgb(if active { self.theme.surface } else { self.theme.text_dim }))
+            .cursor_pointer()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .hover(|s| s.border_color(rgb(self.theme.blue)))
+            .child(label.to_string())
+    }
+    
+    /// A setting with its description on the left and `controls` on the right
+    fn render_row(&self, label: &str, description: &str, controls: impl IntoElement) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .justify_between()
+            .gap_4()
+            .py_2()
+            .border_t_1()
+            .border_color(rgb(self.theme.border))
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .child(div().text_sm().child(label.to_string()))
+                    .child(div().text_xs().text_color(rgb(self.theme.text_muted)).child(description.to_string()))
+            )
+            .child(controls)
+    }
+    
+    /// − value + controls; `change` is called with whether to step up
+    fn render_stepper(&self, id: &'static str, value: String, change: fn(&mut Config, bool), cx: &mut Context<Self>) -> Div {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(
+                self.render_button((id, 0usize), "−", false)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| this.change(cx, |config| change(config, false))))
+            )
+            .child(div().w_20().text_center().text_sm().font_weight(FontWeight::MEDIUM).child(value))
+            .child(
+                self.render_button((id, 1usize), "+", false)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| this.change(cx, |config| change(config, true))))
+            )
+    }
+}
+
+impl Render for SettingsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let config = &self.config;
+        let save_interval = match config.save_interval_secs {
+            secs if secs >= 60 && secs.is_multiple_of(60) => format!("{} min", secs / 60),
+            secs => format!("{} s", secs),
+        };
+        let dedup_window = match config.dedup_window_ms {
+            0 => "Off".to_string(),
+            ms => format!("{} ms", ms),
+        };
+        let refresh = format!("{} ms", config.refresh_ms);
+        let key_scale = match config.key_scale {
+            KeyScale::Auto => "Fit".to_string(),
+            KeyScale::Factor(factor) => format!("{:.0}%", factor * 100.0),
+        };
+        let fitted = config.key_scale == KeyScale::Auto;
+        let privacy_mode = self.stats_manager.is_privacy_mode();
+        
+        div()
+            .bg(rgb(self.theme.surface))
+            .rounded_xl()
+            .p_4()
+            .border_1()
+            .border_color(rgb(self.theme.border))
+            .flex()
+            .flex_col()
+            .child(div().text_base().font_weight(FontWeight::SEMIBOLD).mb_2().child("⚙ Settings"))
+            .child(self.render_row(
+                "Autosave interval",
+                "Time between periodic saves; stats are also saved when activity stops",
+                self.render_stepper("set-save-interval", save_interval, |config, up| {
+                    config.save_interval_secs = step(SAVE_INTERVALS_SECS, config.save_interval_secs, up);
+                }, cx),
+            ))
+            .child(self.render_row(
+                "Duplicate window",
+                "Identical key presses or clicks closer together count once",
+                self.render_stepper("set-dedup-window", dedup_window, |config, up| {
+                    config.dedup_window_ms = step(DEDUP_WINDOWS_MS, config.dedup_window_ms, up);
+                }, cx),
+            ))
+            .child(self.render_row(
+                "Refresh rate",
+                "Time between dashboard updates while input arrives; slower when idle or in the background",
+                self.render_stepper("set-refresh", refresh, |config, up| {
+                    config.refresh_ms = step(REFRESH_RATES_MS, config.refresh_ms, up);
+                }, cx),
+            ))
+            .child(self.render_row(
+                "Heatmap key size",
+                "Size of the heatmap keys, or fitted to the window width",
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        self.render_button("set-key-scale-fit", "Fit", fitted)
+                            .on_click(cx.listener(move |this, _ev, _window, cx| {
+                                this.change(cx, |config| {
+                                    config.key_scale = if fitted { KeyScale::default() } else { KeyScale::Auto };
+                                });
+                            }))
+                    )
+                    .child(self.render_stepper("set-key-scale", key_scale, |config, up| {
+                        let factor = match config.key_scale {
+                            KeyScale::Auto => 1.0,
+                            KeyScale::Factor(factor) => factor,
+                        };
+                        config.key_scale = KeyScale::Factor(step(KEY_SCALES, factor, up));
+                    }, cx)),
+            ))
+            .child(self.render_row(
+                "Privacy mode",
+                "Count key presses by category only, without key identities",
+                self.render_button("set-privacy", if privacy_mode { "On" } else { "Off" }, privacy_mode)
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.stats_manager.set_privacy_mode(!privacy_mode);
+                        cx.notify();
+                    })),
+            ))
+            .when_some(self.status.clone(), |this, status| {
+                let (color, text) = match status {
+                    Ok(text) => (self.theme.text_faint, text),
+                    Err(text) => (self.theme.red, format!("⚠ {}", text)),
+                };
+                this.child(div().pt_2().text_xs().text_color(rgb(color)).child(text))
+            })
+    }
+}
@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Light or dark colors for the whole window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 2] = [ThemeMode::Dark, ThemeMode::Light];
+    
+    /// Name used in the config and stats files
+    pub fn id(&self) -> &'static str {
+        match self {
+            ThemeMode::Dark => "dark",
+            ThemeMode::Light => "light",
+        }
+    }
+    
+    /// The mode after this one, for a cycling toggle
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+    
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeMode::Dark => Theme::dark(),
+            ThemeMode::Light => Theme::light(),
+        }
+    }
+}
+
+impl FromStr for ThemeMode {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ThemeMode::ALL
+            .into_iter()
+            .find(|mode| mode.id() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown theme: {}", s))
+    }
+}
+
+/// `#rrggbb` color in the config file, as a 0xRRGGBB value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexColor(pub u32);
+
+impl FromStr for HexColor {
+    type Err = String;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .map(HexColor)
+            .ok_or_else(|| format!("invalid color {:?}, expected #rrggbb", s))
+    }
+}
+
+impl TryFrom<String> for HexColor {
+    type Error = String;
+    
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<HexColor> for String {
+    fn from(color: HexColor) -> Self {
+        format!("#{:06x}", color.0)
+    }
+}
+
+/// Accent colors replacing those of both built-in themes, from the
+/// `[accent_colors]` table of the config file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccentColors {
+    pub blue: Option<HexColor>,
+    pub purple: Option<HexColor>,
+    pub green: Option<HexColor>,
+    pub yellow: Option<HexColor>,
+    pub red: Option<HexColor>,
+    pub orange: Option<HexColor>,
+    pub cyan: Option<HexColor>,
+    pub teal: Option<HexColor>,
+}
+
+/// Named colors of the user interface, as 0xRRGGBB values for `gpui::rgb`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Window background
+    pub background: u32,
+    /// Menu bar, status bar and the compact view
+    pub bar: u32,
+    /// Cards and panels
+    pub surface: u32,
+    pub surface_hover: u32,
+    /// Buttons, tooltips and selected toggles
+    pub raised: u32,
+    pub raised_hover: u32,
+    /// Recessed areas such as the keyboard plate and the cursor grid
+    pub inset: u32,
+    pub inset_border: u32,
+    /// Case around the heatmap keyboard
+    pub case: u32,
+    /// Borders of cards and controls, stronger ones (also hovered buttons),
+    /// and those of hovered controls
+    pub border: u32,
+    pub border_strong: u32,
+    pub border_hover: u32,
+    /// Text from most to least prominent; `text_faint` also marks empty bars
+    pub text: u32,
+    pub text_bright: u32,
+    pub text_dim: u32,
+    pub text_muted: u32,
+    pub text_faint: u32,
+    /// Top edge and face of unused heatmap keys, and the shadow under all keys
+    pub key_top: u32,
+    pub key_face: u32,
+    pub key_shadow: u32,
+    /// Accent colors
+    pub blue: u32,
+    pub purple: u32,
+    pub green: u32,
+    pub yellow: u32,
+    pub red: u32,
+    pub orange: u32,
+    pub cyan: u32,
+    pub teal: u32,
+    /// Calendar squares from no activity to the busiest days
+    pub calendar_levels: [u32; 5],
+    /// Error banner, and the hover colors of the close button
+    pub error_surface: u32,
+    pub error_hover: u32,
+    pub error_border: u32,
+    pub error_text: u32,
+}
+
+impl Theme {
+    /// Tokyo Night inspired dark colors, the original look
+    pub fn dark() -> Self {
+        Self {
+            background: 0x0f0f14,
+            bar: 0x16161e,
+            surface: 0x1a1b26,
+            surface_hover: 0x1f2030,
+            raised: 0x24283b,
+            raised_hover: 0x292e42,
+            inset: 0x12121a,
+            inset_border: 0x252530,
+            case: 0x1a1a24,
+            border: 0x2a2a3a,
+            border_strong: 0x3a3a4a,
+            border_hover: 0x4a4a5a,
+            text: 0xe0e0e0,
+            text_bright: 0xc0caf5,
+            text_dim: 0x888898,
+            text_muted: 0x565f89,
+            text_faint: 0x414868,
+            key_top: 0x3a3a4a,
+            key_face: 0x2a2a3a,
+            key_shadow: 0x0a0a10,
+            blue: 0x7aa2f7,
+            purple: 0xbb9af7,
+            green: 0x9ece6a,
+            yellow: 0xe0af68,
+            red: 0xf7768e,
+            orange: 0xff9e64,
+            cyan: 0x7dcfff,
+            teal: 0x73daca,
+            calendar_levels: [0x1f2335, 0x2e4a6b, 0x3d6fa5, 0x5a8fd8, 0x7aa2f7],
+            error_surface: 0x2d1f2a,
+            error_hover: 0x5a2a2a,
+            error_border: 0x7a3a3a,
+            error_text: 0xff6666,
+        }
+    }
+    
+    /// Light colors for bright environments, with accents dark enough to
+    /// read on a light background
+    pub fn light() -> Self {
+        Self {
+            background: 0xe1e2e7,
+            bar: 0xd5d6db,
+            surface: 0xf2f3f6,
+            surface_hover: 0xe9eaf0,
+            raised: 0xd0d5e3,
+            raised_hover: 0xc4c8da,
+            inset: 0xe6e7ed,
+            inset_border: 0xc8cbd6,
+            case: 0xcfd1da,
+            border: 0xc4c8da,
+            border_strong: 0xa8aecb,
+            border_hover: 0x8990b3,
+            text: 0x343b58,
+            text_bright: 0x1f2335,
+            text_dim: 0x5a607d,
+            text_muted: 0x6172b0,
+            text_faint: 0xa8aecb,
+            key_top: 0xf4f5f8,
+            key_face: 0xdfe1e8,
+            key_shadow: 0x9aa0b8,
+            blue: 0x2e7de9,
+            purple: 0x9854f1,
+            green: 0x587539,
+            yellow: 0x8c6c3e,
+            red: 0xf52a65,
+            orange: 0xb15c00,
+            cyan: 0x007197,
+            teal: 0x118c74,
+            calendar_levels: [0xd0d5e3, 0xa9c3ef, 0x7ea6ec, 0x5590ea, 0x2e7de9],
+            error_surface: 0xf6dde3,
+            error_hover: 0xf0c0cc,
+            error_border: 0xe08a9f,
+            error_text: 0xd0203f,
+        }
+    }
+}
+
+impl Theme {
+    /// This theme with the accents set in `accents` replaced
+    pub fn with_accents(mut self, accents: &AccentColors) -> Self {
+        let overrides = [
+            (&mut self.blue, accents.blue),
+            (&mut self.purple, accents.purple),
+            (&mut self.green, accents.green),
+            (&mut self.yellow, accents.yellow),
+            (&mut self.red, accents.red),
+            (&mut self.orange, accents.orange),
+            (&mut self.cyan, accents.cyan),
+            (&mut self.teal, accents.teal),
+        ];
+        for (color, custom) in overrides {
+            if let Some(HexColor(custom)) = custom {
+                *color = custom;
+            }
+        }
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_theme_modes() {
+        assert_eq!("Light".parse::<ThemeMode>(), Ok(ThemeMode::Light));
+        assert!("sepia".parse::<ThemeMode>().is_err());
+        for mode in ThemeMode::ALL {
+            assert_eq!(mode.id().parse::<ThemeMode>(), Ok(mode));
+            assert_ne!(mode.next(), mode);
+        }
+    }
+
+    #[test]
+    fn parses_hex_colors() {
+        assert_eq!("#ff9E64".parse::<HexColor>(), Ok(HexColor(0xff9e64)));
+        assert!("ff9e64".parse::<HexColor>().is_err());
+        assert!("#fff".parse::<HexColor>().is_err());
+        assert!("#gggggg".parse::<HexColor>().is_err());
+        assert!("#+fffff".parse::<HexColor>().is_err());
+        assert_eq!(String::from(HexColor(0x00aa01)), "#00aa01");
+    }
+
+    #[test]
+    fn accents_replace_only_the_colors_set() {
+        let accents = AccentColors { blue: Some(HexColor(0x123456)), ..AccentColors::default() };
+        let theme = Theme::light().with_accents(&accents);
+        assert_eq!(theme.blue, 0x123456);
+        assert_eq!(Theme { blue: Theme::light().blue, ..theme }, Theme::light());
+        assert_eq!(Theme::dark().with_accents(&AccentColors::default()), Theme::dark());
+    }
+
+    #[test]
+    fn themes_keep_text_readable() {
+        // Relative luminance difference between text and its background
+        fn luminance(color: u32) -> f32 {
+            let channel = |shift: u32| ((color >> shift) & 0xff) as f32 / 255.0;
+            0.299 * channel(16) + 0.587 * channel(8) + 0.114 * channel(0)
+        }
+        for theme in [Theme::dark(), Theme::light()] {
+            for text in [theme.text, theme.text_bright, theme.text_dim] {
+                assert!((luminance(text) - luminance(theme.surface)).abs() > 0.35, "{:06x} on {:06x}", text, theme.surface);
+            }
+        }
+    }
+}